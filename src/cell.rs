@@ -25,7 +25,9 @@ use serde::Serialize;
 use serde::Deserialize;
 
 // Standard library imports.
+use std::borrow::Cow;
 use std::cell::Cell as StdCell;
+use std::collections::BTreeSet;
 use std::collections::HashSet;
 
 // Exports.
@@ -42,10 +44,21 @@ pub use selector::*;
 #[derive(Debug, Clone)]
 #[cfg_attr(test, derive(PartialEq))]
 #[derive(Serialize, Deserialize)]
-#[serde(transparent)]
 pub struct Cell {
     /// The cell's expression.
     expr: Expr,
+    /// The cell's description.
+    #[serde(default, skip_serializing_if = "str::is_empty")]
+    description: Cow<'static, str>,
+    /// The cell's tags.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    tags: BTreeSet<Cow<'static, str>>,
+    /// Whether the cell is locked against modification.
+    #[serde(default, skip_serializing_if = "is_false")]
+    locked: bool,
+    /// A color which shadows the cell's expression.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    override_color: Option<Color>,
     #[serde(skip)]
     cached: StdCell<Option<Color>>,
 }
@@ -55,6 +68,10 @@ impl Cell {
     pub fn new() -> Self {
         Cell {
             expr: Default::default(),
+            description: Cow::Borrowed(""),
+            tags: BTreeSet::new(),
+            locked: false,
+            override_color: None,
             cached: StdCell::new(None),
         }
     }
@@ -63,10 +80,102 @@ impl Cell {
     pub fn new_with_expr(expr: Expr) -> Self {
         Cell {
             expr,
+            description: Cow::Borrowed(""),
+            tags: BTreeSet::new(),
+            locked: false,
+            override_color: None,
             cached: StdCell::new(None),
         }
     }
 
+    /// Returns the `Cell` with the given description.
+    pub fn with_description<T>(mut self, description: T) -> Self
+        where T: Into<Cow<'static, str>>
+    {
+        self.description = description.into();
+        self
+    }
+
+    /// Returns the cell's description.
+    pub fn description(&self) -> &Cow<'static, str> {
+        &self.description
+    }
+
+    /// Sets the cell's description, returning the previous description.
+    pub fn set_description<T>(&mut self, description: T) -> Cow<'static, str>
+        where T: Into<Cow<'static, str>>
+    {
+        std::mem::replace(&mut self.description, description.into())
+    }
+
+    /// Returns the `Cell` with the given tags.
+    pub fn with_tags<I, T>(mut self, tags: I) -> Self
+        where
+            I: IntoIterator<Item=T>,
+            T: Into<Cow<'static, str>>,
+    {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the cell's tags.
+    pub fn tags(&self) -> &BTreeSet<Cow<'static, str>> {
+        &self.tags
+    }
+
+    /// Returns true if the cell has the given tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    /// Adds a tag to the cell. Returns false if the cell already had the tag.
+    pub fn add_tag<T>(&mut self, tag: T) -> bool
+        where T: Into<Cow<'static, str>>
+    {
+        self.tags.insert(tag.into())
+    }
+
+    /// Removes a tag from the cell. Returns false if the cell did not have the
+    /// tag.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        self.tags.remove(tag)
+    }
+
+    /// Returns the `Cell` with the given lock state.
+    pub fn with_locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Returns true if the cell is locked.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Sets the cell's lock state, returning the previous lock state.
+    pub fn set_locked(&mut self, locked: bool) -> bool {
+        std::mem::replace(&mut self.locked, locked)
+    }
+
+    /// Returns the `Cell` with the given override color.
+    pub fn with_override_color(mut self, color: Option<Color>) -> Self {
+        self.override_color = color;
+        self
+    }
+
+    /// Returns the cell's override color. If set, this is returned as the
+    /// cell's color instead of the color of its expression.
+    pub fn override_color(&self) -> Option<&Color> {
+        self.override_color.as_ref()
+    }
+
+    /// Sets the cell's override color, returning the previous override color.
+    pub fn set_override_color(&mut self, color: Option<Color>)
+        -> Option<Color>
+    {
+        std::mem::replace(&mut self.override_color, color)
+    }
+
     /// Returns a reference to the cell's color expression.
     pub fn expr(&self) -> &Expr {
         &self.expr
@@ -77,13 +186,18 @@ impl Cell {
         &mut self.expr
     }
 
-    /// Returns the Expr's color.
+    /// Returns the cell's color. This is the override color if one is set,
+    /// otherwise it is the Expr's color.
     pub fn color(
         &self,
         basic: &BasicPalette,
         index_list: &mut HashSet<u32>)
         -> Result<Option<Color>, PaletteError>
     {
+        if let Some(color) = &self.override_color {
+            return Ok(Some(color.clone()));
+        }
+
         let cached = self.cached.clone().take();
         if cached.is_none() {
             self.evaluate_color(basic, index_list)
@@ -110,3 +224,10 @@ impl Default for Cell {
         Cell::new()
     }
 }
+
+
+/// Returns true if the given value is false. Used to skip serializing unset
+/// flags.
+fn is_false(value: &bool) -> bool {
+    !*value
+}
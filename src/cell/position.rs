@@ -101,6 +101,54 @@ impl Position {
             None => Position::ZERO,
         }
     }
+
+    /// Returns the previous position before the given one, wrapping to MAX if
+    /// the position is ZERO. This is the inverse of `wrapping_succ`.
+    pub fn prev(&self) -> Position {
+        self.checked_prev().unwrap_or(Position::MAX)
+    }
+
+    /// Returns the previous position before the given one, or None if the
+    /// position is ZERO.
+    pub fn checked_prev(&self) -> Option<Position> {
+        self.offset(0, 0, -1)
+    }
+
+    /// Returns the position offset from the given one by the given number of
+    /// pages, lines, and columns, or None if the result is out of range.
+    ///
+    /// Column offsets which pass the end or start of a line carry over into
+    /// the next or previous line, and line offsets similarly carry over into
+    /// the next or previous page.
+    pub fn offset(&self, pages: i32, lines: i32, columns: i32)
+        -> Option<Position>
+    {
+        const RADIX: i64 = u16::MAX as i64 + 1;
+
+        let column = i64::from(self.column) + i64::from(columns);
+        let line = i64::from(self.line) + i64::from(lines)
+            + column.div_euclid(RADIX);
+        let page = i64::from(self.page) + i64::from(pages)
+            + line.div_euclid(RADIX);
+
+        Some(Position {
+            page: u16::try_from(page).ok()?,
+            line: line.rem_euclid(RADIX) as u16,
+            column: column.rem_euclid(RADIX) as u16,
+        })
+    }
+
+    /// Returns the position offset from the given one by the given amount
+    /// along the given axis, or None if the offset overflows.
+    pub fn checked_offset(&self, axis: Axis, amount: u16) -> Option<Position> {
+        let mut pos = *self;
+        match axis {
+            Axis::Page   => pos.page = pos.page.checked_add(amount)?,
+            Axis::Line   => pos.line = pos.line.checked_add(amount)?,
+            Axis::Column => pos.column = pos.column.checked_add(amount)?,
+        }
+        Some(pos)
+    }
 }
 
 // Conversion for simplifying serialization.
@@ -177,6 +225,22 @@ impl std::fmt::Display for PositionParseError {
 impl std::error::Error for PositionParseError {}
 
 
+////////////////////////////////////////////////////////////////////////////////
+// Axis
+////////////////////////////////////////////////////////////////////////////////
+/// A `Position` axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+pub enum Axis {
+    /// The page axis.
+    Page,
+    /// The line axis.
+    Line,
+    /// The column axis.
+    Column,
+}
+
+
 
 ////////////////////////////////////////////////////////////////////////////////
 // PositionSelector
@@ -259,6 +323,79 @@ impl PositionSelector {
 
         (low, high)
     }
+
+    /// Returns an iterator over all positions selected by the selector, in
+    /// position order.
+    ///
+    /// Each unspecified part of the selector ranges over every `u16` value,
+    /// so this may yield up to 2^48 positions. Use [`positions_within`] to
+    /// restrict the iteration to a smaller range.
+    ///
+    /// [`positions_within`]: PositionSelector::positions_within
+    pub fn positions(&self) -> PositionSelectorIter {
+        self.positions_within(self.bounds())
+    }
+
+    /// Returns an iterator over the positions selected by the selector which
+    /// lie within the given inclusive `(low, high)` bounds, in position
+    /// order.
+    pub fn positions_within(&self, bound: (Position, Position))
+        -> PositionSelectorIter
+    {
+        let (low, high) = bound;
+        PositionSelectorIter {
+            selector: *self,
+            next: Some(low),
+            high,
+        }
+    }
+
+    /// Returns the first selected position at or after the given position, or
+    /// None if no such position exists.
+    fn first_at_or_after(&self, from: Position) -> Option<Position> {
+        let min_line = self.line.unwrap_or(0);
+        let min_column = self.column.unwrap_or(0);
+        let Position { page, line, column } = from;
+
+        match self.page {
+            Some(p) if page > p => return None,
+            Some(p) if page < p => return Some(
+                Position { page: p, line: min_line, column: min_column }),
+            _ => (),
+        }
+
+        match self.line {
+            Some(l) if line > l => return self.next_page(page)
+                .map(|page| Position { page, line: l, column: min_column }),
+            Some(l) if line < l => return Some(
+                Position { page, line: l, column: min_column }),
+            _ => (),
+        }
+
+        match self.column {
+            Some(c) if column > c => self.next_line(page, line)
+                .map(|(page, line)| Position { page, line, column: c }),
+            Some(c) if column < c => Some(Position { page, line, column: c }),
+            _ => Some(from),
+        }
+    }
+
+    /// Returns the next selectable page after the given one.
+    fn next_page(&self, page: u16) -> Option<u16> {
+        match self.page {
+            Some(_) => None,
+            None    => page.checked_add(1),
+        }
+    }
+
+    /// Returns the next selectable page and line after the given ones.
+    fn next_line(&self, page: u16, line: u16) -> Option<(u16, u16)> {
+        match (self.line, line.checked_add(1)) {
+            (None, Some(line)) => Some((page, line)),
+            _ => self.next_page(page)
+                .map(|page| (page, self.line.unwrap_or(0))),
+        }
+    }
 }
 
 impl From<Position> for PositionSelector {
@@ -305,3 +442,33 @@ impl std::str::FromStr for PositionSelector {
             .finish()
     }
 }
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PositionSelectorIter
+////////////////////////////////////////////////////////////////////////////////
+/// An iterator over the positions selected by a `PositionSelector` within a
+/// given range.
+#[derive(Debug, Clone)]
+pub struct PositionSelectorIter {
+    /// The selector.
+    selector: PositionSelector,
+    /// The position to resume the search from.
+    next: Option<Position>,
+    /// The inclusive upper bound of the iteration.
+    high: Position,
+}
+
+impl Iterator for PositionSelectorIter {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next
+            .and_then(|from| self.selector.first_at_or_after(from))
+            .filter(|pos| *pos <= self.high);
+        self.next = current.and_then(|pos| pos.checked_succ());
+        current
+    }
+}
+
+impl std::iter::FusedIterator for PositionSelectorIter {}
@@ -10,6 +10,7 @@
 
 // Local imports.
 use crate::cell::CellSelector;
+use crate::cell::NameRegexCache;
 use crate::palette::BasicPalette;
 use crate::parse::AtmaScanner;
 use crate::parse::AtmaToken;
@@ -25,6 +26,7 @@ use tephra::result::FailureOwned;
 use tephra::result::ParseResultExt as _;
 
 // Standard library imports.
+use std::collections::HashSet;
 use std::iter::FromIterator;
 
 
@@ -36,8 +38,7 @@ use std::iter::FromIterator;
 /// The lifetime of the CellSelector is the lifetime of any names. The same
 /// `CellSelection` may be resolved for a palette multiple times yielding
 /// different results if the palette is modified intermediately.
-#[derive(Debug, Clone)]
-#[cfg_attr(test, derive(PartialEq))]
+#[derive(Debug, Clone, PartialEq)]
 #[derive(Serialize, Deserialize)]
 pub struct CellSelection<'name>(Vec<CellSelector<'name>>);
 
@@ -55,22 +56,90 @@ impl<'name> CellSelection<'name> {
 
     /// Resolves the CellSelection into a CellIndexSelection containing all of
     /// the selected and occupied cells for the given palette.
+    ///
+    /// All additive selectors are applied before any subtractive selectors,
+    /// so the order of the selectors does not affect the result. Each name
+    /// regex pattern is compiled once, and invalid patterns select nothing.
     pub fn resolve(&self, basic: &BasicPalette) -> CellIndexSelection {
+        let mut index_selection = CellIndexSelection(Selection::new());
+        let mut regexes = NameRegexCache::default();
+
         // Do quick check for an all selectors.
-        for selector in &self.0[..] {
-            if selector.is_all_selector() {
-                return CellIndexSelection(
-                    CellSelector::All.resolve(basic).into_iter().collect());
+        if self.0.iter().any(CellSelector::is_all_selector) {
+            index_selection.insert_all(CellSelector::All.resolve(basic));
+        } else {
+            for selector in &self.0[..] {
+                index_selection.insert_all(
+                    selector.resolve_cached(basic, &mut regexes));
             }
         }
 
-        let mut index_selection = CellIndexSelection(Selection::new());
-        for selector in &self.0[..] {
-            index_selection.insert_all(selector.resolve(basic));
+        for selector in self.subtractive_selectors() {
+            index_selection.remove_all(
+                selector.resolve_cached(basic, &mut regexes));
         }
         index_selection
     }
 
+    /// Resolves the CellSelection into a list of all of the selected and
+    /// occupied cell indices for the given palette, preserving the order of
+    /// the selectors. Indices within a single selector are yielded in
+    /// ascending order, and each index is yielded only at its first
+    /// occurrence. Indices selected by any subtractive selector are omitted.
+    pub fn resolve_ordered(&self, basic: &BasicPalette) -> Vec<u32> {
+        let mut regexes = NameRegexCache::default();
+        let mut seen = HashSet::new();
+        for selector in self.subtractive_selectors() {
+            seen.extend(selector.resolve_cached(basic, &mut regexes));
+        }
+
+        let mut indices = Vec::new();
+        for selector in &self.0[..] {
+            for idx in selector.resolve_cached(basic, &mut regexes) {
+                if seen.insert(idx) {
+                    indices.push(idx);
+                }
+            }
+        }
+        indices
+    }
+
+    /// Resolves each of the CellSelection's selectors individually, returning
+    /// each selector paired with the selected and occupied cell indices it
+    /// produces for the given palette. Subtractive selectors are paired with
+    /// the indices they remove. This can be used to detect selectors which
+    /// select nothing.
+    pub fn resolve_verbose(&self, basic: &BasicPalette)
+        -> Vec<(CellSelector<'name>, Vec<u32>)>
+    {
+        let mut regexes = NameRegexCache::default();
+        self.0
+            .iter()
+            .map(|selector| {
+                let indices = match selector {
+                    CellSelector::Subtract(inner) => inner
+                        .resolve_cached(basic, &mut regexes)
+                        .collect(),
+                    _ => selector
+                        .resolve_cached(basic, &mut regexes)
+                        .collect(),
+                };
+                (selector.clone(), indices)
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over the inner selectors of the subtractive
+    /// `CellSelector`s.
+    fn subtractive_selectors(&self)
+        -> impl Iterator<Item=&CellSelector<'name>>
+    {
+        self.0.iter().filter_map(|selector| match selector {
+            CellSelector::Subtract(inner) => Some(&**inner),
+            _ => None,
+        })
+    }
+
     /// Returns true if the selection is trivially empty.
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
@@ -163,10 +232,52 @@ impl CellIndexSelection {
         }
     }
 
+    /// Removes cell indices from the selection using an iterator.
+    pub fn remove_all<I>(&mut self, indices: I)
+        where I: IntoIterator<Item=u32>
+    {
+        for idx in indices.into_iter() {
+            self.0.minus_in_place(idx.into())
+        }
+    }
+
     /// Returns an iterator oof cell indexes.
     pub fn iter(&self) -> impl Iterator<Item=u32> + '_ {
         self.0.iter()
     }
+
+    /// Returns a new selection containing the indices in either selection.
+    pub fn union(&self, other: &CellIndexSelection) -> CellIndexSelection {
+        let mut res = self.clone();
+        res.insert_all(other.iter());
+        res
+    }
+
+    /// Returns a new selection containing the indices in both selections.
+    pub fn intersection(&self, other: &CellIndexSelection)
+        -> CellIndexSelection
+    {
+        // A ∩ B = A - (A - B)
+        self.difference(&self.difference(other))
+    }
+
+    /// Returns a new selection containing the indices in this selection but
+    /// not in the other selection.
+    pub fn difference(&self, other: &CellIndexSelection)
+        -> CellIndexSelection
+    {
+        let mut res = self.clone();
+        res.remove_all(other.iter());
+        res
+    }
+
+    /// Returns a new selection containing the indices in exactly one of the
+    /// selections.
+    pub fn symmetric_difference(&self, other: &CellIndexSelection)
+        -> CellIndexSelection
+    {
+        self.difference(other).union(&other.difference(self))
+    }
 }
 
 impl FromIterator<u32> for CellIndexSelection {
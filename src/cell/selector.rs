@@ -50,6 +50,27 @@ pub const REF_RANGE_TOKEN: char = '-';
 /// The CellSelection list separator token.
 pub const REF_SEP_TOKEN: char = ',';
 
+/// The CellSelector subtractive prefix token.
+///
+/// This is the same character as [`REF_RANGE_TOKEN`]. Whitespace is not
+/// significant in a selection, so a `-` following an index or position is
+/// always parsed as a range separator: both `:0-:3` and `:0 -:3` select the
+/// range from 0 to 3. A subtractive selector must be preceded by a
+/// [`REF_SEP_TOKEN`], as in `:0, -:3`.
+pub const REF_SUBTRACT_TOKEN: char = '-';
+
+/// The CellSelector tag prefix token.
+pub const REF_TAG_PREFIX_TOKEN: char = '#';
+
+/// The CellSelector name regex delimiter token.
+pub const REF_REGEX_TOKEN: char = '/';
+
+/// The CellSelector 'all named' selection keyword.
+pub const REF_NAMED_KEYWORD: &str = "named";
+
+/// The CellSelector 'all grouped' selection keyword.
+pub const REF_GROUPED_KEYWORD: &str = "grouped";
+
 
 ////////////////////////////////////////////////////////////////////////////////
 // CellSelector
@@ -63,6 +84,12 @@ pub enum CellSelector<'name> {
     /// Select all cells.
     All,
 
+    /// Select all cells with an assigned name.
+    AllNamed,
+
+    /// Select all cells which are members of any group.
+    AllGrouped,
+
     /// Select the cell with the given index.
     Index(u32),
     
@@ -109,6 +136,20 @@ pub enum CellSelector<'name> {
 
     /// Select alls cells within the given group.
     GroupAll(Cow<'name, str>),
+
+    /// Select all cells with the given tag.
+    Tag(Cow<'name, str>),
+
+    /// Select all cells whose assigned names match the given regular
+    /// expression. The pattern is validated when parsed or deserialized; an
+    /// invalid pattern constructed directly selects no cells.
+    #[cfg(feature = "name-regex")]
+    #[serde(deserialize_with = "deserialize_name_regex")]
+    NameRegex(Cow<'name, str>),
+
+    /// Remove the cells selected by the given selector from a
+    /// `CellSelection`. A subtractive selector selects nothing on its own.
+    Subtract(Box<CellSelector<'name>>),
 }
 
 impl<'name> CellSelector<'name> {
@@ -126,11 +167,21 @@ impl<'name> CellSelector<'name> {
         }
     }
 
+    /// Returns true if the selector removes cells from a `CellSelection`.
+    pub fn is_subtractive(&self) -> bool {
+        match self {
+            CellSelector::Subtract(_) => true,
+            _ => false,
+        }
+    }
+
     /// Converts a `CellSelector` to a static lifetime.
     pub fn into_static(self) -> CellSelector<'static> {
         use CellSelector::*;
         match self {
             All => All,
+            AllNamed => AllNamed,
+            AllGrouped => AllGrouped,
             Index(idx) => Index(idx),
             IndexRange { low, high } => IndexRange { low, high },
             PositionSelector(pos_sel) => PositionSelector(pos_sel),
@@ -146,6 +197,10 @@ impl<'name> CellSelector<'name> {
                 high
             },
             GroupAll(group) => GroupAll(Cow::from(group.into_owned())),
+            Tag(tag) => Tag(Cow::from(tag.into_owned())),
+            #[cfg(feature = "name-regex")]
+            NameRegex(pattern) => NameRegex(Cow::from(pattern.into_owned())),
+            Subtract(selector) => Subtract(Box::new(selector.into_static())),
         }
     }
 
@@ -210,12 +265,41 @@ impl<'name> CellSelector<'name> {
 
     /// Returns an ordered iterator over the selected, occupied indices within
     /// the given palette.
+    ///
+    /// Name regex selectors with invalid patterns select nothing, and a
+    /// warning is logged.
     pub fn resolve<'p>(&self, basic: &'p BasicPalette)
         -> impl Iterator<Item=u32>
     {
-        self.index_iter(basic)
-            .collect::<std::collections::BTreeSet<u32>>()
-            .into_iter()
+        self.resolve_cached(basic, &mut NameRegexCache::default())
+    }
+
+    /// Returns an ordered iterator over the selected, occupied indices within
+    /// the given palette, using the given cache to compile name regex
+    /// patterns.
+    pub(crate) fn resolve_cached<'p>(
+        &self,
+        basic: &'p BasicPalette,
+        regexes: &mut NameRegexCache)
+        -> impl Iterator<Item=u32>
+    {
+        let indices: std::collections::BTreeSet<u32> = match self {
+            CellSelector::AllNamed   => basic.named_indices().collect(),
+            CellSelector::AllGrouped => basic.grouped_indices().collect(),
+            CellSelector::Tag(tag)   => basic.tagged_indices(tag).collect(),
+            #[cfg(feature = "name-regex")]
+            CellSelector::NameRegex(pattern) => match regexes.get(
+                pattern,
+                basic.case_insensitive_names())
+            {
+                Some(regex) => basic.regex_named_indices(regex).collect(),
+                None        => std::collections::BTreeSet::new(),
+            },
+            _                        => self.index_iter(basic).collect(),
+        };
+        #[cfg(not(feature = "name-regex"))]
+        let _ = regexes;
+        indices.into_iter()
     }
 
     /// Returns an index iterator for the selector within the given palette.
@@ -303,6 +387,18 @@ impl<'name> CellSelector<'name> {
                         Few::Zero           => None,
                     }
                 },
+
+                // Named, grouped, and tag selectors are resolved directly.
+                AllNamed   |
+                AllGrouped |
+                Tag(_)     => None,
+
+                // Name regex selectors are resolved directly.
+                #[cfg(feature = "name-regex")]
+                NameRegex(_) => None,
+
+                // Subtractive selectors are applied by the `CellSelection`.
+                Subtract(_) => None,
             }
         };
         CellSelectorIndexIter {
@@ -319,6 +415,8 @@ impl<'name> std::fmt::Display for CellSelector<'name> {
         use CellSelector::*;
         match self {
             All => write!(f, "{}", REF_ALL_TOKEN),
+            AllNamed => write!(f, "{}{}", REF_ALL_TOKEN, REF_NAMED_KEYWORD),
+            AllGrouped => write!(f, "{}{}", REF_ALL_TOKEN, REF_GROUPED_KEYWORD),
             Index(idx) => write!(f, "{}{}", REF_PREFIX_TOKEN, idx),
             IndexRange { low, high } => write!(f, "{}{}{}{}{}",
                 REF_PREFIX_TOKEN, low, REF_RANGE_TOKEN, REF_PREFIX_TOKEN, high),
@@ -333,6 +431,14 @@ impl<'name> std::fmt::Display for CellSelector<'name> {
                 group, REF_PREFIX_TOKEN, high),
             GroupAll(group) => write!(f, 
                 "{}{}{}", group, REF_PREFIX_TOKEN, REF_ALL_TOKEN),
+            Tag(tag) => write!(f, "{}{}", REF_TAG_PREFIX_TOKEN, tag),
+            #[cfg(feature = "name-regex")]
+            NameRegex(pattern) => write!(f, "{}{}{}",
+                REF_REGEX_TOKEN,
+                pattern.replace(REF_REGEX_TOKEN, "\\/"),
+                REF_REGEX_TOKEN),
+            Subtract(selector) => write!(f,
+                "{}{}", REF_SUBTRACT_TOKEN, selector),
         }
     }
 }
@@ -391,6 +497,69 @@ impl std::str::FromStr for CellSelector<'static> {
     }
 }
 
+/// Compiles the pattern of a `CellSelector::NameRegex`.
+///
+/// ### Errors
+///
+/// Returns an error if the pattern is not a valid regular expression.
+#[cfg(feature = "name-regex")]
+pub fn name_regex(pattern: &str, case_insensitive: bool)
+    -> Result<regex::Regex, regex::Error>
+{
+    regex::RegexBuilder::new(pattern)
+        .case_insensitive(case_insensitive)
+        .build()
+}
+
+/// Deserializes the pattern of a `CellSelector::NameRegex`, rejecting invalid
+/// regular expressions.
+#[cfg(feature = "name-regex")]
+fn deserialize_name_regex<'de, 'name, D>(deserializer: D)
+    -> Result<Cow<'name, str>, D::Error>
+    where D: serde::Deserializer<'de>
+{
+    let pattern = String::deserialize(deserializer)?;
+    let _ = name_regex(&pattern, false)
+        .map_err(serde::de::Error::custom)?;
+    Ok(Cow::from(pattern))
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// NameRegexCache
+////////////////////////////////////////////////////////////////////////////////
+/// A cache of compiled name regex patterns, allowing each pattern to be
+/// compiled once while resolving a selection.
+#[derive(Debug, Default)]
+pub(crate) struct NameRegexCache {
+    /// The compiled regexes, keyed by their patterns. Invalid patterns are
+    /// stored as None.
+    #[cfg(feature = "name-regex")]
+    regexes: std::collections::HashMap<String, Option<regex::Regex>>,
+}
+
+impl NameRegexCache {
+    /// Returns the compiled regex for the given pattern, compiling it if it
+    /// is not already cached. Returns None and logs a warning if the pattern
+    /// is invalid.
+    #[cfg(feature = "name-regex")]
+    fn get(&mut self, pattern: &str, case_insensitive: bool)
+        -> Option<&regex::Regex>
+    {
+        self.regexes
+            .entry(pattern.to_owned())
+            .or_insert_with(|| match name_regex(pattern, case_insensitive) {
+                Ok(regex) => Some(regex),
+                Err(e)    => {
+                    tracing::warn!("Invalid name regex /{}/: {}", pattern, e);
+                    None
+                },
+            })
+            .as_ref()
+    }
+}
+
+
 ////////////////////////////////////////////////////////////////////////////////
 // CellSelectorIter
 ////////////////////////////////////////////////////////////////////////////////
@@ -507,6 +676,8 @@ impl<'t, 'p> Iterator for CellSelectorIndexIter<'t, 'p> {
             // * Group should be resolved and handled by Index.
             // * GroupAll should be handled by GroupRange.
             // * PositionSelector should be handled by PositionRange.
+            // * AllNamed, AllGrouped, and Tag should be resolved directly.
+            // * Subtract should be handled by CellSelection.
             Some(_) => unreachable!(),
         }
     }
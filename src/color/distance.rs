@@ -0,0 +1,154 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Color difference metrics.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::color::ColorExt as _;
+
+// External library imports.
+use color::Color;
+use serde::Deserialize;
+use serde::Serialize;
+
+
+////////////////////////////////////////////////////////////////////////////////
+// DeltaE
+////////////////////////////////////////////////////////////////////////////////
+/// A color difference metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+pub enum DeltaE {
+    /// The CIE76 color difference.
+    Cie76,
+    /// The CIEDE2000 color difference.
+    Ciede2000,
+}
+
+impl DeltaE {
+    /// Returns the difference between the given colors using the metric.
+    pub fn distance(&self, a: &Color, b: &Color) -> f32 {
+        match self {
+            DeltaE::Cie76     => delta_e_76(a, b),
+            DeltaE::Ciede2000 => delta_e_2000(a, b),
+        }
+    }
+}
+
+impl Default for DeltaE {
+    fn default() -> Self {
+        DeltaE::Cie76
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Color difference functions
+////////////////////////////////////////////////////////////////////////////////
+
+/// Returns the CIE76 color difference (ΔE*ab) between the given colors.
+pub fn delta_e_76(a: &Color, b: &Color) -> f32 {
+    lab_delta_e_76(a.lab_components(), b.lab_components())
+}
+
+/// Returns the CIEDE2000 color difference (ΔE00) between the given colors.
+pub fn delta_e_2000(a: &Color, b: &Color) -> f32 {
+    lab_delta_e_2000(a.lab_components(), b.lab_components())
+}
+
+/// Returns the CIE76 color difference (ΔE*ab) between the given CIELab
+/// `[L, a, b]` components. This is the Euclidean distance between the
+/// components.
+pub fn lab_delta_e_76(lab_a: [f32; 3], lab_b: [f32; 3]) -> f32 {
+    let [l1, a1, b1] = lab_a;
+    let [l2, a2, b2] = lab_b;
+    ((l2 - l1) * (l2 - l1) + (a2 - a1) * (a2 - a1) + (b2 - b1) * (b2 - b1))
+        .sqrt()
+}
+
+/// Returns the CIEDE2000 color difference (ΔE00) between the given CIELab
+/// `[L, a, b]` components, using unit weighting factors.
+///
+/// This follows the formulation given in "The CIEDE2000 Color-Difference
+/// Formula: Implementation Notes, Supplementary Test Data, and Mathematical
+/// Observations" by G. Sharma, W. Wu, and E. N. Dalal.
+pub fn lab_delta_e_2000(lab_a: [f32; 3], lab_b: [f32; 3]) -> f32 {
+    const POW_25_7: f32 = 6_103_515_625.0;
+
+    let [l1, a1, b1] = lab_a;
+    let [l2, a2, b2] = lab_b;
+
+    // Compute the adjusted chroma and hue.
+    let c1 = (a1 * a1 + b1 * b1).sqrt();
+    let c2 = (a2 * a2 + b2 * b2).sqrt();
+    let c_bar_7 = ((c1 + c2) / 2.0).powi(7);
+    let g = 0.5 * (1.0 - (c_bar_7 / (c_bar_7 + POW_25_7)).sqrt());
+
+    let a1p = (1.0 + g) * a1;
+    let a2p = (1.0 + g) * a2;
+    let c1p = (a1p * a1p + b1 * b1).sqrt();
+    let c2p = (a2p * a2p + b2 * b2).sqrt();
+    let h1p = hue_angle(b1, a1p);
+    let h2p = hue_angle(b2, a2p);
+
+    // Compute the differences.
+    let chroma_zero = c1p * c2p == 0.0;
+    let dl = l2 - l1;
+    let dc = c2p - c1p;
+    let dh = if chroma_zero {
+        0.0
+    } else if (h2p - h1p).abs() <= 180.0 {
+        h2p - h1p
+    } else if h2p - h1p > 180.0 {
+        h2p - h1p - 360.0
+    } else {
+        h2p - h1p + 360.0
+    };
+    let dh = 2.0 * (c1p * c2p).sqrt() * (dh / 2.0).to_radians().sin();
+
+    // Compute the means.
+    let l_bar = (l1 + l2) / 2.0;
+    let c_bar = (c1p + c2p) / 2.0;
+    let h_bar = if chroma_zero {
+        h1p + h2p
+    } else if (h1p - h2p).abs() <= 180.0 {
+        (h1p + h2p) / 2.0
+    } else if h1p + h2p < 360.0 {
+        (h1p + h2p + 360.0) / 2.0
+    } else {
+        (h1p + h2p - 360.0) / 2.0
+    };
+
+    // Compute the weighting functions.
+    let t = 1.0
+        - 0.17 * (h_bar - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar).to_radians().cos()
+        + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+    let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let c_bar_7 = c_bar.powi(7);
+    let rc = 2.0 * (c_bar_7 / (c_bar_7 + POW_25_7)).sqrt();
+    let sl = 1.0 + (0.015 * (l_bar - 50.0).powi(2))
+        / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let sc = 1.0 + 0.045 * c_bar;
+    let sh = 1.0 + 0.015 * c_bar * t;
+    let rt = -(2.0 * d_theta).to_radians().sin() * rc;
+
+    let dl = dl / sl;
+    let dc = dc / sc;
+    let dh = dh / sh;
+    (dl * dl + dc * dc + dh * dh + rt * dc * dh).sqrt()
+}
+
+/// Returns the hue angle in degrees for the given CIELab components, in the
+/// range [0.0, 360.0).
+fn hue_angle(b: f32, a: f32) -> f32 {
+    if a == 0.0 && b == 0.0 { return 0.0; }
+    let h = b.atan2(a).to_degrees();
+    if h < 0.0 { h + 360.0 } else { h }
+}
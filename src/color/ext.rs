@@ -0,0 +1,472 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Supplementary color methods.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::palette::ColorSpace;
+
+// External library imports.
+use color::Color;
+use color::Hsl;
+use color::Hsv;
+use color::Rgb;
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ColorExt
+////////////////////////////////////////////////////////////////////////////////
+/// Supplementary methods for `Color`s.
+pub trait ColorExt {
+    /// Returns the WCAG relative luminance of the color, in the range
+    /// [0.0, 1.0].
+    fn relative_luminance(&self) -> f32;
+
+    /// Returns the WCAG contrast ratio between the color and the given color,
+    /// in the range [1.0, 21.0]. The ratio is symmetric.
+    fn contrast_ratio(&self, other: &Color) -> f32;
+
+    /// Returns black or white, whichever has the higher contrast when drawn
+    /// over the color.
+    fn readable_text_color(&self) -> Color;
+
+    /// Returns the CIELab `[L, a, b]` components of the color, using the D65
+    /// reference white.
+    fn lab_components(&self) -> [f32; 3];
+
+    /// Performs a linear interpolation between the color and the given color
+    /// in the CIELab color space.
+    fn lab_linear_interpolate(&self, other: &Color, amount: f32) -> Color;
+
+    /// Returns the color's `#RRGGBB` hex code.
+    fn to_hex_string(&self) -> String;
+
+    /// Returns the color as it would appear to a viewer with the given color
+    /// vision deficiency.
+    fn simulate_deficiency(&self, deficiency: ColorDeficiency) -> Color;
+
+    /// Returns the `HueCategory` of the color's HSV hue, or None if the color
+    /// is achromatic.
+    fn hue_category(&self) -> Option<HueCategory>;
+
+    /// Returns true if the color's hue is red, orange, or yellow. Achromatic
+    /// colors are not warm.
+    fn is_warm(&self) -> bool;
+
+    /// Returns the color with its HSV hue replaced by the given hue in
+    /// degrees. The hue is wrapped into the range [0.0, 360.0).
+    fn with_hue(&self, hue: f32) -> Color;
+
+    /// Returns the color with its HSV saturation replaced by the given
+    /// saturation. The saturation is clamped to the range [0.0, 1.0].
+    fn with_saturation(&self, saturation: f32) -> Color;
+
+    /// Returns the color with its HSV value replaced by the given value. The
+    /// value is clamped to the range [0.0, 1.0].
+    fn with_value(&self, value: f32) -> Color;
+
+    /// Returns the color with its HSL lightness replaced by the given
+    /// lightness. The lightness is clamped to the range [0.0, 1.0].
+    fn with_lightness(&self, lightness: f32) -> Color;
+
+    /// Composites the color over the given opaque background color with the
+    /// given alpha, using source-over compositing in linear RGB. The alpha is
+    /// clamped to the range [0.0, 1.0].
+    fn blend_over(&self, background: &Color, alpha: f32) -> Color;
+
+    /// Returns the color packed into a `u32` as `0x00RRGGBB`. That is, the red
+    /// channel occupies the second most significant byte, and the blue channel
+    /// occupies the least significant byte. The most significant byte is 0.
+    fn to_rgb_u32(&self) -> u32;
+
+    /// Returns the color packed into a `u32` as `0xAARRGGBB`. That is, the
+    /// alpha occupies the most significant byte, and the blue channel occupies
+    /// the least significant byte. Colors are opaque, so the alpha is 0xFF.
+    fn to_argb_u32(&self) -> u32;
+
+    /// Constructs a color from a `u32` packed as `0x00RRGGBB`. That is, the
+    /// red channel is read from the second most significant byte, and the
+    /// blue channel is read from the least significant byte. The most
+    /// significant byte is ignored.
+    fn from_rgb_u32(rgb: u32) -> Self where Self: Sized;
+
+    /// Constructs a color from a `u32` packed as `0xAARRGGBB`. That is, the
+    /// alpha is read from the most significant byte, and the blue channel is
+    /// read from the least significant byte. Colors are opaque, so the alpha
+    /// is ignored.
+    fn from_argb_u32(argb: u32) -> Self where Self: Sized;
+
+    /// Returns the color with its gamma-encoded sRGB channels decoded into
+    /// linear light values.
+    fn srgb_to_linear(&self) -> Color;
+
+    /// Returns the color with its linear light channels gamma-encoded as sRGB
+    /// values.
+    fn linear_to_srgb(&self) -> Color;
+
+    /// Returns the color with its RGB channels clamped to the range
+    /// [0.0, 1.0]. NaN channels are replaced with 0.0.
+    ///
+    /// Color constructors such as `Rgb::from` do not clamp their channels,
+    /// so this should be called on any color built from computed channel
+    /// values.
+    fn clamped(&self) -> Color;
+
+    /// Returns the weighted mean of the given colors in the given color
+    /// space. The weights are normalized by their sum, and hue channels are
+    /// averaged around the hue circle. Returns None if no colors are given,
+    /// if any weight is not finite, or if the weights do not have a positive
+    /// sum.
+    fn mix_many(colors: &[(Color, f32)], space: ColorSpace) -> Option<Color>
+        where Self: Sized;
+}
+
+impl ColorExt for Color {
+    fn relative_luminance(&self) -> f32 {
+        let [r, g, b] = self.rgb_ratios();
+        0.2126 * srgb_to_linear(r)
+            + 0.7152 * srgb_to_linear(g)
+            + 0.0722 * srgb_to_linear(b)
+    }
+
+    fn contrast_ratio(&self, other: &Color) -> f32 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        let (light, dark) = if a >= b { (a, b) } else { (b, a) };
+        (light + 0.05) / (dark + 0.05)
+    }
+
+    fn readable_text_color(&self) -> Color {
+        let lum = self.relative_luminance();
+        let white_contrast = 1.05 / (lum + 0.05);
+        let black_contrast = (lum + 0.05) / 0.05;
+        if white_contrast >= black_contrast {
+            Color::from(Rgb::from([1.0, 1.0, 1.0]))
+        } else {
+            Color::from(Rgb::from([0.0, 0.0, 0.0]))
+        }
+    }
+
+    fn lab_components(&self) -> [f32; 3] {
+        let [r, g, b] = self.rgb_ratios();
+        let r = srgb_to_linear(r);
+        let g = srgb_to_linear(g);
+        let b = srgb_to_linear(b);
+
+        let x = 0.4124564 * r + 0.3575761 * g + 0.1804375 * b;
+        let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+        let z = 0.0193339 * r + 0.1191920 * g + 0.9503041 * b;
+
+        let fx = lab_f(x / D65_WHITE[0]);
+        let fy = lab_f(y / D65_WHITE[1]);
+        let fz = lab_f(z / D65_WHITE[2]);
+
+        [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+    }
+
+    fn lab_linear_interpolate(&self, other: &Color, amount: f32) -> Color {
+        let [la, aa, ba] = self.lab_components();
+        let [lb, ab, bb] = other.lab_components();
+        color_from_lab([
+            la + (lb - la) * amount,
+            aa + (ab - aa) * amount,
+            ba + (bb - ba) * amount,
+        ])
+    }
+
+    fn to_hex_string(&self) -> String {
+        let [r, g, b] = self.rgb_octets();
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    }
+
+    fn simulate_deficiency(&self, deficiency: ColorDeficiency) -> Color {
+        let [r, g, b] = self.rgb_ratios();
+        let r = srgb_to_linear(r);
+        let g = srgb_to_linear(g);
+        let b = srgb_to_linear(b);
+
+        // Convert to LMS cone space.
+        let l = 17.8824 * r + 43.5161 * g + 4.11935 * b;
+        let m = 3.45565 * r + 27.1554 * g + 3.86714 * b;
+        let s = 0.0299566 * r + 0.184309 * g + 1.46709 * b;
+
+        // Project onto the plane seen by the dichromat.
+        let (l, m, s) = match deficiency {
+            ColorDeficiency::Protanopia
+                => (2.02344 * m - 2.52581 * s, m, s),
+            ColorDeficiency::Deuteranopia
+                => (l, 0.494207 * l + 1.24827 * s, s),
+            ColorDeficiency::Tritanopia
+                => (l, m, -0.395913 * l + 0.801109 * m),
+        };
+
+        // Convert back to RGB.
+        let r = 0.08094445 * l - 0.1305044 * m + 0.1167211 * s;
+        let g = -0.01024853 * l + 0.05401933 * m - 0.1136147 * s;
+        let b = -0.0003652969 * l - 0.004121615 * m + 0.6935114 * s;
+
+        Color::from(Rgb::from([
+            linear_to_srgb(r).max(0.0).min(1.0),
+            linear_to_srgb(g).max(0.0).min(1.0),
+            linear_to_srgb(b).max(0.0).min(1.0),
+        ]))
+    }
+
+    fn hue_category(&self) -> Option<HueCategory> {
+        let [h, s, _] = self.hsv_components();
+        if s == 0.0 { return None; }
+        Some(HueCategory::from_hue(h))
+    }
+
+    fn is_warm(&self) -> bool {
+        match self.hue_category() {
+            Some(HueCategory::Red)    |
+            Some(HueCategory::Orange) |
+            Some(HueCategory::Yellow) => true,
+            _                         => false,
+        }
+    }
+
+    fn with_hue(&self, hue: f32) -> Color {
+        let [_, s, v] = self.hsv_components();
+        Color::from(Hsv::from([hue.rem_euclid(360.0), s, v]))
+    }
+
+    fn with_saturation(&self, saturation: f32) -> Color {
+        let [h, _, v] = self.hsv_components();
+        Color::from(Hsv::from([h, saturation.max(0.0).min(1.0), v]))
+    }
+
+    fn with_value(&self, value: f32) -> Color {
+        let [h, s, _] = self.hsv_components();
+        Color::from(Hsv::from([h, s, value.max(0.0).min(1.0)]))
+    }
+
+    fn with_lightness(&self, lightness: f32) -> Color {
+        let [h, s, _] = self.hsl_components();
+        Color::from(Hsl::from([h, s, lightness.max(0.0).min(1.0)]))
+    }
+
+    fn blend_over(&self, background: &Color, alpha: f32) -> Color {
+        let alpha = alpha.max(0.0).min(1.0);
+        if alpha == 1.0 { return *self; }
+        if alpha == 0.0 { return *background; }
+
+        let fg = self.rgb_ratios();
+        let bg = background.rgb_ratios();
+        let mut res = [0.0; 3];
+        for (r, (f, b)) in res.iter_mut().zip(fg.iter().zip(bg.iter())) {
+            let f = srgb_to_linear(*f);
+            let b = srgb_to_linear(*b);
+            *r = linear_to_srgb(f * alpha + b * (1.0 - alpha))
+                .max(0.0)
+                .min(1.0);
+        }
+        Color::from(Rgb::from(res))
+    }
+
+    fn to_rgb_u32(&self) -> u32 {
+        let [r, g, b] = self.rgb_octets();
+        u32::from_be_bytes([0, r, g, b])
+    }
+
+    fn to_argb_u32(&self) -> u32 {
+        let [r, g, b] = self.rgb_octets();
+        u32::from_be_bytes([0xFF, r, g, b])
+    }
+
+    fn from_rgb_u32(rgb: u32) -> Self {
+        let [_, r, g, b] = rgb.to_be_bytes();
+        Color::from(Rgb::from([r, g, b]))
+    }
+
+    fn from_argb_u32(argb: u32) -> Self {
+        let [_, r, g, b] = argb.to_be_bytes();
+        Color::from(Rgb::from([r, g, b]))
+    }
+
+    fn srgb_to_linear(&self) -> Color {
+        let [r, g, b] = self.rgb_ratios();
+        Color::from(Rgb::from([
+            srgb_to_linear(r),
+            srgb_to_linear(g),
+            srgb_to_linear(b),
+        ]))
+    }
+
+    fn linear_to_srgb(&self) -> Color {
+        let [r, g, b] = self.rgb_ratios();
+        Color::from(Rgb::from([
+            linear_to_srgb(r).max(0.0).min(1.0),
+            linear_to_srgb(g).max(0.0).min(1.0),
+            linear_to_srgb(b).max(0.0).min(1.0),
+        ]))
+    }
+
+    fn clamped(&self) -> Color {
+        let clamp = |c: f32| if c.is_nan() { 0.0 } else { c.max(0.0).min(1.0) };
+        let [r, g, b] = self.rgb_ratios();
+        Color::from(Rgb::from([clamp(r), clamp(g), clamp(b)]))
+    }
+
+    fn mix_many(colors: &[(Color, f32)], space: ColorSpace) -> Option<Color> {
+        space.weighted_mean(colors)
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ColorDeficiency
+////////////////////////////////////////////////////////////////////////////////
+/// A dichromatic color vision deficiency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorDeficiency {
+    /// Absence of the long-wavelength (red) cones.
+    Protanopia,
+    /// Absence of the medium-wavelength (green) cones.
+    Deuteranopia,
+    /// Absence of the short-wavelength (blue) cones.
+    Tritanopia,
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// HueCategory
+////////////////////////////////////////////////////////////////////////////////
+/// A named range of hues.
+///
+/// The categories divide the HSV hue circle as follows:
+///
+/// | Category  | Hue range (degrees) |
+/// |-----------|---------------------|
+/// | Red       | [345, 15)           |
+/// | Orange    | [15, 45)            |
+/// | Yellow    | [45, 75)            |
+/// | Green     | [75, 165)           |
+/// | Cyan      | [165, 195)          |
+/// | Blue      | [195, 255)          |
+/// | Magenta   | [255, 345)          |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HueCategory {
+    /// Red hues.
+    Red,
+    /// Orange hues.
+    Orange,
+    /// Yellow hues.
+    Yellow,
+    /// Green hues.
+    Green,
+    /// Cyan hues.
+    Cyan,
+    /// Blue hues.
+    Blue,
+    /// Magenta hues.
+    Magenta,
+}
+
+impl HueCategory {
+    /// Returns the `HueCategory` for the given hue in degrees.
+    pub fn from_hue(hue: f32) -> Self {
+        let hue = hue.rem_euclid(360.0);
+        match hue {
+            h if h <  15.0 => HueCategory::Red,
+            h if h <  45.0 => HueCategory::Orange,
+            h if h <  75.0 => HueCategory::Yellow,
+            h if h < 165.0 => HueCategory::Green,
+            h if h < 195.0 => HueCategory::Cyan,
+            h if h < 255.0 => HueCategory::Blue,
+            h if h < 345.0 => HueCategory::Magenta,
+            _              => HueCategory::Red,
+        }
+    }
+}
+
+impl std::fmt::Display for HueCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use HueCategory::*;
+        match self {
+            Red     => write!(f, "red"),
+            Orange  => write!(f, "orange"),
+            Yellow  => write!(f, "yellow"),
+            Green   => write!(f, "green"),
+            Cyan    => write!(f, "cyan"),
+            Blue    => write!(f, "blue"),
+            Magenta => write!(f, "magenta"),
+        }
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Conversion functions
+////////////////////////////////////////////////////////////////////////////////
+
+/// Constructs a `Color` from the given CIELab `[L, a, b]` components, using
+/// the D65 reference white. Colors outside of the sRGB gamut are clamped into
+/// it.
+pub fn color_from_lab(lab: [f32; 3]) -> Color {
+    let [l, a, b] = lab;
+    let fy = (l + 16.0) / 116.0;
+    let fx = fy + a / 500.0;
+    let fz = fy - b / 200.0;
+
+    let x = lab_f_inv(fx) * D65_WHITE[0];
+    let y = lab_f_inv(fy) * D65_WHITE[1];
+    let z = lab_f_inv(fz) * D65_WHITE[2];
+
+    let r =  3.2404542 * x - 1.5371385 * y - 0.4985314 * z;
+    let g = -0.9692660 * x + 1.8760108 * y + 0.0415560 * z;
+    let b =  0.0556434 * x - 0.2040259 * y + 1.0572252 * z;
+
+    Color::from(Rgb::from([
+        linear_to_srgb(r).max(0.0).min(1.0),
+        linear_to_srgb(g).max(0.0).min(1.0),
+        linear_to_srgb(b).max(0.0).min(1.0),
+    ]))
+}
+
+/// The D65 reference white point in XYZ.
+const D65_WHITE: [f32; 3] = [0.95047, 1.0, 1.08883];
+
+/// The CIELab forward companding function.
+fn lab_f(t: f32) -> f32 {
+    const DELTA: f32 = 6.0 / 29.0;
+    if t > DELTA * DELTA * DELTA {
+        t.cbrt()
+    } else {
+        t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+    }
+}
+
+/// The CIELab inverse companding function.
+fn lab_f_inv(t: f32) -> f32 {
+    const DELTA: f32 = 6.0 / 29.0;
+    if t > DELTA {
+        t * t * t
+    } else {
+        3.0 * DELTA * DELTA * (t - 4.0 / 29.0)
+    }
+}
+
+/// Converts a gamma-encoded sRGB channel ratio to a linear channel ratio.
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear channel ratio to a gamma-encoded sRGB channel ratio.
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
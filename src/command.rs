@@ -11,6 +11,7 @@
 // Internal modules.
 mod ancillary;
 mod dispatch;
+mod execute;
 mod option;
 mod script;
 
@@ -22,5 +23,6 @@ pub mod list;
 // Exports.
 pub use ancillary::*;
 pub use dispatch::*;
+pub use execute::*;
 pub use option::*;
 pub use script::*;
@@ -0,0 +1,192 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Single-line text command execution.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::Cell;
+use crate::cell::CellRef;
+use crate::cell::CellSelection;
+use crate::command::Stmt;
+use crate::error::CommandError;
+use crate::palette::BasicPalette;
+use crate::palette::History;
+use crate::palette::Operation;
+use crate::parse::AtmaScanner;
+use crate::parse::command_stmt;
+
+// External library imports.
+use tephra::combinator::end_of_text;
+use tephra::combinator::left;
+use tephra::lexer::Lexer;
+use tephra::position::Lf;
+use tephra::result::ParseResultExt as _;
+use tracing::event;
+use tracing::Level;
+use tracing::span;
+
+
+////////////////////////////////////////////////////////////////////////////////
+// execute
+////////////////////////////////////////////////////////////////////////////////
+/// Parses and applies a single command line to the given palette, recording
+/// the applied operations in the given `History`, if any.
+///
+/// The supported commands are:
+///
+/// + `insert EXPR`: Inserts the expression's colors at the first unoccupied
+///   indices.
+/// + `delete SELECTION`: Removes the selected cells.
+/// + `name NAME POSITION_SELECTOR`: Assigns a name to a position selector.
+/// + `group GROUP SELECTION`: Adds the selected cells to a group.
+/// + `set CELL_REF EXPR`: Sets a cell's color expression.
+/// + `undo [COUNT]`: Undoes the given number of operations, default 1.
+/// + `redo [COUNT]`: Redoes the given number of operations, default 1.
+///
+/// The `insert`, `name`, `group`, and `set` commands use the same syntax as
+/// the corresponding script statements, and may optionally end with a `;`.
+/// The `undo` and `redo` commands return an error if no `History` is given.
+pub fn execute(
+    palette: &mut BasicPalette,
+    history: Option<&mut History>,
+    line: &str)
+    -> Result<(), CommandError>
+{
+    let span = span!(Level::DEBUG, "execute");
+    let _enter = span.enter();
+    event!(Level::TRACE, "executing command: {:?}", line);
+
+    let line = line.trim();
+    let (verb, args) = match line.find(char::is_whitespace) {
+        Some(split) => (&line[..split], line[split..].trim()),
+        None        => (line, ""),
+    };
+
+    let ops = match verb {
+        "undo" | "redo" => {
+            let count = match args {
+                "" => 1,
+                _  => args.parse::<usize>()
+                    .map_err(|_| CommandError::InvalidCommand {
+                        msg: format!("invalid {} count '{}'", verb, args)
+                            .into(),
+                    })?,
+            };
+            let history = history.ok_or_else(|| CommandError::InvalidCommand {
+                msg: format!("cannot {}: no history", verb).into(),
+            })?;
+            let _ = match verb {
+                "undo" => palette.try_undo(history, count)?,
+                _      => palette.try_redo(history, count)?,
+            };
+            return Ok(());
+        },
+
+        "delete" => {
+            let selection: CellSelection<'static> = args.parse()?;
+            selection
+                .resolve(palette)
+                .into_iter()
+                .map(|idx| Operation::RemoveCell {
+                    cell_ref: CellRef::Index(idx),
+                })
+                .collect()
+        },
+
+        "insert" | "name" | "group" | "set"
+            => stmt_operations(palette, parse_command_stmt(line)?)?,
+
+        "" => return Err(CommandError::InvalidCommand {
+            msg: "empty command".into(),
+        }),
+
+        _ => return Err(CommandError::InvalidCommand {
+            msg: format!("unrecognized command '{}'", verb).into(),
+        }),
+    };
+
+    palette
+        .apply_operations(&ops[..], history)
+        .map_err(CommandError::from)
+}
+
+/// Parses the given text as a command statement, appending the terminating
+/// `;` if it is missing.
+fn parse_command_stmt(text: &str) -> Result<Stmt, CommandError> {
+    let text = if text.ends_with(';') {
+        text.to_owned()
+    } else {
+        format!("{};", text)
+    };
+
+    // Setup parser.
+    let scanner = AtmaScanner::new();
+    let column_metrics = Lf::with_tab_width(4);
+    let mut lexer = Lexer::new(scanner, &text, column_metrics);
+    lexer.set_filter_fn(|tok| !tok.is_whitespace_or_comment());
+
+    // Perform parse.
+    left(command_stmt, end_of_text)
+        (lexer)
+        .finish()
+        .map_err(CommandError::from)
+}
+
+/// Returns the operations which will apply the given command statement to
+/// the palette.
+fn stmt_operations(palette: &mut BasicPalette, stmt: Stmt)
+    -> Result<Vec<Operation>, CommandError>
+{
+    match stmt {
+        Stmt::Expr { expr } => {
+            let mut ops = Vec::new();
+            let mut next = 0;
+            for expr in expr.exprs(palette)? {
+                let idx = palette
+                    .unoccupied_index_or_next(next)
+                    .ok_or(CommandError::InvalidCommand {
+                        msg: "no free indices".into(),
+                    })?;
+                ops.push(Operation::InsertCell {
+                    idx,
+                    cell: Cell::new_with_expr(expr),
+                });
+                next = idx.wrapping_add(1);
+            }
+            Ok(ops)
+        },
+
+        Stmt::Set { cell_ref, expr } => {
+            let mut exprs = expr.exprs(palette)?;
+            if exprs.len() != 1 {
+                return Err(CommandError::UnsupportedExpr {
+                    msg: "set requires an expression for a single cell"
+                        .into(),
+                });
+            }
+            Ok(vec![Operation::SetExpr { cell_ref, expr: exprs.remove(0) }])
+        },
+
+        Stmt::Name { name, selector }
+            => Ok(vec![Operation::AssignName { name, selector }]),
+
+        Stmt::Group { group, selection } => Ok(selection
+            .resolve(palette)
+            .into_iter()
+            .map(|idx| Operation::AssignGroup {
+                cell_ref: CellRef::Index(idx),
+                group: group.clone(),
+                idx: None,
+            })
+            .collect()),
+
+        _ => Err(CommandError::InvalidCommand {
+            msg: "header statements are not supported as commands".into(),
+        }),
+    }
+}
@@ -276,7 +276,7 @@ fn list_grid<'a>(
             for elem in line_buf.drain(..) {
                 match elem {
                     Ok(Some(c)) => color_display.print(c),
-                    Err(PaletteError::UndefinedColor { cell_ref, circular }) => {
+                    Err(PaletteError::UndefinedColor { cell_ref, circular, .. }) => {
                         color_display.print_invalid();
                         tracing::warn!("{:?} {:?}", cell_ref, circular);
                     },
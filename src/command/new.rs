@@ -43,7 +43,8 @@ fn create_new_palette(
 
     if !no_history { palette = palette.with_history(); }
     if let Some(name) = name {
-        let _ = palette.inner_mut().assign_name(name, PositionSelector::ALL)?;
+        let _ = palette.inner_mut()
+            .assign_name(name, PositionSelector::ALL, true)?;
     }
     Ok(palette)
 }
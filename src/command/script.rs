@@ -9,6 +9,8 @@
 ////////////////////////////////////////////////////////////////////////////////
 
 // Local imports.
+use crate::cell::CellRef;
+use crate::cell::CellSelection;
 use crate::cell::Position;
 use crate::cell::PositionSelector;
 use crate::command::CommonOptions;
@@ -18,13 +20,11 @@ use crate::error::FileError;
 use crate::palette::InsertExpr;
 use crate::palette::Palette;
 use crate::parse::AtmaScanner;
-use crate::parse::stmt;
-use crate::parse::stmts;
+use crate::parse::script;
 use crate::setup::Config;
 use crate::setup::Settings;
 
 // External library imports.
-use tephra::combinator::end_of_text;
 use tephra::lexer::Lexer;
 use tephra::position::Lf;
 use tephra::result::FailureOwned;
@@ -53,6 +53,11 @@ pub struct Script {
 }
 
 impl Script {
+    /// Returns the script's statements.
+    pub fn stmts(&self) -> &[Stmt] {
+        &self.stmts[..]
+    }
+
     /// Executes the script on the given palette.
     pub fn execute(
         self,
@@ -113,24 +118,10 @@ impl std::str::FromStr for Script {
         let mut lexer = Lexer::new(scanner, text, column_metrics);
         lexer.set_filter_fn(|tok| !tok.is_whitespace_or_comment());
 
-        let (script, succ) = stmts
+        script
             (lexer)
-            .map_value(|stmts| Script { stmts })?
-            .take_value();
-
-        // Try to parse end-of-text, and if it fails, return the error from
-        // a stmt parse.
-        let end = end_of_text
-            (succ.lexer);
-        if let Err(fail) = end {
-            stmt
-                (fail.lexer)
-                .map_value(|_| script)
-                .finish()
-        } else {
-            end.map_value(|_| script)
-                .finish()
-        }
+            .map_value(|stmts| Script { stmts })
+            .finish()
     }
 }
 
@@ -155,6 +146,18 @@ pub enum Stmt {
     Expr {
         expr: InsertExpr,
     },
+    Set {
+        cell_ref: CellRef<'static>,
+        expr: InsertExpr,
+    },
+    Name {
+        name: Cow<'static, str>,
+        selector: PositionSelector,
+    },
+    Group {
+        group: Cow<'static, str>,
+        selection: CellSelection<'static>,
+    },
 }
 
 impl Stmt {
@@ -224,6 +227,25 @@ impl Stmt {
                     .insert_exprs(&[expr], name, positioning, cursor_behavior)
                     .context("expr insert failed.")?;
             },
+
+            Set { cell_ref, expr }      => {
+                let mut exprs = expr.exprs(palette.inner())?;
+                if exprs.len() != 1 {
+                    anyhow::bail!("set requires a single expr, {} provided.",
+                        exprs.len());
+                }
+                palette
+                    .set_expr(cell_ref, exprs.remove(0))
+                    .context("expr set failed.")?;
+            },
+
+            Name { name, selector }     => {
+                palette.set_name(Some(name), selector)?;
+            },
+
+            Group { group, selection }  => {
+                palette.set_group(Some(group), selection, false)?;
+            },
         }
 
         Ok(())
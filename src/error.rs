@@ -10,6 +10,7 @@
 
 // Local imports.
 use crate::cell::CellRef;
+use crate::cell::PositionSelector;
 use crate::parse::AtmaScanner;
 
 // External library imports.
@@ -33,6 +34,13 @@ pub struct ParseError {
     source: FailureOwned<Lf>,
 }
 
+impl ParseError {
+    /// Returns the kind of the parse error, if it is known.
+    pub fn kind(&self) -> Option<ParseErrorKind> {
+        ParseErrorKind::of(&self.source)
+    }
+}
+
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(msg) = &self.msg { write!(f, "{}", msg)?; }
@@ -58,6 +66,80 @@ impl<'t> From<Failure<'t, AtmaScanner, Lf>> for ParseError {
     }
 }
 
+
+////////////////////////////////////////////////////////////////////////////////
+// ParseErrorKind
+////////////////////////////////////////////////////////////////////////////////
+/// A machine-readable classification of a parse error.
+///
+/// Parsers which produce a `FailureOwned` attach the kind as the failure's
+/// source, where it can be recovered using `ParseErrorKind::of`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseErrorKind {
+    /// The text is not a syntactically valid expression.
+    InvalidSyntax,
+    /// The expression is not a valid insert expression.
+    InvalidInsertExpr,
+    /// The expression is not a valid ramp expression.
+    InvalidRampExpr,
+    /// The expression is not a valid blend expression.
+    InvalidBlendExpr,
+    /// The expression is not a valid blend function.
+    InvalidBlendFunction,
+    /// The expression is not a valid unary blend function.
+    InvalidUnaryBlendFunction,
+    /// The expression is not a valid binary blend function.
+    InvalidBinaryBlendFunction,
+    /// The expression is not a valid color space.
+    InvalidColorSpace,
+    /// The expression is not a valid interpolate value.
+    InvalidInterpolate,
+    /// The expression is not a valid interpolate range.
+    InvalidInterpolateRange,
+    /// The expression is not a valid interpolate function.
+    InvalidInterpolateFunction,
+}
+
+impl ParseErrorKind {
+    /// Returns the kind attached to the given failure, if any.
+    pub fn of(failure: &FailureOwned<Lf>) -> Option<Self> {
+        failure.source
+            .as_ref()
+            .and_then(|source| source.downcast_ref::<ParseErrorKind>())
+            .copied()
+    }
+
+    /// Attaches the kind to the given failure, unless the failure already has
+    /// a source.
+    pub fn attach(self, mut failure: FailureOwned<Lf>) -> FailureOwned<Lf> {
+        if failure.source.is_none() {
+            failure.source = Some(Box::new(self));
+        }
+        failure
+    }
+}
+
+impl std::fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ParseErrorKind::*;
+        f.write_str(match self {
+            InvalidSyntax              => "invalid syntax",
+            InvalidInsertExpr          => "invalid insert expression",
+            InvalidRampExpr            => "invalid ramp expression",
+            InvalidBlendExpr           => "invalid blend expression",
+            InvalidBlendFunction       => "invalid blend function",
+            InvalidUnaryBlendFunction  => "invalid unary blend function",
+            InvalidBinaryBlendFunction => "invalid binary blend function",
+            InvalidColorSpace          => "invalid color space",
+            InvalidInterpolate         => "invalid interpolate value",
+            InvalidInterpolateRange    => "invalid interpolate range",
+            InvalidInterpolateFunction => "invalid interpolate function",
+        })
+    }
+}
+
+impl std::error::Error for ParseErrorKind {}
+
 ////////////////////////////////////////////////////////////////////////////////
 // ScriptError
 ////////////////////////////////////////////////////////////////////////////////
@@ -81,6 +163,87 @@ impl std::error::Error for ScriptError {
 }
 
 
+////////////////////////////////////////////////////////////////////////////////
+// CommandError
+////////////////////////////////////////////////////////////////////////////////
+/// An error occurred while executing a palette command.
+#[derive(Debug)]
+pub enum CommandError {
+    /// The command text could not be parsed.
+    ParseError {
+        /// The error message.
+        msg: Option<String>,
+        /// The error source.
+        source: FailureOwned<Lf>,
+    },
+
+    /// The command could not be applied to the palette.
+    PaletteError {
+        /// The error source.
+        source: PaletteError,
+    },
+
+    /// The command was given an expression it does not support.
+    UnsupportedExpr {
+        /// A description of the unsupported expression.
+        msg: Cow<'static, str>,
+    },
+
+    /// The command was not recognized or had invalid arguments.
+    InvalidCommand {
+        /// A description of the error.
+        msg: Cow<'static, str>,
+    },
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::ParseError { msg, .. } => {
+                if let Some(msg) = msg { write!(f, "{}", msg)?; }
+                Ok(())
+            },
+
+            CommandError::PaletteError { source } => {
+                write!(f, "{}", source)
+            },
+
+            CommandError::UnsupportedExpr { msg } => write!(f,
+                "Unsupported expression: {}", msg),
+
+            CommandError::InvalidCommand { msg } => write!(f,
+                "Invalid command: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CommandError::ParseError { source, .. } => Some(source),
+            CommandError::PaletteError { source } => Some(source),
+            CommandError::UnsupportedExpr { .. } => None,
+            CommandError::InvalidCommand { .. } => None,
+        }
+    }
+}
+
+impl From<FailureOwned<Lf>> for CommandError {
+    fn from(err: FailureOwned<Lf>) -> Self {
+        CommandError::ParseError {
+            msg: Some("command parse error".to_owned()),
+            source: err,
+        }
+    }
+}
+
+impl From<PaletteError> for CommandError {
+    fn from(err: PaletteError) -> Self {
+        CommandError::PaletteError { source: err }
+    }
+}
+
+
 ////////////////////////////////////////////////////////////////////////////////
 // FileError
 ////////////////////////////////////////////////////////////////////////////////
@@ -265,6 +428,8 @@ pub enum PaletteError {
         cell_ref: CellRef<'static>,
         /// Whether the color is undefined due to a circular reference.
         circular: bool,
+        /// The cell whose expression holds the failing reference, if any.
+        referrer: Option<CellRef<'static>>,
     },
 
     /// All available positions in the palette are assigned.
@@ -275,6 +440,32 @@ pub enum PaletteError {
         /// A description of the invalid input.
         msg: Cow<'static, str>,
     },
+
+    /// A name is already assigned to a different position selector.
+    NameAlreadyAssigned {
+        /// The name.
+        name: Cow<'static, str>,
+        /// The position selector the name is assigned to.
+        existing_selector: PositionSelector,
+    },
+
+    /// An attempt was made to modify a locked cell.
+    CellLocked {
+        /// The index of the locked cell.
+        idx: u32,
+    },
+
+    /// An attempt to resolve a cell's color followed too many references.
+    ReferenceTooDeep {
+        /// The number of references followed.
+        depth: usize,
+    },
+
+    /// An applied operation could not be written to the palette's journal.
+    JournalWriteFailed {
+        /// The journal error.
+        source: FileError,
+    },
 }
 
 impl std::fmt::Display for PaletteError {
@@ -293,24 +484,98 @@ impl std::fmt::Display for PaletteError {
                     max)
             },
 
-            PaletteError::UndefinedColor { cell_ref, circular } => {
-                write!(f,
-                    "color is undefined {} cell references: {}",
-                    if *circular { "due to circular" } else { "for" },
-                    cell_ref)
+            PaletteError::UndefinedColor { cell_ref, circular, referrer } => {
+                match referrer {
+                    Some(referrer) => write!(f,
+                        "cell {} references {}", referrer, cell_ref)?,
+                    None => write!(f,
+                        "color is undefined for cell {}", cell_ref)?,
+                }
+                if *circular { write!(f, " (circular reference)")?; }
+                Ok(())
             },
 
             PaletteError::AllPositionsAssigned => write!(f,
                 "All palette positions are already assigned."),
 
             PaletteError::InvalidInputValue { msg } => write!(f,
-                "Invalid input value: {}", msg)
+                "Invalid input value: {}", msg),
+
+            PaletteError::NameAlreadyAssigned { name, existing_selector } => {
+                write!(f,
+                    "name '{}' is already assigned to {}",
+                    name,
+                    existing_selector)
+            },
+
+            PaletteError::CellLocked { idx } => write!(f,
+                "cell {} is locked", idx),
+
+            PaletteError::ReferenceTooDeep { depth } => write!(f,
+                "reference depth limit exceeded after following {} references",
+                depth),
+
+            PaletteError::JournalWriteFailed { .. } => write!(f,
+                "failed to write operation to palette journal"),
         }
     }
 }
 
 impl std::error::Error for PaletteError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        None
+        match self {
+            PaletteError::JournalWriteFailed { source } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl PaletteError {
+    /// Returns the error with the given cell recorded as the referrer of an
+    /// `UndefinedColor` error, unless a referrer is already recorded.
+    pub(crate) fn with_referrer<'name>(self, cell_ref: &CellRef<'name>)
+        -> Self
+    {
+        match self {
+            PaletteError::UndefinedColor { cell_ref: r, circular, referrer }
+                => PaletteError::UndefinedColor {
+                    cell_ref: r,
+                    circular,
+                    referrer: referrer
+                        .or_else(|| Some(cell_ref.clone().into_static())),
+                },
+            e => e,
+        }
+    }
+
+    /// Returns a single-line description of the error and its sources. See
+    /// [`display_chain`].
+    ///
+    /// [`display_chain`]: fn.display_chain.html
+    pub fn display_chain(&self) -> String {
+        display_chain(self)
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Error rendering
+////////////////////////////////////////////////////////////////////////////////
+/// Returns a single-line description of the given error followed by each of
+/// its sources, separated by colons. Sources whose descriptions repeat the
+/// previous description are omitted.
+pub fn display_chain(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut rendered = error.to_string().trim_end().to_owned();
+    let mut prev = rendered.clone();
+    let mut source = error.source();
+    while let Some(err) = source {
+        let msg = err.to_string().trim_end().to_owned();
+        if !msg.is_empty() && msg != prev {
+            rendered.push_str(": ");
+            rendered.push_str(&msg);
+        }
+        prev = msg;
+        source = err.source();
     }
+    rendered
 }
@@ -56,8 +56,32 @@ pub mod utility;
 // Exports.
 /// Color encodings.
 pub mod color {
+    pub mod distance;
+    mod ext;
+    #[cfg(feature = "named-colors")]
+    mod names;
     pub use color::*;
+    pub use self::ext::*;
+    #[cfg(feature = "named-colors")]
+    pub use self::names::*;
 }
+pub use self::color::Cmyk;
+/// The most commonly used color types are re-exported at the crate root, so
+/// they can be imported without naming the `color` module.
+///
+/// ```rust
+/// use atma::Color;
+/// use atma::Rgb;
+/// use atma::color::ColorExt as _;
+///
+/// let color = Color::from(Rgb::from([0xFF, 0x80, 0x00]));
+/// assert_eq!(color.to_rgb_u32(), 0xFF8000);
+/// ```
+pub use self::color::Color;
+pub use self::color::Hsl;
+pub use self::color::Hsv;
+pub use self::color::Rgb;
+pub use self::color::Xyz;
 pub use palette::Expr;
 pub use palette::Palette;
 pub use setup::Config;
@@ -9,10 +9,12 @@
 ////////////////////////////////////////////////////////////////////////////////
 
 // Internal modules.
+mod ase;
 mod full;
 mod basic;
 mod expr;
 mod history;
+mod journal;
 mod operation;
 
 // Exports.
@@ -20,4 +22,5 @@ pub use full::*;
 pub use basic::*;
 pub use expr::*;
 pub use history::*;
+pub use journal::*;
 pub use operation::*;
@@ -0,0 +1,272 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Adobe Swatch Exchange (ASE) encoding and decoding.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::color::Cmyk;
+use crate::color::Color;
+use crate::color::ColorExt as _;
+use crate::color::Rgb;
+use crate::color::color_from_lab;
+
+// Standard library imports.
+use std::convert::TryFrom;
+use std::io::Error;
+use std::io::ErrorKind;
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Constants
+////////////////////////////////////////////////////////////////////////////////
+
+/// The ASE file signature.
+const ASE_SIGNATURE: &[u8; 4] = b"ASEF";
+
+/// The group start block type.
+const BLOCK_GROUP_START: u16 = 0xC001;
+
+/// The group end block type.
+const BLOCK_GROUP_END: u16 = 0xC002;
+
+/// The color entry block type.
+const BLOCK_COLOR: u16 = 0x0001;
+
+/// The 'normal' (i.e., not global or spot) color type.
+const COLOR_TYPE_NORMAL: u16 = 2;
+
+
+////////////////////////////////////////////////////////////////////////////////
+// AseBlock
+////////////////////////////////////////////////////////////////////////////////
+/// A block of an ASE file.
+#[derive(Debug, Clone, PartialEq)]
+pub(in crate::palette) enum AseBlock {
+    /// Opens a named group. Subsequent blocks are within the group until the
+    /// matching `GroupEnd`.
+    GroupStart(String),
+    /// Closes the most recently opened group.
+    GroupEnd,
+    /// A named color.
+    Color {
+        /// The name of the color.
+        name: String,
+        /// The color.
+        color: Color,
+        /// The color model used to encode the color.
+        model: AseColorModel,
+    },
+}
+
+/// An ASE color model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(in crate::palette) enum AseColorModel {
+    /// The RGB color model.
+    Rgb,
+    /// The CMYK color model.
+    Cmyk,
+    /// The CIELab color model.
+    Lab,
+    /// The grayscale color model.
+    Gray,
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Decoding
+////////////////////////////////////////////////////////////////////////////////
+
+/// Decodes the blocks of the given ASE data.
+pub(in crate::palette) fn decode_ase(mut data: &[u8])
+    -> Result<Vec<AseBlock>, Error>
+{
+    let data = &mut data;
+    if take(data, 4)? != ASE_SIGNATURE {
+        return Err(invalid_ase_data("missing ASEF signature"));
+    }
+    let _version_major = read_u16(data)?;
+    let _version_minor = read_u16(data)?;
+    let block_count = read_u32(data)?;
+
+    let mut blocks = Vec::new();
+    for _ in 0..block_count {
+        let block_type = read_u16(data)?;
+        let block_len = usize::try_from(read_u32(data)?)
+            .map_err(|_| invalid_ase_data("block length out of range"))?;
+        let block = &mut take(data, block_len)?;
+
+        match block_type {
+            BLOCK_GROUP_START => blocks.push(
+                AseBlock::GroupStart(read_utf16_string(block)?)),
+
+            BLOCK_GROUP_END => blocks.push(AseBlock::GroupEnd),
+
+            BLOCK_COLOR => {
+                let name = read_utf16_string(block)?;
+                let (color, model) = match take(block, 4)? {
+                    b"RGB " => (Color::from(Rgb::from([
+                            read_f32(block)?,
+                            read_f32(block)?,
+                            read_f32(block)?,
+                        ])), AseColorModel::Rgb),
+                    b"CMYK" => (Color::from(Cmyk::from([
+                            read_f32(block)?,
+                            read_f32(block)?,
+                            read_f32(block)?,
+                            read_f32(block)?,
+                        ])), AseColorModel::Cmyk),
+                    b"LAB " => (color_from_lab([
+                            read_f32(block)? * 100.0,
+                            read_f32(block)?,
+                            read_f32(block)?,
+                        ]), AseColorModel::Lab),
+                    b"Gray" => {
+                        let v = read_f32(block)?;
+                        (Color::from(Rgb::from([v, v, v])), AseColorModel::Gray)
+                    },
+                    _ => return Err(invalid_ase_data("unknown color model")),
+                };
+                blocks.push(AseBlock::Color { name, color, model });
+            },
+
+            // Skip unrecognized blocks.
+            _ => (),
+        }
+    }
+
+    Ok(blocks)
+}
+
+/// Removes and returns the given number of bytes from the front of the data.
+fn take<'d>(data: &mut &'d [u8], len: usize) -> Result<&'d [u8], Error> {
+    if data.len() < len {
+        return Err(invalid_ase_data("unexpected end of data"));
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
+
+/// Reads a big-endian u16 from the front of the data.
+fn read_u16(data: &mut &[u8]) -> Result<u16, Error> {
+    let bytes = take(data, 2)?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Reads a big-endian u32 from the front of the data.
+fn read_u32(data: &mut &[u8]) -> Result<u32, Error> {
+    let bytes = take(data, 4)?;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Reads a big-endian f32 from the front of the data.
+fn read_f32(data: &mut &[u8]) -> Result<f32, Error> {
+    read_u32(data).map(f32::from_bits)
+}
+
+/// Reads a length-prefixed, null-terminated UTF-16 string from the front of
+/// the data.
+fn read_utf16_string(data: &mut &[u8]) -> Result<String, Error> {
+    let len = usize::from(read_u16(data)?);
+    let mut units = Vec::with_capacity(len);
+    for _ in 0..len {
+        units.push(read_u16(data)?);
+    }
+    if units.last() == Some(&0) {
+        let _ = units.pop();
+    }
+    String::from_utf16(&units)
+        .map_err(|_| invalid_ase_data("invalid UTF-16 string"))
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Encoding
+////////////////////////////////////////////////////////////////////////////////
+
+/// Encodes the given blocks as ASE data.
+pub(in crate::palette) fn encode_ase(blocks: &[AseBlock])
+    -> Result<Vec<u8>, Error>
+{
+    let mut data = Vec::new();
+    data.extend_from_slice(ASE_SIGNATURE);
+    data.extend_from_slice(&1u16.to_be_bytes());
+    data.extend_from_slice(&0u16.to_be_bytes());
+    let block_count = u32::try_from(blocks.len())
+        .map_err(|_| invalid_ase_data("too many blocks"))?;
+    data.extend_from_slice(&block_count.to_be_bytes());
+
+    for block in blocks {
+        let mut body = Vec::new();
+        let block_type = match block {
+            AseBlock::GroupStart(name) => {
+                write_utf16_string(&mut body, name)?;
+                BLOCK_GROUP_START
+            },
+
+            AseBlock::GroupEnd => BLOCK_GROUP_END,
+
+            AseBlock::Color { name, color, model } => {
+                write_utf16_string(&mut body, name)?;
+                match model {
+                    AseColorModel::Rgb => {
+                        body.extend_from_slice(b"RGB ");
+                        for c in color.rgb_ratios().iter() {
+                            body.extend_from_slice(&c.to_be_bytes());
+                        }
+                    },
+                    AseColorModel::Cmyk => {
+                        body.extend_from_slice(b"CMYK");
+                        for c in color.cmyk_components().iter() {
+                            body.extend_from_slice(&c.to_be_bytes());
+                        }
+                    },
+                    AseColorModel::Lab => {
+                        body.extend_from_slice(b"LAB ");
+                        let [l, a, b] = color.lab_components();
+                        for c in [l / 100.0, a, b].iter() {
+                            body.extend_from_slice(&c.to_be_bytes());
+                        }
+                    },
+                    AseColorModel::Gray => {
+                        body.extend_from_slice(b"Gray");
+                        let v = color.relative_luminance();
+                        body.extend_from_slice(&v.to_be_bytes());
+                    },
+                }
+                body.extend_from_slice(&COLOR_TYPE_NORMAL.to_be_bytes());
+                BLOCK_COLOR
+            },
+        };
+
+        let body_len = u32::try_from(body.len())
+            .map_err(|_| invalid_ase_data("block too large"))?;
+        data.extend_from_slice(&block_type.to_be_bytes());
+        data.extend_from_slice(&body_len.to_be_bytes());
+        data.extend_from_slice(&body);
+    }
+
+    Ok(data)
+}
+
+/// Writes a length-prefixed, null-terminated UTF-16 string to the data.
+fn write_utf16_string(data: &mut Vec<u8>, s: &str) -> Result<(), Error> {
+    let units: Vec<u16> = s.encode_utf16().chain(std::iter::once(0)).collect();
+    let len = u16::try_from(units.len())
+        .map_err(|_| invalid_ase_data("name too long"))?;
+    data.extend_from_slice(&len.to_be_bytes());
+    for unit in units {
+        data.extend_from_slice(&unit.to_be_bytes());
+    }
+    Ok(())
+}
+
+/// Constructs an I/O error for invalid ASE data.
+pub(in crate::palette) fn invalid_ase_data(msg: &'static str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
@@ -11,16 +11,34 @@
 // Local imports.
 use crate::bimap::BiMap;
 use crate::cell::Cell;
+use crate::cell::Axis;
+use crate::cell::CellIndexSelection;
 use crate::cell::CellRef;
+use crate::cell::CellSelection;
 use crate::cell::Position;
 use crate::cell::PositionSelector;
 use crate::color::Color;
+use crate::color::Rgb;
+use crate::color::ColorExt as _;
+use crate::color::distance::DeltaE;
+use crate::error::CommandError;
 use crate::error::FileError;
 use crate::error::FileErrorContext as _;
 use crate::error::PaletteError;
+use crate::palette::ColorEncoding;
+use crate::palette::ColorSpace;
 use crate::palette::Expr;
 use crate::palette::History;
+use crate::palette::InsertExpr;
 use crate::palette::Operation;
+use crate::palette::RampExpr;
+use crate::palette::SharedJournal;
+use crate::palette::TaggedColor;
+use crate::palette::ase::AseBlock;
+use crate::palette::ase::AseColorModel;
+use crate::palette::ase::decode_ase;
+use crate::palette::ase::encode_ase;
+use crate::palette::ase::invalid_ase_data;
 use crate::utility::Few;
 use crate::utility::split_intersect;
 
@@ -28,11 +46,14 @@ use crate::utility::split_intersect;
 use serde::Deserialize;
 use serde::Serialize;
 use ron::ser::PrettyConfig;
+use ron::ser::to_string;
 use ron::ser::to_string_pretty;
 
 // Standard library imports.
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::convert::TryInto;
@@ -45,14 +66,35 @@ use std::path::Path;
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+// Constants
+////////////////////////////////////////////////////////////////////////////////
+/// The current `BasicPalette` RON serialization format version.
+///
+/// Versions before 2 serialize each cell as its bare `Expr`. Version 2
+/// serializes cells as structs, so that they can carry descriptions, tags,
+/// locks, and override colors.
+pub const RON_FORMAT_VERSION: u32 = 2;
+
+/// The first `BasicPalette` RON serialization format version which serializes
+/// cells as structs.
+const RON_CELL_STRUCT_VERSION: u32 = 2;
+
+/// The default maximum depth of references followed when resolving a color.
+pub const DEFAULT_MAX_REFERENCE_DEPTH: usize = 256;
+
+
 ////////////////////////////////////////////////////////////////////////////////
 // BasicPalette
 ////////////////////////////////////////////////////////////////////////////////
 /// The Atma palette object.
 #[derive(Debug, Clone)]
-#[cfg_attr(test, derive(PartialEq))]
 #[derive(Serialize, Deserialize)]
 pub struct BasicPalette {
+    /// The serialization format version. Palettes written before versioning
+    /// was introduced are read as version 0.
+    #[serde(default)]
+    version: u32,
     // TODO: Consider using a Vec here.
     /// BasicPalette cells storage. Holds cells containing color expressions.
     cells: BTreeMap<u32, Cell>,
@@ -70,6 +112,49 @@ pub struct BasicPalette {
     // TODO: Undo/redo should track the cursor position.
     /// The positioning cursor.
     position_cursor: Position,
+    /// Whether names are resolved case-insensitively. If true, names are
+    /// stored in lowercase.
+    #[serde(default)]
+    case_insensitive_names: bool,
+    /// The number of operations successfully applied to the palette.
+    #[serde(default)]
+    revision: u64,
+    /// The maximum depth of references followed when resolving a color.
+    #[serde(skip, default = "default_max_reference_depth")]
+    max_reference_depth: usize,
+    /// Whether cell locks are currently ignored. This is set while replaying
+    /// history or applying forced operations.
+    #[serde(skip)]
+    ignore_locks: bool,
+    /// Lookup tables mapping indices to their assigned names and groups.
+    /// These are built on demand, and are updated in place as names and
+    /// groups are assigned and unassigned. Other modifications of names,
+    /// positions, or groups discard them.
+    #[serde(skip)]
+    reverse_index: RefCell<Option<ReverseIndex>>,
+    /// Colors resolved during a batch color resolution, keyed by index. This
+    /// is None outside of a batch resolution.
+    #[serde(skip)]
+    color_memo: RefCell<Option<ColorMemo>>,
+    /// The journal recording each applied operation, if any.
+    #[serde(skip)]
+    journal: Option<SharedJournal>,
+}
+
+#[cfg(test)]
+impl PartialEq for BasicPalette {
+    fn eq(&self, other: &Self) -> bool {
+        // The revision is excluded, as it records how the palette was
+        // constructed rather than its content.
+        self.version == other.version
+            && self.cells == other.cells
+            && self.names == other.names
+            && self.positions == other.positions
+            && self.groups == other.groups
+            && self.next_index == other.next_index
+            && self.position_cursor == other.position_cursor
+            && self.case_insensitive_names == other.case_insensitive_names
+    }
 }
 
 
@@ -82,12 +167,20 @@ impl BasicPalette {
     /// Constructs a new `BasicPalette`.
     pub fn new() -> Self {
         BasicPalette {
+            version: RON_FORMAT_VERSION,
             cells: BTreeMap::new(),
             names: BiMap::new(),
             positions: BiMap::new(),
             groups: BTreeMap::new(),
             next_index: 0,
             position_cursor: Position::ZERO,
+            case_insensitive_names: false,
+            revision: 0,
+            max_reference_depth: DEFAULT_MAX_REFERENCE_DEPTH,
+            ignore_locks: false,
+            reverse_index: RefCell::new(None),
+            color_memo: RefCell::new(None),
+            journal: None,
         }
     }
 
@@ -105,34 +198,124 @@ impl BasicPalette {
 
     /// Constructs a new `BasicPalette` by parsing data from the given file.
     pub fn read_from_file(file: &mut File) -> Result<Self, FileError> {
-        BasicPalette::parse_ron_from_file(file)
-    }
-
-    /// Parses a `BasicPalette` from a file using the RON format.
-    fn parse_ron_from_file(file: &mut File) -> Result<Self, FileError> {
         let len = file.metadata()
             .context("Failed to read file metadata")?
             .len();
         let mut buf = Vec::with_capacity(len as usize);
         let _ = file.read_to_end(&mut buf)
             .context("Failed to read palette file")?;
+        BasicPalette::parse_ron_from_bytes(&buf)
+    }
+
+    /// Constructs a new `BasicPalette` by parsing data from the given reader.
+    pub fn read_from_reader<R>(reader: &mut R) -> Result<Self, FileError>
+        where R: Read
+    {
+        let mut buf = Vec::new();
+        let _ = reader.read_to_end(&mut buf)
+            .context("Failed to read palette data")?;
+        BasicPalette::parse_ron_from_bytes(&buf)
+    }
 
+    /// Parses a `BasicPalette` from the given bytes using the RON format.
+    fn parse_ron_from_bytes(buf: &[u8]) -> Result<Self, FileError> {
         use ron::de::Deserializer;
         let mut d = Deserializer::from_bytes(&buf)
             .context("Failed deserializing RON file")?;
-        let palette = BasicPalette::deserialize(&mut d)
+        let probe = RonVersionProbe::deserialize(&mut d)
             .context("Failed parsing RON file")?;
+        probe.check_supported()?;
+
+        let mut d = Deserializer::from_bytes(&buf)
+            .context("Failed deserializing RON file")?;
+        let mut palette = if probe.is_legacy() {
+            BasicPalette::from(LegacyBasicPalette::deserialize(&mut d)
+                .context("Failed parsing RON file")?)
+        } else {
+            BasicPalette::deserialize(&mut d)
+                .context("Failed parsing RON file")?
+        };
         d.end()
             .context("Failed parsing RON file")?;
+        palette.migrate();
         Ok(palette)
     }
 
+    /// Applies the operations recorded in the given journal, as written by a
+    /// [`PaletteJournal`], to the palette. Returns the number of operations
+    /// applied.
+    ///
+    /// The journal should be replayed onto the palette state it was started
+    /// from. Operations are applied without regard to cell locks, as they
+    /// were already validated when they were journaled. An incomplete final
+    /// entry, as may be left by an interrupted write, is ignored. Replayed
+    /// operations are not recorded in the palette's own journal.
+    ///
+    /// [`PaletteJournal`]: crate::palette::PaletteJournal
+    pub fn replay_journal<R>(&mut self, reader: R) -> Result<usize, FileError>
+        where R: Read
+    {
+        let journal = self.journal.take();
+        let res = self.apply_journal_entries(reader);
+        self.journal = journal;
+        res
+    }
+
+    /// Applies the operations recorded in the given journal to the palette,
+    /// returning the number of operations applied.
+    fn apply_journal_entries<R>(&mut self, reader: R)
+        -> Result<usize, FileError>
+        where R: Read
+    {
+        use std::io::BufRead as _;
+        let mut reader = std::io::BufReader::new(reader);
+        let mut line = String::new();
+        let mut count = 0;
+        loop {
+            line.clear();
+            let len = reader.read_line(&mut line)
+                .context("Failed to read journal")?;
+            // Stop at the end of the journal or at an incomplete entry.
+            if len == 0 || !line.ends_with('\n') { break; }
+            if line.trim().is_empty() { continue; }
+
+            use ron::de::Deserializer;
+            let mut d = Deserializer::from_str(&line)
+                .with_context(|| format!(
+                    "Failed deserializing journal entry {}", count + 1))?;
+            let op = Operation::deserialize(&mut d)
+                .with_context(|| format!(
+                    "Failed parsing journal entry {}", count + 1))?;
+            d.end()
+                .with_context(|| format!(
+                    "Failed parsing journal entry {}", count + 1))?;
+
+            let _ = self.apply_operation_forced(&op)
+                .map_err(|e| std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    e))
+                .with_context(|| format!(
+                    "Failed to apply journal entry {}", count + 1))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Upgrades a palette read from an older serialization format to the
+    /// current format version.
+    pub(in crate::palette) fn migrate(&mut self) {
+        // Palettes before version 2 store cells as bare expressions. These are
+        // converted to cells when they are read, so no changes are required.
+        self.version = RON_FORMAT_VERSION;
+    }
+
     /// Writes the `BasicPalette` to the file at the given path.
     pub fn write_to_path<P>(&self, path: &P) -> Result<(), FileError>
         where P: AsRef<Path> + Debug
     {
         let mut file = OpenOptions::new()
             .write(true)
+            .truncate(true)
             .create(true)
             .open(path)
             .with_context(|| format!("Failed to open file {:?}", path))?;
@@ -152,24 +335,458 @@ impl BasicPalette {
     }
 
     /// Writes the `BasicPalette` to the given file.
+    ///
+    /// ### Output stability
+    ///
+    /// The generated output is deterministic: cells, names, positions, and
+    /// groups are always written in sorted key order. Reading a palette and
+    /// immediately writing it back produces byte-identical output, provided
+    /// that it was originally written by this method.
     pub fn write_to_file(&self, file: &mut File) -> Result<(), FileError> {
-        self.generate_ron_into_file(file)
+        self.generate_ron_into_writer(file, &PaletteWriteConfig::default())
+    }
+
+    /// Writes the `BasicPalette` to the given file using the given output
+    /// formatting options.
+    pub fn write_to_file_with(
+        &self,
+        file: &mut File,
+        config: PaletteWriteConfig)
+        -> Result<(), FileError>
+    {
+        self.generate_ron_into_writer(file, &config)
+    }
+
+    /// Writes the `BasicPalette` to the given writer. The output is identical
+    /// to that of [`write_to_file`].
+    ///
+    /// [`write_to_file`]: BasicPalette::write_to_file
+    pub fn write_to_writer<W>(&self, writer: &mut W) -> Result<(), FileError>
+        where W: Write
+    {
+        self.generate_ron_into_writer(writer, &PaletteWriteConfig::default())
+    }
+
+    /// Generates a RON formatted `BasicPalette` by serializing into the given
+    /// writer.
+    fn generate_ron_into_writer<W>(
+        &self,
+        writer: &mut W,
+        config: &PaletteWriteConfig)
+        -> Result<(), FileError>
+        where W: Write
+    {
+        let s = if config.pretty {
+            let pretty = PrettyConfig::new()
+                .with_depth_limit(config.depth_limit)
+                .with_indentor(config.indentor.clone())
+                .with_enumerate_arrays(config.enumerate_arrays)
+                .with_separate_tuple_members(true);
+            to_string_pretty(self, pretty)?
+        } else {
+            to_string(self)?
+        };
+
+        writer.write_all(s.as_bytes())?;
+        Ok(())
+    }
+
+    /// Constructs a new `BasicPalette` by reading JSON data from the file at
+    /// the given path.
+    #[cfg(feature = "json")]
+    pub fn read_json_from_path<P>(path: &P) -> Result<Self, FileError>
+        where P: AsRef<Path> + Debug
+    {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .open(path)
+            .with_context(|| format!("Failed to open file {:?}", path))?;
+        BasicPalette::read_json_from_file(&mut file)
+    }
+
+    /// Constructs a new `BasicPalette` by reading JSON data from the given
+    /// file, as written by [`write_json_to_file`].
+    ///
+    /// [`write_json_to_file`]: BasicPalette::write_json_to_file
+    #[cfg(feature = "json")]
+    pub fn read_json_from_file(file: &mut File) -> Result<Self, FileError> {
+        let json: JsonBasicPalette = serde_json::from_reader(
+                std::io::BufReader::new(file))
+            .map_err(std::io::Error::from)
+            .context("Failed parsing JSON file")?;
+        Ok(json.into())
     }
 
-    /// Generates a RON formatted `BasicPalette` by serializing into the given file.
-    fn generate_ron_into_file(&self, file: &mut File) -> Result<(), FileError> {
-        let pretty = PrettyConfig::new()
-            .with_depth_limit(2)
-            .with_separate_tuple_members(true);
-        let s = to_string_pretty(self, pretty)?;
+    /// Writes the `BasicPalette` to the file at the given path as JSON.
+    #[cfg(feature = "json")]
+    pub fn write_json_to_path<P>(&self, path: &P) -> Result<(), FileError>
+        where P: AsRef<Path> + Debug
+    {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(path)
+            .with_context(|| format!("Failed to open file {:?}", path))?;
+        self.write_json_to_file(&mut file)
+    }
 
-        file.write_all(s.as_bytes())?;
+    /// Writes the `BasicPalette` to the given file as JSON.
+    ///
+    /// The JSON output has the same structure as the RON output, except that
+    /// the assigned positions are written as a list of `[position, index]`
+    /// pairs, because JSON object keys must be strings.
+    #[cfg(feature = "json")]
+    pub fn write_json_to_file(&self, file: &mut File) -> Result<(), FileError> {
+        let mut writer = std::io::BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, &JsonBasicPalette::from(self))
+            .map_err(std::io::Error::from)
+            .context("Failed writing JSON file")?;
+        writer.flush()?;
         Ok(())
     }
 
+    /// Constructs a new `BasicPalette` by reading a GIMP palette from the file
+    /// at the given path.
+    pub fn read_gpl_from_path<P>(path: &P) -> Result<Self, FileError>
+        where P: AsRef<Path> + Debug
+    {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .open(path)
+            .with_context(|| format!("Failed to open file {:?}", path))?;
+        BasicPalette::read_gpl_from_file(&mut file)
+    }
+
+    /// Constructs a new `BasicPalette` by reading a GIMP palette from the
+    /// given file.
+    ///
+    /// Each color is inserted into a new cell, using sequential indices. The
+    /// cells are assigned sequential positions, wrapping lines according to
+    /// the `Columns` value if present. Named colors will have their names
+    /// assigned to their cell's position. Repeated names are made unique by
+    /// appending a number.
+    pub fn read_gpl_from_file(file: &mut File) -> Result<Self, FileError> {
+        let mut buf = String::new();
+        let _ = file.read_to_string(&mut buf)
+            .context("Failed to read GIMP palette file")?;
+
+        let mut lines = buf.lines().enumerate();
+        match lines.next() {
+            Some((_, header)) if header.trim() == "GIMP Palette" => (),
+            _ => return Err(invalid_gpl_data("missing GIMP Palette header"))
+                .context("Failed to parse GIMP palette file"),
+        }
+
+        let mut palette = BasicPalette::new();
+        let mut columns: Option<u16> = None;
+        let mut position = Position::ZERO;
+        for (line_idx, line) in lines {
+            let line_number = line_idx + 1;
+            let line = line.trim();
+            if let Some(value) = line.strip_prefix("Columns:") {
+                columns = value.trim().parse().ok().filter(|c| *c > 0);
+                continue;
+            }
+
+            // Skip comment, metadata, and other non-numeric lines.
+            let mut parts = line.split_whitespace();
+            let first = match parts.next() {
+                Some(first) if first.starts_with(|c: char| c.is_ascii_digit())
+                    => first,
+                _   => continue,
+            };
+
+            let mut octets = [0u8; 3];
+            let channels = std::iter::once(Some(first))
+                .chain(std::iter::repeat_with(|| parts.next()));
+            for (octet, channel) in octets.iter_mut().zip(channels) {
+                *octet = channel
+                    .and_then(|c| c.parse().ok())
+                    .ok_or_else(|| invalid_gpl_data("malformed RGB triple"))
+                    .with_context(|| format!(
+                        "Failed to parse GIMP palette file at line {}",
+                        line_number))?;
+            }
+            let name = parts.collect::<Vec<_>>().join(" ");
+
+            let idx = palette.next_index;
+            palette.next_index = idx.checked_add(1)
+                .ok_or_else(|| invalid_gpl_data("too many colors"))
+                .context("Failed to parse GIMP palette file")?;
+            let context = || format!(
+                "Failed to parse GIMP palette file at line {}",
+                line_number);
+            let _ = palette
+                .insert_cell(idx, Cell::new_with_expr(
+                    Expr::Color(Color::from(Rgb::from(octets)))))
+                .and_then(|_| palette
+                    .assign_position(position, CellRef::Index(idx)))
+                .map_err(invalid_palette_data)
+                .with_context(context)?;
+            if !name.is_empty() && name != "Untitled" {
+                let name = unique_name(&palette, name);
+                let _ = palette
+                    .assign_name(name, position.into(), false)
+                    .map_err(invalid_palette_data)
+                    .with_context(context)?;
+            }
+
+            position = match columns {
+                Some(columns) if position.column.saturating_add(1) >= columns
+                    => Position {
+                        line: position.line.wrapping_add(1),
+                        column: 0,
+                        .. position
+                    },
+                _ => position.wrapping_succ(),
+            };
+        }
+
+        Ok(palette)
+    }
+
+    /// Writes the `BasicPalette` to the file at the given path in the GIMP
+    /// palette format.
+    pub fn write_gpl_to_path<P>(
+        &self,
+        path: &P,
+        name: Option<&str>,
+        columns: Option<u32>)
+        -> Result<(), FileError>
+        where P: AsRef<Path> + Debug
+    {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(path)
+            .with_context(|| format!("Failed to open file {:?}", path))?;
+        self.write_gpl_to_file(&mut file, name, columns)
+    }
+
+    /// Writes the `BasicPalette` to the given file in the GIMP palette format.
+    ///
+    /// Cells are written in index order, using their assigned names if
+    /// present. Cells without a valid color are skipped.
+    pub fn write_gpl_to_file(
+        &self,
+        file: &mut File,
+        name: Option<&str>,
+        columns: Option<u32>)
+        -> Result<(), FileError>
+    {
+        let mut s = String::from("GIMP Palette\n");
+        if let Some(name) = name {
+            s.push_str(&format!("Name: {}\n", name));
+        }
+        if let Some(columns) = columns {
+            s.push_str(&format!("Columns: {}\n", columns));
+        }
+        s.push_str("#\n");
+
+        for idx in self.cells.keys() {
+            let cell_ref = CellRef::Index(*idx);
+            if let Ok(Some(color)) = self.color(&cell_ref) {
+                let [r, g, b] = color.rgb_octets();
+                let cell_name = self.assigned_name(&cell_ref)
+                    .map(|n| n.as_ref())
+                    .unwrap_or("Untitled");
+                s.push_str(&format!("{:3} {:3} {:3}\t{}\n",
+                    r, g, b, cell_name));
+            }
+        }
+
+        file.write_all(s.as_bytes())
+            .context("Failed to write GIMP palette file")
+    }
+
+    /// Constructs a new `BasicPalette` by reading an Adobe Swatch Exchange
+    /// (ASE) file at the given path.
+    pub fn read_ase_from_path<P>(path: &P) -> Result<Self, FileError>
+        where P: AsRef<Path> + Debug
+    {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .open(path)
+            .with_context(|| format!("Failed to open file {:?}", path))?;
+        BasicPalette::read_ase_from_file(&mut file)
+    }
+
+    /// Constructs a new `BasicPalette` by reading an Adobe Swatch Exchange
+    /// (ASE) file from the given file.
+    ///
+    /// Each color is inserted into a new cell, using sequential indices and
+    /// sequential positions. Named colors will have their names assigned to
+    /// their cell's position, and repeated names are made unique by appending
+    /// a number. Colors within an ASE group are added to the
+    /// palette group of the same name, with nested group names joined by a
+    /// `/`. CMYK colors retain their CMYK encoding; all other color models
+    /// are converted to RGB.
+    pub fn read_ase_from_file(file: &mut File) -> Result<Self, FileError> {
+        let mut buf = Vec::new();
+        let _ = file.read_to_end(&mut buf)
+            .context("Failed to read ASE file")?;
+        let blocks = decode_ase(&buf)
+            .context("Failed to parse ASE file")?;
+
+        let mut palette = BasicPalette::new();
+        let mut group_path: Vec<String> = Vec::new();
+        let mut position = Position::ZERO;
+        for block in blocks {
+            let (name, color, model) = match block {
+                AseBlock::GroupStart(name) => {
+                    group_path.push(name);
+                    continue;
+                },
+                AseBlock::GroupEnd => {
+                    let _ = group_path.pop();
+                    continue;
+                },
+                AseBlock::Color { name, color, model } => (name, color, model),
+            };
+
+            let expr = match model {
+                AseColorModel::Cmyk => Expr::TaggedColor(TaggedColor {
+                    color,
+                    encoding: ColorEncoding::Cmyk,
+                    alpha: 1.0,
+                }),
+                _ => Expr::Color(color),
+            };
+
+            let idx = palette.next_index;
+            palette.next_index = idx.checked_add(1)
+                .ok_or_else(|| invalid_ase_data("too many colors"))
+                .context("Failed to parse ASE file")?;
+            let _ = palette
+                .insert_cell(idx, Cell::new_with_expr(expr))
+                .and_then(|_| palette
+                    .assign_position(position, CellRef::Index(idx)))
+                .map_err(invalid_palette_data)
+                .context("Failed to parse ASE file")?;
+            if !name.is_empty() {
+                let name = unique_name(&palette, name);
+                let _ = palette
+                    .assign_name(name, position.into(), false)
+                    .map_err(invalid_palette_data)
+                    .context("Failed to parse ASE file")?;
+            }
+            if !group_path.is_empty() {
+                let _ = palette
+                    .assign_group(
+                        CellRef::Index(idx),
+                        group_path.join("/"),
+                        None)
+                    .map_err(invalid_palette_data)
+                    .context("Failed to parse ASE file")?;
+            }
+
+            position = position.wrapping_succ();
+        }
+
+        Ok(palette)
+    }
+
+    /// Writes the `BasicPalette` to the file at the given path in the Adobe
+    /// Swatch Exchange (ASE) format.
+    pub fn write_ase_to_path<P>(&self, path: &P) -> Result<(), FileError>
+        where P: AsRef<Path> + Debug
+    {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(path)
+            .with_context(|| format!("Failed to open file {:?}", path))?;
+        self.write_ase_to_file(&mut file)
+    }
+
+    /// Writes the `BasicPalette` to the given file in the Adobe Swatch
+    /// Exchange (ASE) format.
+    ///
+    /// Ungrouped cells are written first, in index order, followed by each
+    /// group in name order. Group names containing a `/` are written as
+    /// nested ASE groups. Cells belonging to more than one group are written
+    /// only once, within the first of their groups. Cells are written using their assigned names if
+    /// present, and cells without a valid color are skipped. Colors authored
+    /// in CMYK are written as CMYK; all others are written as RGB.
+    pub fn write_ase_to_file(&self, file: &mut File) -> Result<(), FileError> {
+        let mut blocks = Vec::new();
+
+        let grouped: HashSet<u32> = self.grouped_indices().collect();
+        for idx in self.cells.keys().filter(|idx| !grouped.contains(idx)) {
+            if let Some(block) = self.ase_color_block(*idx) {
+                blocks.push(block);
+            }
+        }
+
+        let mut written: HashSet<u32> = HashSet::new();
+        let mut open_groups: Vec<&str> = Vec::new();
+        for (group, members) in self.groups.iter() {
+            let path: Vec<&str> = group.split('/').collect();
+            let common = open_groups.iter()
+                .zip(path.iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+            while open_groups.len() > common {
+                let _ = open_groups.pop();
+                blocks.push(AseBlock::GroupEnd);
+            }
+            for part in &path[common..] {
+                open_groups.push(part);
+                blocks.push(AseBlock::GroupStart((*part).to_string()));
+            }
+
+            for idx in members.iter().filter(|idx| written.insert(**idx)) {
+                if let Some(block) = self.ase_color_block(*idx) {
+                    blocks.push(block);
+                }
+            }
+        }
+        for _ in open_groups {
+            blocks.push(AseBlock::GroupEnd);
+        }
+
+        let data = encode_ase(&blocks)
+            .context("Failed to generate ASE file")?;
+        file.write_all(&data)
+            .context("Failed to write ASE file")
+    }
+
+    /// Returns the ASE color block for the cell with the given index, or None
+    /// if the cell has no valid color.
+    fn ase_color_block(&self, idx: u32) -> Option<AseBlock> {
+        let cell_ref = CellRef::Index(idx);
+        let color = self.color(&cell_ref).ok()??;
+        let model = match self.cell(&cell_ref).map(Cell::expr) {
+            Ok(Expr::TaggedColor(TaggedColor {
+                encoding: ColorEncoding::Cmyk, ..
+            })) => AseColorModel::Cmyk,
+            _ => AseColorModel::Rgb,
+        };
+        let name = self.assigned_name(&cell_ref)
+            .map(|n| n.to_string())
+            .unwrap_or_default();
+        Some(AseBlock::Color { name, color, model })
+    }
+
     ////////////////////////////////////////////////////////////////////////////
     // Accessors
     ////////////////////////////////////////////////////////////////////////////
+    /// Returns the palette's revision. This is incremented each time the
+    /// palette is modified, either directly or by applying an `Operation`,
+    /// including by undo and redo. Applying an `Operation` increments the
+    /// revision exactly once.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Increments the palette's revision.
+    fn bump_revision(&mut self) {
+        self.revision = self.revision.wrapping_add(1);
+    }
+
     /// Returns the palette's `Position` cursor.
     pub fn position_cursor(&self) -> Position {
         self.position_cursor
@@ -177,9 +794,93 @@ impl BasicPalette {
 
     /// Sets the palette's `Position` cursor, returning its previous value.
     pub fn set_position_cursor(&mut self, pos: Position) -> Position {
+        self.bump_revision();
         std::mem::replace(&mut self.position_cursor, pos)
     }
 
+    /// Returns the maximum depth of references followed when resolving a
+    /// color.
+    pub fn max_reference_depth(&self) -> usize {
+        self.max_reference_depth
+    }
+
+    /// Sets the maximum depth of references followed when resolving a color,
+    /// returning its previous value. Resolving a color through a deeper chain
+    /// of references will fail with a `ReferenceTooDeep` error.
+    pub fn set_max_reference_depth(&mut self, depth: usize) -> usize {
+        std::mem::replace(&mut self.max_reference_depth, depth)
+    }
+
+    /// Returns the palette's journal, if one is set.
+    pub fn journal(&self) -> Option<&SharedJournal> {
+        self.journal.as_ref()
+    }
+
+    /// Sets the journal recording the palette's operations, returning the
+    /// previous journal.
+    ///
+    /// Each `Operation` successfully applied by [`apply_operation`],
+    /// including those applied by undo and redo, is appended to the journal.
+    /// Modifications made through the palette's other methods are not
+    /// recorded.
+    ///
+    /// [`apply_operation`]: #method.apply_operation
+    pub fn set_journal(&mut self, journal: Option<SharedJournal>)
+        -> Option<SharedJournal>
+    {
+        std::mem::replace(&mut self.journal, journal)
+    }
+
+    /// Returns true if names are resolved case-insensitively.
+    pub fn case_insensitive_names(&self) -> bool {
+        self.case_insensitive_names
+    }
+
+    /// Sets whether names are resolved case-insensitively. Enabling
+    /// case-insensitivity converts all assigned names to lowercase.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error without modifying the palette if enabling
+    /// case-insensitivity would cause two assigned names to collide.
+    pub fn set_case_insensitive_names(&mut self, enabled: bool)
+        -> Result<(), PaletteError>
+    {
+        self.invalidate_reverse_index();
+        if enabled && !self.case_insensitive_names {
+            let mut names = BiMap::new();
+            for (name, selector) in self.names.iter() {
+                let folded: Cow<'static, str> = name.to_lowercase().into();
+                if let Some(existing) = names.get_left(&folded) {
+                    return Err(PaletteError::NameAlreadyAssigned {
+                        name: folded,
+                        existing_selector: existing.clone(),
+                    });
+                }
+                let _ = names.insert(folded, selector.clone());
+            }
+            self.names = names;
+        }
+        if enabled != self.case_insensitive_names { self.bump_revision(); }
+        self.case_insensitive_names = enabled;
+        Ok(())
+    }
+
+    /// Returns the name as it is stored in the palette.
+    fn fold_name<'name>(&self, name: Cow<'name, str>) -> Cow<'name, str> {
+        if self.case_insensitive_names {
+            name.to_lowercase().into()
+        } else {
+            name
+        }
+    }
+
+    /// Returns the `PositionSelector` assigned to the given name, ignoring
+    /// case if the palette resolves names case-insensitively.
+    pub fn resolve_name_ci(&self, name: &str) -> Option<&PositionSelector> {
+        self.names.get_left(&self.fold_name(Cow::Borrowed(name)))
+    }
+
     /// Retreives a copy of the color associated with the given `CellRef`.
     pub fn color<'name>(&self, cell_ref: &CellRef<'name>)
         -> Result<Option<Color>, PaletteError>
@@ -188,6 +889,45 @@ impl BasicPalette {
         self.cycle_detect_color(cell_ref, &mut index_list)
     }
 
+    /// Retreives a copy of the color associated with the given `CellRef`, as
+    /// if the cells at the given indices held the given override expressions.
+    /// The palette itself is not modified.
+    pub fn color_with_overrides<'name>(
+        &self,
+        cell_ref: &CellRef<'name>,
+        overrides: &BTreeMap<u32, Expr>)
+        -> Result<Option<Color>, PaletteError>
+    {
+        // Rebuild the cells rather than cloning them so that no cached colors
+        // are carried over.
+        let mut cells: BTreeMap<u32, Cell> = self.cells
+            .iter()
+            .map(|(idx, cell)| (*idx, Cell::new_with_expr(cell.expr().clone())
+                .with_override_color(cell.override_color().cloned())))
+            .collect();
+        for (idx, expr) in overrides {
+            let _ = cells.insert(*idx, Cell::new_with_expr(expr.clone()));
+        }
+
+        let preview = BasicPalette {
+            version: self.version,
+            cells,
+            names: self.names.clone(),
+            positions: self.positions.clone(),
+            groups: self.groups.clone(),
+            next_index: self.next_index,
+            position_cursor: self.position_cursor,
+            case_insensitive_names: self.case_insensitive_names,
+            revision: self.revision,
+            max_reference_depth: self.max_reference_depth,
+            ignore_locks: false,
+            reverse_index: RefCell::new(None),
+            color_memo: RefCell::new(None),
+            journal: None,
+        };
+        preview.color(cell_ref)
+    }
+
     /// Retreives a copy of the color associated with the given `CellRef`.
     pub(in super) fn cycle_detect_color<'name>(
         &self,
@@ -200,50 +940,182 @@ impl BasicPalette {
             return Err(PaletteError::UndefinedColor {
                 cell_ref: cell_ref.clone().into_static(),
                 circular: true,
+                referrer: None,
             });
         }
+        self.check_reference_depth(index_list)?;
+        let depth = index_list.len();
         let _ = index_list.insert(idx);
 
-        self.cells
+        // A memoized color was resolved without encountering a cycle, so it is
+        // valid regardless of the path used to reach it. It may have been
+        // resolved through a shorter chain of references, however, so its
+        // references must still fit within the maximum reference depth.
+        let memoized = self.color_memo
+            .borrow()
+            .as_ref()
+            .and_then(|memo| memo.colors.get(&idx).cloned());
+        if let Some((color, height)) = memoized {
+            if depth + height >= self.max_reference_depth {
+                return Err(PaletteError::ReferenceTooDeep {
+                    depth: self.max_reference_depth,
+                });
+            }
+            if let Some(memo) = self.color_memo.borrow_mut().as_mut() {
+                memo.deepest = memo.deepest.max(depth + height);
+            }
+            return Ok(color);
+        }
+
+        let outer_deepest = self.color_memo
+            .borrow_mut()
+            .as_mut()
+            .map(|memo| std::mem::replace(&mut memo.deepest, depth));
+
+        let color = self.cells
             .get(&idx)
             .ok_or(PaletteError::UndefinedColor { 
                 cell_ref: cell_ref.clone().into_static(),
                 circular: false,
+                referrer: None,
             })
-            .and_then(|cell| cell.color(self, index_list))
+            .and_then(|cell| cell
+                .color(self, index_list)
+                .map_err(|e| e.with_referrer(cell_ref)));
+
+        if let Some(memo) = self.color_memo.borrow_mut().as_mut() {
+            if let Ok(color) = &color {
+                let height = memo.deepest.saturating_sub(depth);
+                let _ = memo.colors.insert(idx, (color.clone(), height));
+            }
+            memo.deepest = memo.deepest.max(outer_deepest.unwrap_or(0));
+        }
+        color
     }
 
-    /// Retreives a reference to the `Cell` associated with the given `CellRef`.
-    pub fn cell<'name>(&self, cell_ref: &CellRef<'name>)
-        -> Result<&Cell, PaletteError>
+    /// Returns an error if following another reference would exceed the
+    /// maximum reference depth. This guards against stack overflows when
+    /// resolving long chains of references.
+    fn check_reference_depth(&self, index_list: &HashSet<u32>)
+        -> Result<(), PaletteError>
+    {
+        let depth = index_list.len();
+        if depth >= self.max_reference_depth {
+            Err(PaletteError::ReferenceTooDeep { depth })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Calls the given function with color memoization enabled, so that each
+    /// cell's color is resolved at most once. Only successfully resolved
+    /// colors are memoized, so cycles are still detected, and the depth of
+    /// each memoized color's references is recorded, so reference chains
+    /// which are too deep are still detected. The memoized colors are
+    /// discarded when the outermost call returns.
+    fn with_color_memo<F, R>(&self, f: F) -> R
+        where F: FnOnce() -> R
+    {
+        let outermost = {
+            let mut memo = self.color_memo.borrow_mut();
+            let outermost = memo.is_none();
+            if outermost { *memo = Some(ColorMemo::default()); }
+            outermost
+        };
+        let res = f();
+        if outermost { *self.color_memo.borrow_mut() = None; }
+        res
+    }
+
+    /// Retreives the alpha value of the color associated with the given
+    /// `CellRef`.
+    pub fn alpha<'name>(&self, cell_ref: &CellRef<'name>)
+        -> Result<f32, PaletteError>
+    {
+        let mut index_list = HashSet::new();
+        self.cycle_detect_alpha(cell_ref, &mut index_list)
+    }
+
+    /// Retreives the alpha value of the color associated with the given
+    /// `CellRef`.
+    pub(in super) fn cycle_detect_alpha<'name>(
+        &self,
+        cell_ref: &CellRef<'name>,
+        index_list: &mut HashSet<u32>)
+        -> Result<f32, PaletteError>
     {
         let idx = BasicPalette::resolve_ref_to_index(&self, cell_ref)?;
+        if index_list.contains(&idx) {
+            return Err(PaletteError::UndefinedColor {
+                cell_ref: cell_ref.clone().into_static(),
+                circular: true,
+                referrer: None,
+            });
+        }
+        self.check_reference_depth(index_list)?;
+        let _ = index_list.insert(idx);
 
         self.cells
             .get(&idx)
-            .ok_or(PaletteError::UndefinedCellReference { 
+            .ok_or(PaletteError::UndefinedColor { 
                 cell_ref: cell_ref.clone().into_static(),
+                circular: false,
+                referrer: None,
             })
+            .and_then(|cell| cell
+                .expr()
+                .alpha(self, index_list)
+                .map_err(|e| e.with_referrer(cell_ref)))
     }
 
-    /// Retreives a mutable reference to the `Cell` associated with the given
-    /// `CellRef`.
-    pub fn cell_mut<'name>(&mut self, cell_ref: &CellRef<'name>)
-        -> Result<&mut Cell, PaletteError>
+    /// Retreives a reference to the `Cell` associated with the given `CellRef`.
+    pub fn cell<'name>(&self, cell_ref: &CellRef<'name>)
+        -> Result<&Cell, PaletteError>
     {
         let idx = BasicPalette::resolve_ref_to_index(&self, cell_ref)?;
 
         self.cells
-            .get_mut(&idx)
+            .get(&idx)
             .ok_or(PaletteError::UndefinedCellReference { 
                 cell_ref: cell_ref.clone().into_static(),
             })
     }
+
+    /// Retreives a mutable reference to the `Cell` associated with the given
+    /// `CellRef`.
+    pub fn cell_mut<'name>(&mut self, cell_ref: &CellRef<'name>)
+        -> Result<&mut Cell, PaletteError>
+    {
+        let idx = BasicPalette::resolve_ref_to_index(&self, cell_ref)?;
+        if !self.cells.contains_key(&idx) {
+            return Err(PaletteError::UndefinedCellReference { 
+                cell_ref: cell_ref.clone().into_static(),
+            });
+        }
+
+        // The cell may be modified through the returned reference.
+        self.bump_revision();
+        Ok(self.cells.get_mut(&idx).expect("retreive occupied cell"))
+    }
     
     /// Resolves a `CellRef` to its index in the palette.
     pub fn resolve_ref_to_index<'name>(&self, cell_ref: &CellRef<'name>)
         -> Result<u32, PaletteError>
     {
+        if let (true, CellRef::Name(name)) = 
+            (self.case_insensitive_names, cell_ref)
+        {
+            let folded = CellRef::Name(self.fold_name(name.clone()));
+            return BasicPalette::resolve_ref_to_index_using(
+                    &self.names,
+                    &self.positions,
+                    &self.groups,
+                    &folded)
+                .map_err(|_| PaletteError::UndefinedCellReference { 
+                    cell_ref: cell_ref.clone().into_static(),
+                });
+        }
+
         BasicPalette::resolve_ref_to_index_using(
             &self.names,
             &self.positions,
@@ -325,6 +1197,33 @@ impl BasicPalette {
         self.cells.get(idx).is_some()
     }
 
+    /// Returns the number of occupied cells in the palette.
+    pub fn occupied_cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns an iterator over the occupied indices in the palette, in
+    /// ascending order.
+    pub fn occupied_indices(&self) -> impl Iterator<Item=u32> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// Returns an iterator over the occupied indices in the palette and their
+    /// `Cell`s, in ascending index order.
+    pub fn iter_cells(&self) -> impl Iterator<Item=(u32, &Cell)> + '_ {
+        self.cells.iter().map(|(idx, cell)| (*idx, cell))
+    }
+
+    /// Returns an iterator over the occupied indices in the palette and
+    /// mutable references to their `Cell`s, in ascending index order.
+    pub fn iter_cells_mut(&mut self)
+        -> impl Iterator<Item=(u32, &mut Cell)> + '_
+    {
+        // The cells may be modified through the returned references.
+        self.bump_revision();
+        self.cells.iter_mut().map(|(idx, cell)| (*idx, cell))
+    }
+
     /// Returns the full range of occupied indices in the palette, or None if
     /// the palette is empty.
     pub(in crate) fn occupied_index_range(&self) -> Few<u32> {
@@ -369,8 +1268,7 @@ impl BasicPalette {
 
     /// Returns true if the given name is assigned in the palette.
     pub fn is_assigned_name(&self, name: &str) -> bool {
-        self.names
-            .get_left(&Cow::Borrowed(name))
+        self.resolve_name_ci(name)
             .is_some()
     }
 
@@ -390,8 +1288,7 @@ impl BasicPalette {
 
     /// Returns the index associated with the given name if it is occupied.
     pub fn resolve_name_if_occupied(&self, name: &str) -> Option<u32> {
-        self.names
-            .get_left(&Cow::Borrowed(name))
+        self.resolve_name_ci(name)
             .and_then(|pos_sel| {
                 match Position::try_from(pos_sel.clone()) {
                     Err(_) => None,
@@ -405,6 +1302,129 @@ impl BasicPalette {
             })
     }
 
+    /// Searches the assigned names for the given query, returning the matching
+    /// names and their occupied indices, ordered by match quality.
+    ///
+    /// Matching is case-insensitive. Exact matches are ranked first, followed
+    /// by prefix matches, substring matches, and subsequence matches. Matches
+    /// of equal quality are ordered by name length and then alphabetically.
+    /// Names which do not resolve to an occupied cell are omitted.
+    ///
+    /// This examines every assigned name, so it takes O(n * m) time for n
+    /// names of length m, plus O(k log k) time to rank the k matches.
+    pub fn search_names(&self, query: &str) -> Vec<(Cow<'static, str>, u32)> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(u8, Cow<'static, str>, u32)> = self.names
+            .iter()
+            .filter_map(|(name, pos_sel)| {
+                let rank = name_match_rank(&name.to_lowercase(), &query)?;
+                let idx = Position::try_from(pos_sel.clone())
+                    .ok()
+                    .and_then(|pos| self.positions.get_left(&pos).cloned())
+                    .filter(|idx| self.cells.contains_key(idx))?;
+                Some((rank, name.clone(), idx))
+            })
+            .collect();
+
+        matches.sort_by(|(rank_a, name_a, _), (rank_b, name_b, _)| rank_a
+            .cmp(rank_b)
+            .then_with(|| name_a.len().cmp(&name_b.len()))
+            .then_with(|| name_a.cmp(name_b)));
+
+        matches
+            .into_iter()
+            .map(|(_, name, idx)| (name, idx))
+            .collect()
+    }
+
+    /// Returns an iterator over the occupied indices with an assigned name.
+    pub fn named_indices(&self) -> impl Iterator<Item=u32> + '_ {
+        self.names
+            .iter()
+            .filter_map(move |(_, pos_sel)| {
+                match Position::try_from(pos_sel.clone()) {
+                    Err(_) => None,
+                    Ok(pos) => self.positions.get_left(&pos).cloned(),
+                }
+            })
+            .filter(move |idx| self.cells.contains_key(idx))
+    }
+
+    /// Returns an iterator over the occupied indices with an assigned name
+    /// matching the given regular expression.
+    #[cfg(feature = "name-regex")]
+    pub fn regex_named_indices<'a>(&'a self, regex: &'a regex::Regex)
+        -> impl Iterator<Item=u32> + 'a
+    {
+        self.names
+            .iter()
+            .filter(move |(name, _)| regex.is_match(name))
+            .filter_map(move |(_, pos_sel)| {
+                match Position::try_from(pos_sel.clone()) {
+                    Err(_) => None,
+                    Ok(pos) => self.positions.get_left(&pos).cloned(),
+                }
+            })
+            .filter(move |idx| self.cells.contains_key(idx))
+    }
+
+    /// Returns each name whose position selector selects the position
+    /// assigned to the given index.
+    pub fn names_of(&self, idx: u32)
+        -> Result<Vec<Cow<'static, str>>, PaletteError>
+    {
+        self.with_reverse_index(|reverse_index| reverse_index.names
+            .get(&idx)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// Returns all of the names, positions, and group memberships which
+    /// resolve to the same index as the given `CellRef`.
+    pub fn aliases<'name>(&self, cell_ref: &CellRef<'name>)
+        -> Result<Aliases, PaletteError>
+    {
+        let index = self.resolve_ref_to_index(cell_ref)?;
+        Ok(Aliases {
+            index,
+            names: self.names_of(index)?,
+            position: self.positions.get_right(&index).cloned(),
+            groups: self.groups_of(index)?,
+        })
+    }
+
+    /// Returns each group containing the given index, along with the index's
+    /// position within the group.
+    pub fn groups_of(&self, idx: u32)
+        -> Result<Vec<(Cow<'static, str>, u32)>, PaletteError>
+    {
+        self.with_reverse_index(|reverse_index| reverse_index.groups
+            .get(&idx)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// Calls the given function with the palette's reverse index, building
+    /// the index first if it has been invalidated.
+    fn with_reverse_index<F, R>(&self, f: F) -> Result<R, PaletteError>
+        where F: FnOnce(&ReverseIndex) -> R
+    {
+        let mut reverse_index = self.reverse_index.borrow_mut();
+        let built = match reverse_index.take() {
+            Some(built) => built,
+            None        => ReverseIndex::build(self)?,
+        };
+        let res = f(&built);
+        *reverse_index = Some(built);
+        Ok(res)
+    }
+
+    /// Discards the palette's reverse index. This must be called whenever
+    /// names, positions, or groups are modified without updating the index.
+    fn invalidate_reverse_index(&mut self) {
+        *self.reverse_index.get_mut() = None;
+    }
+
     /// Returns the name of each group assigned to the given cell reference.
     pub fn assigned_groups<'name>(&self, cell_ref: &CellRef<'name>)
         -> Result<Vec<&Cow<'static, str>>, PaletteError>
@@ -424,7 +1444,9 @@ impl BasicPalette {
     pub fn is_assigned_group(&self, group: &str, idx: u32) -> bool {
         self.groups
             .get(group)
-            .map(|elems| usize::try_from(idx).unwrap() < elems.len())
+            .and_then(|elems| usize::try_from(idx)
+                .ok()
+                .map(|idx| idx < elems.len()))
             .unwrap_or(false)
     }
 
@@ -432,8 +1454,9 @@ impl BasicPalette {
     pub fn is_occupied_group(&self, group: &str, idx: u32) -> bool {
         self.groups
             .get(group)
-            .and_then(|elems| elems
-                .get(usize::try_from(idx).unwrap())
+            .and_then(|elems| usize::try_from(idx)
+                .ok()
+                .and_then(|idx| elems.get(idx))
                 .and_then(|cell_idx| self.cells.get(cell_idx)))
             .is_some()
     }
@@ -477,7 +1500,9 @@ impl BasicPalette {
     {
         self.groups
             .get(group)
-            .and_then(|elems| elems.get(usize::try_from(idx).unwrap()))
+            .and_then(|elems| usize::try_from(idx)
+                .ok()
+                .and_then(|idx| elems.get(idx)))
             .and_then(|idx| if self.cells.contains_key(idx) {
                 Some(*idx)
             } else {
@@ -485,6 +1510,26 @@ impl BasicPalette {
             })
     }
 
+    /// Returns an iterator over the occupied indices which are members of any
+    /// group. Indices may be repeated if they are members of multiple groups.
+    pub fn grouped_indices(&self) -> impl Iterator<Item=u32> + '_ {
+        self.groups
+            .values()
+            .flat_map(|members| members.iter().cloned())
+            .filter(move |idx| self.cells.contains_key(idx))
+    }
+
+    /// Returns an iterator over the occupied indices whose cells have the given
+    /// tag.
+    pub fn tagged_indices<'a>(&'a self, tag: &'a str)
+        -> impl Iterator<Item=u32> + 'a
+    {
+        self.cells
+            .iter()
+            .filter(move |(_, cell)| cell.has_tag(tag))
+            .map(|(idx, _)| *idx)
+    }
+
     /// Returns the position assigned to the given cell reference.
     pub fn assigned_position<'name>(&self, cell_ref: &CellRef<'name>)
         -> Option<&Position>
@@ -551,6 +1596,548 @@ impl BasicPalette {
     }
 
 
+    ////////////////////////////////////////////////////////////////////////////
+    // Color queries
+    ////////////////////////////////////////////////////////////////////////////
+
+    /// Resolves the colors of each selected cell, in index order. Errors are
+    /// returned for each cell individually, so a failure to resolve one cell
+    /// does not prevent the others from being resolved.
+    ///
+    /// Resolved colors are memoized for the duration of the call, so cells
+    /// which share references are only resolved once.
+    pub fn colors_in_selection<'name>(&self, selection: &CellSelection<'name>)
+        -> Vec<(u32, Result<Option<Color>, PaletteError>)>
+    {
+        let selected = selection.resolve(self);
+        self.with_color_memo(|| {
+            let mut index_list = HashSet::new();
+            selected
+                .into_iter()
+                .map(|idx| {
+                    index_list.clear();
+                    let color = self.cycle_detect_color(
+                        &CellRef::Index(idx),
+                        &mut index_list);
+                    (idx, color)
+                })
+                .collect()
+        })
+    }
+
+    /// Returns the suggested text color for labelling each selected cell, in
+    /// index order. Cells whose colors cannot be resolved are omitted.
+    pub fn text_colors<'name>(&self, selection: &CellSelection<'name>)
+        -> Vec<(u32, Color)>
+    {
+        let selected = selection.resolve(self);
+        self.with_color_memo(|| selected
+            .into_iter()
+            .filter_map(|idx| match self.color(&CellRef::Index(idx)) {
+                Ok(Some(color)) => Some((idx, color.readable_text_color())),
+                _               => None,
+            })
+            .collect())
+    }
+
+    /// Returns the mean of the selected colors in the given color space, or
+    /// None if no selected color could be resolved. Cells with undefined
+    /// colors are skipped.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if resolving a selected color fails for any reason
+    /// other than the color being undefined.
+    pub fn average_color<'name>(
+        &self,
+        selection: &CellSelection<'name>,
+        space: ColorSpace)
+        -> Result<Option<Color>, PaletteError>
+    {
+        let mut colors = Vec::new();
+        for (_, color) in self.colors_in_selection(selection) {
+            match color {
+                Ok(Some(color)) => colors.push(color),
+                Ok(None)        => (),
+                Err(PaletteError::UndefinedColor { .. }) => (),
+                Err(e)          => return Err(e),
+            }
+        }
+        Ok(space.mean(&colors[..]))
+    }
+
+    /// Renders the selected cells as a horizontal strip of swatches, in index
+    /// order. Each cell is painted as a band `cell_width` pixels wide and
+    /// `height` pixels tall. Cells whose colors cannot be resolved are
+    /// painted with a checkerboard pattern.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the width of the strip overflows a `u32`.
+    #[cfg(feature = "image")]
+    pub fn render_strip<'name>(
+        &self,
+        selection: &CellSelection<'name>,
+        cell_width: u32,
+        height: u32)
+        -> Result<image::RgbImage, PaletteError>
+    {
+        /// The size of the checkerboard squares, in pixels.
+        const CHECKER_SIZE: u32 = 4;
+
+        let colors: Vec<Option<[u8; 3]>> = self
+            .colors_in_selection(selection)
+            .into_iter()
+            .map(|(_, color)| match color {
+                Ok(Some(color)) => Some(color.rgb_octets()),
+                _               => None,
+            })
+            .collect();
+        let width = u32::try_from(colors.len())
+            .ok()
+            .and_then(|len| cell_width.checked_mul(len))
+            .ok_or(PaletteError::InvalidInputValue {
+                msg: "strip width exceeds the maximum image width.".into(),
+            })?;
+
+        Ok(image::RgbImage::from_fn(width, height, |x, y| {
+            let band = (x / cell_width) as usize;
+            match colors[band] {
+                Some(octets) => image::Rgb(octets),
+                None if (x / CHECKER_SIZE + y / CHECKER_SIZE) % 2 == 0
+                    => image::Rgb([0xCC, 0xCC, 0xCC]),
+                None => image::Rgb([0xFF, 0xFF, 0xFF]),
+            }
+        }))
+    }
+
+
+    /// Returns true if the given cells resolve to colors whose RGB channels
+    /// all lie within the given tolerance of eachother.
+    pub fn colors_equal<'a, 'b>(
+        &self,
+        a: &CellRef<'a>,
+        b: &CellRef<'b>,
+        epsilon: f32)
+        -> Result<bool, PaletteError>
+    {
+        let color_a = self.color(a)?
+            .ok_or_else(|| PaletteError::UndefinedColor {
+                cell_ref: a.clone().into_static(),
+                circular: false,
+                referrer: None,
+            })?;
+        let color_b = self.color(b)?
+            .ok_or_else(|| PaletteError::UndefinedColor {
+                cell_ref: b.clone().into_static(),
+                circular: false,
+                referrer: None,
+            })?;
+
+        Ok(color_a.rgb_ratios()
+            .iter()
+            .zip(color_b.rgb_ratios().iter())
+            .all(|(ca, cb)| (ca - cb).abs() <= epsilon))
+    }
+
+    /// Returns the indices of the occupied cells whose colors lie within the
+    /// given Euclidean distance of the target color in RGB space. Cells whose
+    /// colors cannot be resolved are skipped.
+    pub fn find_cells_by_color(&self, target: &Color, tolerance: f32)
+        -> Vec<u32>
+    {
+        let [tr, tg, tb] = target.rgb_ratios();
+        self.cells
+            .keys()
+            .filter(|idx| match self.color(&CellRef::Index(**idx)) {
+                Ok(Some(color)) => {
+                    let [r, g, b] = color.rgb_ratios();
+                    let dist = ((r - tr) * (r - tr)
+                        + (g - tg) * (g - tg)
+                        + (b - tb) * (b - tb))
+                        .sqrt();
+                    dist <= tolerance
+                },
+                _ => false,
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Returns the index of the occupied cell whose color is closest to the
+    /// target color, along with its distance. Returns `None` if no cell colors
+    /// can be resolved.
+    ///
+    /// The distance is the CIE76 color difference (ΔE*ab): the Euclidean
+    /// distance between the colors in the CIELab color space using the D65
+    /// reference white. If multiple cells are equally close, the one with the
+    /// lowest index is returned.
+    pub fn nearest_cell(&self, target: &Color) -> Option<(u32, f32)> {
+        self.nearest_cell_using(target, DeltaE::Cie76)
+    }
+
+    /// Returns the index of the occupied cell whose color is closest to the
+    /// target color using the given color difference metric, along with its
+    /// distance. Returns `None` if no cell colors can be resolved. If multiple
+    /// cells are equally close, the one with the lowest index is returned.
+    pub fn nearest_cell_using(&self, target: &Color, metric: DeltaE)
+        -> Option<(u32, f32)>
+    {
+        let mut nearest: Option<(u32, f32)> = None;
+        for idx in self.cells.keys() {
+            if let Ok(Some(color)) = self.color(&CellRef::Index(*idx)) {
+                let dist = metric.distance(&color, target);
+                if nearest.map_or(true, |(_, d)| dist < d) {
+                    nearest = Some((*idx, dist));
+                }
+            }
+        }
+        nearest
+    }
+
+    /// Returns the occupied indices of the palette, sorted by the given key.
+    /// The sort is stable, so cells with equal keys remain in index order.
+    /// Cells without a color are placed last. The palette is not modified.
+    pub fn sorted_indices(&self, key: SortKey) -> Vec<u32> {
+        if key == SortKey::Index {
+            return self.cells.keys().copied().collect();
+        }
+
+        let mut keyed: Vec<(u32, Option<f32>)> = self.cells
+            .keys()
+            .map(|idx| {
+                let color = self.color(&CellRef::Index(*idx)).ok().flatten();
+                (*idx, color.map(|c| key.value(&c)))
+            })
+            .collect();
+
+        keyed.sort_by(|(_, a), (_, b)| match (a, b) {
+            (Some(a), Some(b)) => a
+                .partial_cmp(b)
+                .unwrap_or(std::cmp::Ordering::Equal),
+            (Some(_), None)    => std::cmp::Ordering::Less,
+            (None,    Some(_)) => std::cmp::Ordering::Greater,
+            (None,    None)    => std::cmp::Ordering::Equal,
+        });
+        keyed.into_iter().map(|(idx, _)| idx).collect()
+    }
+
+    /// Returns the WCAG contrast ratio between the colors of the given cells.
+    pub fn contrast_ratio<'a, 'b>(&self, a: &CellRef<'a>, b: &CellRef<'b>)
+        -> Result<f32, PaletteError>
+    {
+        let color_a = self.color(a)?
+            .ok_or_else(|| PaletteError::UndefinedColor {
+                cell_ref: a.clone().into_static(),
+                circular: false,
+                referrer: None,
+            })?;
+        let color_b = self.color(b)?
+            .ok_or_else(|| PaletteError::UndefinedColor {
+                cell_ref: b.clone().into_static(),
+                circular: false,
+                referrer: None,
+            })?;
+
+        Ok(color_a.contrast_ratio(&color_b))
+    }
+
+    /// Resolves the color of the `Cell` associated with the given `CellRef`,
+    /// returning any error encountered without returning the color.
+    pub fn touch<'name>(&self, cell_ref: &CellRef<'name>)
+        -> Result<(), PaletteError>
+    {
+        match self.color(cell_ref)? {
+            Some(color) if color.rgb_ratios().iter().any(|c| !c.is_finite())
+                => Err(PaletteError::InvalidInputValue {
+                    msg: format!("color for cell {} has a non-finite channel \
+                        value.", cell_ref).into(),
+                }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Resolves the color of every `Cell` in the palette, returning the index
+    /// of each cell that failed to resolve with its error.
+    pub fn touch_all(&self) -> Vec<(u32, PaletteError)> {
+        self.cells
+            .keys()
+            .filter_map(|idx| self.touch(&CellRef::Index(*idx))
+                .err()
+                .map(|e| (*idx, e)))
+            .collect()
+    }
+
+    /// Checks the palette for internal consistency, returning every error
+    /// found. Returns an empty `Vec` if the palette is consistent.
+    ///
+    /// Each cell's expression is checked for references which do not resolve
+    /// to an occupied cell, and is evaluated to detect undefined or circular
+    /// colors. Positions, names, and group members which are assigned to
+    /// unoccupied indices are also reported.
+    pub fn validate(&self) -> Vec<PaletteError> {
+        let mut errors = Vec::new();
+
+        for (idx, cell) in self.cells.iter() {
+            let mut refs = Vec::new();
+            cell.expr()
+                .for_each_cell_ref(|cell_ref| refs.push(cell_ref.clone()));
+
+            let mut dangling = false;
+            for cell_ref in refs {
+                match self.resolve_ref_to_index(&cell_ref) {
+                    Ok(ref_idx) if self.cells.contains_key(&ref_idx) => (),
+                    _ => {
+                        dangling = true;
+                        errors.push(PaletteError::UndefinedCellReference {
+                            cell_ref,
+                        });
+                    },
+                }
+            }
+
+            // Dangling references have already been reported, so only check
+            // for circular or otherwise undefined colors.
+            if !dangling {
+                if let Err(e) = self.color(&CellRef::Index(*idx)) {
+                    errors.push(e);
+                }
+            }
+        }
+
+        for (position, idx) in self.positions.iter() {
+            if !self.cells.contains_key(idx) {
+                errors.push(PaletteError::UndefinedCellReference {
+                    cell_ref: CellRef::Position(*position),
+                });
+            }
+        }
+
+        for (name, selector) in self.names.iter() {
+            let assigned_idx = Position::try_from(*selector)
+                .ok()
+                .and_then(|pos| self.positions.get_left(&pos));
+            if let Some(idx) = assigned_idx {
+                if !self.cells.contains_key(idx) {
+                    errors.push(PaletteError::UndefinedCellReference {
+                        cell_ref: CellRef::Name(name.clone()),
+                    });
+                }
+            }
+        }
+
+        for (group, members) in self.groups.iter() {
+            for (group_idx, idx) in members.iter().enumerate() {
+                if !self.cells.contains_key(idx) {
+                    match group_index(group, group_idx) {
+                        Ok(group_idx) => errors.push(
+                            PaletteError::UndefinedCellReference {
+                                cell_ref: CellRef::Group {
+                                    group: group.clone(),
+                                    idx: group_idx,
+                                },
+                            }),
+                        Err(e) => errors.push(e),
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Diffing
+    ////////////////////////////////////////////////////////////////////////////
+
+    /// Returns the `Operation`s which will transform the palette into the
+    /// given palette when applied with [`apply_operations`].
+    ///
+    /// No operations exist for the palette's next index or name case
+    /// sensitivity, so differences in these settings are not included.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if a group in the given palette has too many members
+    /// to be indexed.
+    ///
+    /// [`apply_operations`]: #method.apply_operations
+    pub fn diff(&self, other: &BasicPalette)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        self.diff_operations_iter(other).collect()
+    }
+
+    /// Returns an iterator over the `Operation`s which will transform the
+    /// palette into the given palette. The operations are generated lazily.
+    ///
+    /// No operations exist for the palette's next index or name case
+    /// sensitivity, so differences in these settings are not included.
+    pub fn diff_operations_iter<'a>(&'a self, other: &'a BasicPalette)
+        -> impl Iterator<Item=Result<Operation, PaletteError>> + 'a
+    {
+        use Operation::*;
+
+        // Locked cells must be unlocked before they are modified, and
+        // relocked after.
+        let lock_touched = move |idx: &u32| {
+            match (self.cells.get(idx), other.cells.get(idx)) {
+                (Some(cell_a), Some(cell_b)) => {
+                    cell_a.expr() != cell_b.expr()
+                        || cell_a.description() != cell_b.description()
+                        || cell_a.tags() != cell_b.tags()
+                        || cell_a.override_color() != cell_b.override_color()
+                        || self.positions.get_right(idx)
+                            != other.positions.get_right(idx)
+                        || self.groups.iter().any(|(group, members)|
+                            members.contains(idx)
+                                && other.groups.get(group) != Some(members))
+                        || other.groups.iter().any(|(group, members)|
+                            members.contains(idx)
+                                && self.groups.get(group) != Some(members))
+                },
+                _ => true,
+            }
+        };
+        let unlock_ops = self.cells
+            .iter()
+            .filter(move |(idx, cell)| cell.is_locked() && (lock_touched(idx)
+                || !other.cells.get(idx).map(Cell::is_locked).unwrap_or(false)))
+            .map(|(idx, _)| UnlockCell { cell_ref: CellRef::Index(*idx) });
+        let lock_ops = other.cells
+            .iter()
+            .filter(move |(idx, cell)| cell.is_locked() && (lock_touched(idx)
+                || !self.cells.get(idx).map(Cell::is_locked).unwrap_or(false)))
+            .map(|(idx, _)| LockCell { cell_ref: CellRef::Index(*idx) });
+
+        // Walk both cell maps in index order.
+        let mut self_cells = self.cells.iter().peekable();
+        let mut other_cells = other.cells.iter().peekable();
+        let cell_ops = std::iter::from_fn(move || loop {
+            let next = (
+                self_cells.peek().map(|(idx, _)| **idx),
+                other_cells.peek().map(|(idx, _)| **idx));
+            match next {
+                (None, None) => return None,
+
+                (Some(a), Some(b)) if a == b => {
+                    let (_, cell_a) = self_cells.next()?;
+                    let (_, cell_b) = other_cells.next()?;
+                    if cell_a.expr() != cell_b.expr()
+                        || cell_a.description() != cell_b.description()
+                        || cell_a.tags() != cell_b.tags()
+                        || cell_a.override_color() != cell_b.override_color()
+                    {
+                        return Some(InsertCell {
+                            idx: b,
+                            cell: Cell::new_with_expr(cell_b.expr().clone())
+                                .with_description(
+                                    cell_b.description().clone())
+                                .with_tags(cell_b.tags().clone())
+                                .with_override_color(
+                                    cell_b.override_color().cloned()),
+                        });
+                    }
+                },
+
+                (Some(a), Some(b)) if a > b => {
+                    let (_, cell_b) = other_cells.next()?;
+                    return Some(InsertCell {
+                        idx: b,
+                        cell: Cell::new_with_expr(cell_b.expr().clone())
+                            .with_description(cell_b.description().clone())
+                            .with_tags(cell_b.tags().clone())
+                            .with_override_color(
+                                cell_b.override_color().cloned()),
+                    });
+                },
+                (None, Some(b)) => {
+                    let (_, cell_b) = other_cells.next()?;
+                    return Some(InsertCell {
+                        idx: b,
+                        cell: Cell::new_with_expr(cell_b.expr().clone())
+                            .with_description(cell_b.description().clone())
+                            .with_tags(cell_b.tags().clone())
+                            .with_override_color(
+                                cell_b.override_color().cloned()),
+                    });
+                },
+
+                (Some(a), _) => {
+                    let _ = self_cells.next()?;
+                    return Some(RemoveCell { cell_ref: CellRef::Index(a) });
+                },
+            }
+        });
+
+        // Remove stale assignments.
+        let position_unassign_ops = self.positions
+            .iter()
+            .filter(move |(pos, idx)| other.positions.get_left(pos) != Some(idx))
+            .map(|(_, idx)| UnassignPosition { cell_ref: CellRef::Index(*idx) });
+        let name_unassign_ops = self.names
+            .iter()
+            .filter(move |(name, sel)| other.names.get_left(name) != Some(sel))
+            .map(|(_, sel)| UnassignName { selector: *sel });
+        let group_unassign_ops = self.groups
+            .iter()
+            .filter(move |(group, members)| other.groups
+                .get(*group) != Some(members))
+            .flat_map(|(group, members)| members
+                .iter()
+                .map(move |idx| UnassignGroup {
+                    cell_ref: CellRef::Index(*idx),
+                    group: group.clone(),
+                }));
+
+        // Add new assignments.
+        let position_assign_ops = other.positions
+            .iter()
+            .filter(move |(pos, idx)| self.positions.get_left(pos) != Some(idx))
+            .map(|(pos, idx)| AssignPosition {
+                cell_ref: CellRef::Index(*idx),
+                position: *pos,
+            });
+        let name_assign_ops = other.names
+            .iter()
+            .filter(move |(name, sel)| self.names.get_left(name) != Some(sel))
+            .map(|(name, sel)| AssignName {
+                selector: *sel,
+                name: name.clone(),
+            });
+        let group_assign_ops = other.groups
+            .iter()
+            .filter(move |(group, members)| self.groups
+                .get(*group) != Some(members))
+            .flat_map(|(group, members)| members
+                .iter()
+                .enumerate()
+                .map(move |(group_idx, idx)| group_index(group, group_idx)
+                    .map(|group_idx| AssignGroup {
+                        cell_ref: CellRef::Index(*idx),
+                        group: group.clone(),
+                        idx: Some(group_idx),
+                    })));
+
+        let cursor_op = Some(SetPositionCursor {
+                position: other.position_cursor,
+            })
+            .filter(move |_| self.position_cursor != other.position_cursor);
+
+        unlock_ops
+            .chain(cell_ops)
+            .chain(position_unassign_ops)
+            .chain(name_unassign_ops)
+            .chain(group_unassign_ops)
+            .chain(position_assign_ops)
+            .chain(name_assign_ops)
+            .map(Ok)
+            .chain(group_assign_ops)
+            .chain(cursor_op.into_iter().chain(lock_ops).map(Ok))
+    }
+
+
     ////////////////////////////////////////////////////////////////////////////
     // Composite operation interface
     ////////////////////////////////////////////////////////////////////////////
@@ -583,6 +2170,33 @@ impl BasicPalette {
         Ok(())
     }
 
+    /// Applies an `Operation` to each cell in the given selection.
+    ///
+    /// The selection is resolved once, and the given function is called with
+    /// each selected index to generate the operation for that cell. The
+    /// generated operations' undo ops will be grouped together and inserted
+    /// into the provided `History`.
+    ///
+    /// ### Parameters
+    /// + `selection`: The cells to apply the operations to.
+    /// + `f`: A function generating an operation for a selected index.
+    /// + `history`: The operation history.
+    pub fn apply_to_selection<'name, F>(
+        &mut self,
+        selection: &CellSelection<'name>,
+        f: F,
+        history: Option<&mut History>)
+        -> Result<(), PaletteError>
+        where F: Fn(u32) -> Operation
+    {
+        let ops: Vec<Operation> = selection
+            .resolve(self)
+            .iter()
+            .map(f)
+            .collect();
+        self.apply_operations(&ops, history)
+    }
+
     /// Unapplies the latest set of operations recorded in the given `History`.
     ///
     /// Returns the number of undo operations successfully performed. This may
@@ -602,7 +2216,7 @@ impl BasicPalette {
             history.undo_with(|undo_ops| {
                 let mut redo_ops = Vec::with_capacity(undo_ops.len());
                 for op in undo_ops {
-                    redo_ops.extend(self.apply_operation(op)
+                    redo_ops.extend(self.apply_operation_forced(op)
                         .expect("undo from valid state"));
                 }
                 real_count += 1;
@@ -632,7 +2246,7 @@ impl BasicPalette {
             history.redo_with(|redo_ops| {
                 let mut undo_ops = Vec::with_capacity(redo_ops.len());
                 for op in redo_ops {
-                    undo_ops.extend(self.apply_operation(op)
+                    undo_ops.extend(self.apply_operation_forced(op)
                         .expect("redo from valid state"));
                 }
                 real_count += 1;
@@ -642,28 +2256,152 @@ impl BasicPalette {
         real_count
     }
 
-
-    ////////////////////////////////////////////////////////////////////////////
-    // Primitive operation interface
-    ////////////////////////////////////////////////////////////////////////////
+    /// Unapplies the latest set of operations recorded in the given `History`,
+    /// returning an error instead of panicking if the history and palette are
+    /// not synchronized.
+    ///
+    /// Returns the number of undo operations successfully performed. This may
+    /// be fewer than the number provided if there are fewer undo operations
+    /// recorded than requested.
+    ///
+    /// ### Errors
+    ///
+    /// If an undo operation cannot be applied, the partially applied set of
+    /// operations is rolled back and the error is returned. Any sets of undo
+    /// operations performed before the failure remain undone, and the failed
+    /// set remains in the history as the next available undo.
+    pub fn try_undo(&mut self, history: &mut History, count: usize)
+        -> Result<usize, PaletteError>
+    {
+        let mut real_count = 0;
+        for _ in 0..count {
+            if !history.try_undo_with(|undo_ops| self
+                .apply_operations_or_rollback(undo_ops))?
+            {
+                break;
+            }
+            real_count += 1;
+        }
+        Ok(real_count)
+    }
+
+    /// Reapplies the latest set of undone operations, as recorded in the given
+    /// `History`, returning an error instead of panicking if the history and
+    /// palette are not synchronized.
+    ///
+    /// Returns the number of redo operations successfully performed. This may
+    /// be fewer than the number provided if there are fewer redo operations
+    /// recorded than requested.
+    ///
+    /// ### Errors
+    ///
+    /// If a redo operation cannot be applied, the partially applied set of
+    /// operations is rolled back and the error is returned. Any sets of redo
+    /// operations performed before the failure remain redone, and the failed
+    /// set remains in the history as the next available redo.
+    pub fn try_redo(&mut self, history: &mut History, count: usize)
+        -> Result<usize, PaletteError>
+    {
+        let mut real_count = 0;
+        for _ in 0..count {
+            if !history.try_redo_with(|redo_ops| self
+                .apply_operations_or_rollback(redo_ops))?
+            {
+                break;
+            }
+            real_count += 1;
+        }
+        Ok(real_count)
+    }
+
+    /// Applies the given operations in order, returning the operations which
+    /// will reverse them. If any operation fails, the previously applied
+    /// operations are reversed before returning the error.
+    fn apply_operations_or_rollback(&mut self, ops: &[Operation])
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        let mut inverse_ops = Vec::with_capacity(ops.len());
+        for op in ops {
+            match self.apply_operation_forced(op) {
+                Ok(inverse) => inverse_ops.push(inverse),
+                Err(e) => {
+                    for inverse in inverse_ops.iter().rev() {
+                        for op in inverse {
+                            let _ = self.apply_operation_forced(op);
+                        }
+                    }
+                    return Err(e);
+                },
+            }
+        }
+        Ok(inverse_ops.into_iter().flatten().collect())
+    }
+
+    /// Applies a sequence of `Operation`s to the palette, ignoring any cell
+    /// locks.
+    ///
+    /// The applied operations' undo ops will be grouped together and inserted
+    /// into the provided `History`.
+    ///
+    /// ### Parameters
+    /// + `op`: The operation to apply.
+    /// + `history`: The operation history.
+    pub fn apply_operations_forced(
+        &mut self,
+        ops: &[Operation],
+        history: Option<&mut History>)
+        -> Result<(), PaletteError>
+    {
+        let ignore_locks = std::mem::replace(&mut self.ignore_locks, true);
+        let res = self.apply_operations(ops, history);
+        self.ignore_locks = ignore_locks;
+        res
+    }
+
+    /// Applies an `Operation` to the palette, ignoring any cell locks. Returns
+    /// an `Operation` that will undo the applied changes.
+    fn apply_operation_forced(&mut self, op: &Operation)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        let ignore_locks = std::mem::replace(&mut self.ignore_locks, true);
+        let res = self.apply_operation(op);
+        self.ignore_locks = ignore_locks;
+        res
+    }
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Primitive operation interface
+    ////////////////////////////////////////////////////////////////////////////
 
     /// Applies an `Operation` to the palette. Returns an `Operation` that will
-    /// undo the applied changes.
+    /// undo the applied changes. If the palette has a journal, the operation
+    /// is appended to it.
     ///
     /// ### Parameters
     /// + `op`: The operation to apply.
+    ///
+    /// ### Errors
+    ///
+    /// Returns a `JournalWriteFailed` error if the operation was applied but
+    /// could not be appended to the palette's journal.
     pub fn apply_operation(&mut self, op: &Operation) 
         -> Result<Vec<Operation>, PaletteError>
     {
         use Operation::*;
-        match op {
+        let revision = self.revision;
+        let res = match op {
             InsertCell { idx, cell }
                 => self.insert_cell(*idx, cell.clone()),
             RemoveCell { cell_ref }
                 => self.remove_cell(cell_ref.clone()),
+            SwapCells { a, b }
+                => self.swap_cells(a.clone(), b.clone()),
+            MoveCell { from, to }
+                => self.move_cell(from.clone(), *to),
 
             AssignName { selector, name } 
-                => self.assign_name(name.clone(), selector.clone()),
+                => self.assign_name(name.clone(), selector.clone(), true),
             UnassignName { selector } 
                 => self.unassign_name(selector.clone()),
 
@@ -678,21 +2416,53 @@ impl BasicPalette {
                 => self.unassign_group(cell_ref.clone(), group.clone()),
             ClearGroups { cell_ref } 
                 => self.clear_groups(cell_ref.clone()),
+            RenameGroup { from, to }
+                => self.rename_group(from.clone(), to.clone()),
 
             SetExpr { cell_ref, expr }
                 => self.set_expr(cell_ref.clone(), expr.clone()),
+            SetDescription { cell_ref, description }
+                => self.set_description(
+                    cell_ref.clone(),
+                    description.clone()),
+            AddTag { cell_ref, tag }
+                => self.add_tag(cell_ref.clone(), tag.clone()),
+            RemoveTag { cell_ref, tag }
+                => self.remove_tag(cell_ref.clone(), tag),
+            LockCell { cell_ref }
+                => self.lock_cell(cell_ref.clone()),
+            UnlockCell { cell_ref }
+                => self.unlock_cell(cell_ref.clone()),
+            SetOverride { cell_ref, color }
+                => self.set_override(cell_ref.clone(), color.clone()),
+            ClearOverride { cell_ref }
+                => self.clear_override(cell_ref.clone()),
 
             SetPositionCursor { position }
                 => Ok(vec![SetPositionCursor {
                     position: self.set_position_cursor(*position),
                 }]),
+        };
+        // Count each applied operation once, regardless of how many times
+        // the primitive operations incremented the revision.
+        if res.is_ok() { self.revision = revision.wrapping_add(1); }
+
+        if let (Ok(_), Some(journal)) = (&res, &self.journal) {
+            journal
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .append(op)
+                .map_err(|source| PaletteError::JournalWriteFailed { source })?;
         }
+        res
     }
 
     /// Inserts a `Cell` into the palette at the given index.
     pub fn insert_cell(&mut self, idx: u32, cell: Cell)
         -> Result<Vec<Operation>, PaletteError>
     {
+        self.check_unlocked(idx)?;
+        self.bump_revision();
         match self.cells.insert(idx, cell) {
             // No cell was replaced.
             None => Ok(vec![
@@ -707,35 +2477,240 @@ impl BasicPalette {
         }
     }
 
+    /// Inserts a `Cell` holding the given color into the palette at the given
+    /// index.
+    pub fn insert_color(&mut self, idx: u32, color: Color)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        self.insert_cell(idx, Cell::new_with_expr(Expr::Color(color)))
+    }
+
+    /// Inserts a `Cell` holding a reference to the given cell into the palette
+    /// at the given index.
+    pub fn insert_reference<'name>(
+        &mut self,
+        idx: u32,
+        cell_ref: CellRef<'name>)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        self.insert_cell(idx, Cell::new_with_expr(
+            Expr::Reference(cell_ref.into_static())))
+    }
+
     /// Removes a `Cell` from the palette.
     pub fn remove_cell<'name>(&mut self, cell_ref: CellRef<'name>)
         -> Result<Vec<Operation>, PaletteError> 
     {
         let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+        self.check_unlocked(idx)?;
         
         match self.cells.remove(&idx) {
             // Cell was removed.
-            Some(cell) => Ok(vec![
-                Operation::InsertCell { idx, cell },
-            ]),
+            Some(cell) => {
+                self.bump_revision();
+                Ok(vec![
+                    Operation::InsertCell { idx, cell },
+                ])
+            },
 
             // Cell is already missing.
             None => Ok(Vec::new()),
         }
     }
 
+    /// Swaps the `Cell`s at the referenced indices. Positions, names, and
+    /// groups remain assigned to their indices, so they will refer to the
+    /// swapped cells. If only one of the indices is occupied, its `Cell` is
+    /// moved to the other index.
+    pub fn swap_cells<'a, 'b>(&mut self, a: CellRef<'a>, b: CellRef<'b>)
+        -> Result<Vec<Operation>, PaletteError> 
+    {
+        let a = BasicPalette::resolve_ref_to_index(&self, &a)?;
+        let b = BasicPalette::resolve_ref_to_index(&self, &b)?;
+        if a == b { return Ok(Vec::new()); }
+        self.check_unlocked(a)?;
+        self.check_unlocked(b)?;
+
+        let cell_a = self.cells.remove(&a);
+        let cell_b = self.cells.remove(&b);
+        if cell_a.is_none() && cell_b.is_none() { return Ok(Vec::new()); }
+        self.bump_revision();
+
+        if let Some(cell) = cell_a {
+            let _ = self.cells.insert(b, cell);
+        }
+        if let Some(cell) = cell_b {
+            let _ = self.cells.insert(a, cell);
+        }
+
+        Ok(vec![
+            Operation::SwapCells {
+                a: CellRef::Index(a),
+                b: CellRef::Index(b),
+            },
+        ])
+    }
+
+    /// Moves the referenced `Cell` to the given index. The cell's position and
+    /// group memberships are moved with it, so any names resolving through
+    /// its position will also follow it. `CellRef::Index` references to the
+    /// `Cell`'s old index will no longer resolve to it.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the referenced `Cell` does not exist, or if the
+    /// given index is already occupied by a different `Cell` or is assigned a
+    /// position or group.
+    pub fn move_cell<'name>(&mut self, from: CellRef<'name>, to: u32)
+        -> Result<Vec<Operation>, PaletteError> 
+    {
+        let from_idx = BasicPalette::resolve_ref_to_index(&self, &from)?;
+        if !self.cells.contains_key(&from_idx) {
+            return Err(PaletteError::UndefinedCellReference {
+                cell_ref: from.into_static(),
+            });
+        }
+        if from_idx == to { return Ok(Vec::new()); }
+        self.check_unlocked(from_idx)?;
+        if self.cells.contains_key(&to) {
+            return Err(PaletteError::InvalidInputValue {
+                msg: format!("cannot move cell {} to occupied index {}.",
+                    from, to).into(),
+            });
+        }
+        if self.positions.contains_right(&to)
+            || self.groups.values().any(|members| members.contains(&to))
+        {
+            return Err(PaletteError::InvalidInputValue {
+                msg: format!("cannot move cell {} to assigned index {}.",
+                    from, to).into(),
+            });
+        }
+
+        self.invalidate_reverse_index();
+        self.bump_revision();
+        let cell = self.cells
+            .remove(&from_idx)
+            .expect("remove occupied cell");
+        let _ = self.cells.insert(to, cell);
+
+        if let Some((position, _)) = self.positions.remove_by_right(&from_idx) {
+            let _ = self.positions.insert(position, to);
+        }
+        for members in self.groups.values_mut() {
+            for idx in members.iter_mut().filter(|idx| **idx == from_idx) {
+                *idx = to;
+            }
+        }
+
+        Ok(vec![
+            Operation::MoveCell {
+                from: CellRef::Index(to),
+                to: from_idx,
+            },
+        ])
+    }
+
+    /// Moves the palette's cells so that the occupied indices hold them in the
+    /// order given by `sorted_indices`. The set of occupied indices is
+    /// unchanged. Any `CellRef::Index` references within the cells'
+    /// expressions are updated to follow the moved cells. Positions, names,
+    /// and groups remain assigned to their indices, so they will refer to the
+    /// moved cells.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error without modifying the palette if any moved cell, or
+    /// any cell whose references must be updated, is locked.
+    pub fn reindex_sorted(&mut self, key: SortKey)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        let sorted = self.sorted_indices(key);
+        let index_map: BTreeMap<u32, u32> = sorted
+            .iter()
+            .zip(self.cells.keys())
+            .filter(|(idx, slot)| idx != slot)
+            .map(|(idx, slot)| (*idx, *slot))
+            .collect();
+        let remap = |cell_ref: &mut CellRef<'static>| {
+            if let CellRef::Index(idx) = cell_ref {
+                if let Some(slot) = index_map.get(&*idx) { *idx = *slot; }
+            }
+        };
+
+        let mut changed: Vec<(u32, Cell)> = Vec::new();
+        for (slot, idx) in self.cells.keys().zip(sorted.iter()) {
+            let mut cell = self.cells[idx].clone();
+            cell.expr_mut().for_each_cell_ref_mut(&remap);
+            if slot != idx || cell.expr() != self.cells[idx].expr() {
+                changed.push((*slot, cell));
+            }
+        }
+        // Check all changed cells before modifying the palette.
+        for (slot, _) in changed.iter() {
+            self.check_unlocked(*slot)?;
+        }
+
+        let mut ops = Vec::with_capacity(changed.len());
+        for (slot, cell) in changed {
+            ops.extend(self.insert_cell(slot, cell)?);
+        }
+        Ok(ops)
+    }
+
     /// Assigns a name to a position selector.
+    ///
+    /// If `force` is false and the name is already assigned to a different
+    /// position selector, the palette is not modified and an error is
+    /// returned. Otherwise, the name is removed from any other selector it is
+    /// assigned to.
     pub fn assign_name<T>(
         &mut self,
         name: T,
-        selector: PositionSelector)
+        selector: PositionSelector,
+        force: bool)
         -> Result<Vec<Operation>, PaletteError>
         where T: Into<Cow<'static, str>>
     {
-        let name = name.into();
+        let name = self.fold_name(name.into());
+        if !force {
+            match self.names.get_left(&name) {
+                Some(existing) if *existing != selector => {
+                    return Err(PaletteError::NameAlreadyAssigned {
+                        name,
+                        existing_selector: existing.clone(),
+                    });
+                },
+                _ => (),
+            }
+        }
 
+        self.bump_revision();
         use crate::bimap::Overwritten::*;
-        match self.names.insert(name.clone(), selector) {
+        let overwritten = self.names.insert(name.clone(), selector);
+        let positions = &self.positions;
+        ReverseIndex::update(&mut self.reverse_index, |reverse_index| {
+            match &overwritten {
+                Left(old_name, old_selector) |
+                Right(old_name, old_selector) |
+                Pair(old_name, old_selector) => reverse_index
+                    .remove_name(positions, old_name, old_selector),
+                Both(
+                    (old_name_a, old_selector_a),
+                    (old_name_b, old_selector_b)) =>
+                {
+                    reverse_index
+                        .remove_name(positions, old_name_a, old_selector_a);
+                    reverse_index
+                        .remove_name(positions, old_name_b, old_selector_b);
+                },
+                Neither => (),
+            }
+            reverse_index.insert_name(positions, &name, &selector);
+            Ok(())
+        });
+
+        match overwritten {
             Left(old_name, old_selector) |
             Right(old_name, old_selector) |
             Pair(old_name, old_selector) => Ok(vec![
@@ -772,12 +2747,20 @@ impl BasicPalette {
         -> Result<Vec<Operation>, PaletteError>
     {
         match self.names.remove_by_right(&selector) {
-            Some((name, _)) => Ok(vec![
-                Operation::AssignName {
-                    selector: selector,
-                    name,
-                },
-            ]),
+            Some((name, _)) => {
+                self.bump_revision();
+                let positions = &self.positions;
+                ReverseIndex::update(&mut self.reverse_index, |reverse_index| {
+                    reverse_index.remove_name(positions, &name, &selector);
+                    Ok(())
+                });
+                Ok(vec![
+                    Operation::AssignName {
+                        selector: selector,
+                        name,
+                    },
+                ])
+            },
             None => Ok(Vec::new()),
         }
     }
@@ -790,8 +2773,14 @@ impl BasicPalette {
         cell_ref: CellRef<'name>)
         -> Result<Vec<Operation>, PaletteError>
     {
+        self.invalidate_reverse_index();
         let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+        self.check_unlocked(idx)?;
+        if let Some(displaced) = self.positions.get_left(&position) {
+            self.check_unlocked(*displaced)?;
+        }
 
+        self.bump_revision();
         use crate::bimap::Overwritten::*;
         match self.positions.insert(position, idx) {
             Left(old_pos, old_idx) |
@@ -825,21 +2814,104 @@ impl BasicPalette {
         }
     }
 
+    /// Assigns consecutive positions along the given axis to each cell in the
+    /// selection, in index order, starting from the given position.
+    ///
+    /// No positions are assigned if any of the target positions is already
+    /// occupied or out of range, or if any of the selected cells is locked.
+    pub fn assign_positions_sequential(
+        &mut self,
+        selection: &CellIndexSelection,
+        start: Position,
+        axis: Axis)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        let mut assignments = Vec::new();
+        for (offset, idx) in selection.iter().enumerate() {
+            let position = u16::try_from(offset)
+                .ok()
+                .and_then(|offset| start.checked_offset(axis, offset))
+                .ok_or(PaletteError::AllPositionsAssigned)?;
+            if self.is_occupied_position(&position) {
+                return Err(PaletteError::InvalidInputValue {
+                    msg: format!("position {} is already occupied.", position)
+                        .into(),
+                });
+            }
+            self.check_unlocked(idx)?;
+            assignments.push((position, idx));
+        }
+
+        let mut ops = Vec::with_capacity(assignments.len());
+        for (position, idx) in assignments {
+            ops.extend(self.assign_position(position, CellRef::Index(idx))?);
+        }
+        Ok(ops)
+    }
+
+    /// Assigns positions to every occupied cell which has no assigned
+    /// position, in index order. Positions are filled row by row within a grid
+    /// of the given number of columns and lines, moving to the next page when
+    /// the grid is full. Positions which are already assigned are skipped.
+    ///
+    /// Returns the operations which undo the assignments.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error without modifying the palette if any cell without an
+    /// assigned position is locked.
+    pub fn auto_assign_positions(&mut self, columns: u16, lines: u16)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        let mut ops = Vec::new();
+        if columns == 0 || lines == 0 { return Ok(ops); }
+
+        let unpositioned: Vec<u32> = self.occupied_indices()
+            .filter(|idx| !self.positions.contains_right(idx))
+            .collect();
+        // Check all unpositioned cells before modifying the palette.
+        for idx in unpositioned.iter() {
+            self.check_unlocked(*idx)?;
+        }
+
+        let mut position = Some(Position::ZERO);
+        for idx in unpositioned {
+            while let Some(pos) = position
+                .filter(|pos| self.is_assigned_position(pos))
+            {
+                position = next_grid_position(pos, columns, lines);
+            }
+            let pos = match position {
+                Some(pos) => pos,
+                None      => break,
+            };
+
+            ops.extend(self.assign_position(pos, CellRef::Index(idx))?);
+            position = next_grid_position(pos, columns, lines);
+        }
+        Ok(ops)
+    }
+
     /// Unassigns a position for a cell.
     pub fn unassign_position<'name>(
         &mut self,
         cell_ref: CellRef<'name>)
         -> Result<Vec<Operation>, PaletteError>
     {
+        self.invalidate_reverse_index();
         let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+        self.check_unlocked(idx)?;
         
         match self.positions.remove_by_right(&idx) {
-            Some((position, _)) => Ok(vec![
-                Operation::AssignPosition {
-                    cell_ref: CellRef::Index(idx),
-                    position,
-                },
-            ]),
+            Some((position, _)) => {
+                self.bump_revision();
+                Ok(vec![
+                    Operation::AssignPosition {
+                        cell_ref: CellRef::Index(idx),
+                        position,
+                    },
+                ])
+            },
             None => Ok(Vec::new()),
         }
     }
@@ -855,18 +2927,31 @@ impl BasicPalette {
     {
         let group = group.into();
         let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+        self.check_unlocked(idx)?;
 
         let members = self.groups.entry(group.clone()).or_default();
-        let members_len: u32 = members.len()
-            .try_into()
-            .expect("convert usize to u32");
+        let members_len: u32 = match members.len().try_into() {
+            Ok(len) => len,
+            Err(_) => return Err(PaletteError::InvalidInputValue {
+                msg: format!("group {} has too many members.", group).into(),
+            }),
+        };
         let group_idx = group_idx.unwrap_or(members_len);
         
         if group_idx <= members_len {    
-            let group_idx_usize: usize = group_idx.try_into()
-                .expect("convert u32 to usize");
+            let group_idx_usize: usize = match group_idx.try_into() {
+                Ok(group_idx_usize) => group_idx_usize,
+                Err(_) => return Err(PaletteError::GroupIndexOutOfBounds {
+                    group,
+                    index: group_idx,
+                    max: members_len,
+                }),
+            };
             
             members.insert(group_idx_usize, idx);
+            ReverseIndex::update(&mut self.reverse_index, |reverse_index|
+                reverse_index.update_group(&group, &[], &members[..]));
+            self.bump_revision();
             Ok(vec![
                 Operation::UnassignGroup { 
                     cell_ref: CellRef::Index(idx),
@@ -896,21 +2981,31 @@ impl BasicPalette {
     {
         let group = group.into();
         let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+        self.check_unlocked(idx)?;
         
         let res = match self.groups.get_mut(&group) {
             Some(members) => match members.iter().position(|x| *x == idx) {
-                Some(group_idx) => {
-                    let _ = members.remove(group_idx);
-                    Ok(vec![
-                        Operation::AssignGroup {
-                            cell_ref: CellRef::Index(idx),
-                            group: group.clone(),
-                            idx: Some(group_idx
-                                .try_into()
-                                .expect("convert usize to u32")),
-                        },
-                    ])
-                }
+                Some(group_idx) => match u32::try_from(group_idx) {
+                    Ok(undo_idx) => {
+                        let _ = members.remove(group_idx);
+                        ReverseIndex::update(
+                            &mut self.reverse_index,
+                            |reverse_index| reverse_index
+                                .update_group(&group, &[idx], &members[..]));
+                        self.revision = self.revision.wrapping_add(1);
+                        Ok(vec![
+                            Operation::AssignGroup {
+                                cell_ref: CellRef::Index(idx),
+                                group: group.clone(),
+                                idx: Some(undo_idx),
+                            },
+                        ])
+                    },
+                    Err(_) => Err(PaletteError::InvalidInputValue {
+                        msg: format!("group {} has too many members.", group)
+                            .into(),
+                    }),
+                },
                 None => Ok(Vec::new()),
             },
             None => Ok(Vec::new()),
@@ -927,8 +3022,18 @@ impl BasicPalette {
     pub fn clear_groups<'name>(&mut self, cell_ref: CellRef<'name>)
         -> Result<Vec<Operation>, PaletteError>
     {
+        self.invalidate_reverse_index();
         let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+        self.check_unlocked(idx)?;
+
+        // Check the group indices before modifying the palette.
+        for (group, members) in self.groups.iter() {
+            if let Some(group_idx) = members.iter().position(|x| *x == idx) {
+                let _ = group_index(group, group_idx)?;
+            }
+        }
 
+        self.bump_revision();
         // TODO: Consider using BTreeMap::drain_filter when it becomes stable.
         let mut empty_groups = Vec::new();
         let mut ops = Vec::new();
@@ -938,9 +3043,7 @@ impl BasicPalette {
                 ops.push(Operation::AssignGroup {
                     cell_ref: CellRef::Index(idx),
                     group: group.clone(),
-                    idx: Some(group_idx
-                        .try_into()
-                        .expect("convert usize to u32")),
+                    idx: Some(group_index(group, group_idx)?),
                 });
             }
 
@@ -956,16 +3059,151 @@ impl BasicPalette {
         Ok(ops)
     }
 
+    /// Assigns each occupied cell to a group named for the `HueCategory` of
+    /// its color, e.g., "red" or "blue". Cells without a color, cells with an
+    /// achromatic color, and cells already in their category's group are
+    /// skipped.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error without modifying the palette if any cell to be
+    /// grouped is locked.
+    pub fn group_by_hue(&mut self) -> Result<Vec<Operation>, PaletteError> {
+        let mut assignments = Vec::new();
+        for idx in self.cells.keys() {
+            let category = match self.color(&CellRef::Index(*idx)) {
+                Ok(Some(color)) => color.hue_category(),
+                _               => None,
+            };
+            if let Some(category) = category {
+                let group = category.to_string();
+                let already_member = self.groups
+                    .get(&*group)
+                    .map_or(false, |members| members.contains(idx));
+                if !already_member {
+                    self.check_unlocked(*idx)?;
+                    assignments.push((*idx, group));
+                }
+            }
+        }
+
+        let mut ops = Vec::with_capacity(assignments.len());
+        for (idx, group) in assignments {
+            ops.extend(self.assign_group(CellRef::Index(idx), group, None)?);
+        }
+        Ok(ops)
+    }
+
+    /// Renames a group, preserving the order of its members.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the group `to` already exists.
+    pub fn rename_group<F, T>(&mut self, from: F, to: T)
+        -> Result<Vec<Operation>, PaletteError>
+        where
+            F: Into<Cow<'static, str>>,
+            T: Into<Cow<'static, str>>,
+    {
+        self.invalidate_reverse_index();
+        let from = from.into();
+        let to = to.into();
+        if from == to || !self.groups.contains_key(&from) {
+            return Ok(Vec::new());
+        }
+        if self.groups.contains_key(&to) {
+            return Err(PaletteError::InvalidInputValue {
+                msg: format!("group {} already exists.", to).into(),
+            });
+        }
+        for idx in self.groups[&from].iter() {
+            self.check_unlocked(*idx)?;
+        }
+
+        self.bump_revision();
+        let members = self.groups
+            .remove(&from)
+            .expect("remove existing group");
+        let _ = self.groups.insert(to.clone(), members);
+
+        Ok(vec![
+            Operation::RenameGroup { from: to, to: from },
+        ])
+    }
+
+    /// Removes all cells, names, positions, and groups from the palette, and
+    /// resets the position cursor. Locked cells are also removed.
+    ///
+    /// The returned operations restore the palette when applied with
+    /// [`apply_operations`]. Locked cells are restored unlocked and relocked
+    /// after their positions and groups are assigned.
+    ///
+    /// [`apply_operations`]: BasicPalette::apply_operations
+    pub fn clear_all(&mut self) -> Vec<Operation> {
+        self.invalidate_reverse_index();
+        self.bump_revision();
+        let mut ops = Vec::with_capacity(self.cells.len()
+            + self.names.iter().count()
+            + self.positions.iter().count());
+        let mut locked = Vec::new();
+
+        let cells = std::mem::take(&mut self.cells);
+        for (idx, mut cell) in cells.into_iter() {
+            if cell.set_locked(false) { locked.push(idx); }
+            ops.push(Operation::InsertCell { idx, cell });
+        }
+
+        let names = std::mem::replace(&mut self.names, BiMap::new());
+        for (name, selector) in names.iter() {
+            ops.push(Operation::AssignName {
+                selector: *selector,
+                name: name.clone(),
+            });
+        }
+
+        let positions = std::mem::replace(&mut self.positions, BiMap::new());
+        for (position, idx) in positions.iter() {
+            ops.push(Operation::AssignPosition {
+                cell_ref: CellRef::Index(*idx),
+                position: *position,
+            });
+        }
+
+        // Members are appended in order, so no group index is needed.
+        let groups = std::mem::take(&mut self.groups);
+        for (group, members) in groups.into_iter() {
+            for idx in members.into_iter() {
+                ops.push(Operation::AssignGroup {
+                    cell_ref: CellRef::Index(idx),
+                    group: group.clone(),
+                    idx: None,
+                });
+            }
+        }
+
+        ops.push(Operation::SetPositionCursor {
+            position: self.set_position_cursor(Position::ZERO),
+        });
+
+        for idx in locked {
+            ops.push(Operation::LockCell { cell_ref: CellRef::Index(idx) });
+        }
+
+        ops
+    }
+
     /// Sets the color expression for a `Cell`.
     pub fn set_expr<'name>(&mut self, cell_ref: CellRef<'name>, expr: Expr)
         -> Result<Vec<Operation>, PaletteError>
     {
         let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+        self.check_unlocked(idx)?;
 
         let cell = self.cells.get_mut(&idx)
             .expect("retreive resolved cell");
 
         let old = std::mem::replace(cell.expr_mut(), expr);
+        self.bump_revision();
 
         Ok(vec![
             Operation::SetExpr {
@@ -974,10 +3212,976 @@ impl BasicPalette {
             }
         ])
     }
-}
 
-impl Default for BasicPalette {
-    fn default() -> Self {
-        BasicPalette::new()
+    /// Sets the description for a `Cell`.
+    pub fn set_description<'name, T>(
+        &mut self,
+        cell_ref: CellRef<'name>,
+        description: T)
+        -> Result<Vec<Operation>, PaletteError>
+        where T: Into<Cow<'static, str>>
+    {
+        let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+        self.check_unlocked(idx)?;
+
+        let cell = self.cells.get_mut(&idx)
+            .ok_or(PaletteError::UndefinedCellReference {
+                cell_ref: cell_ref.into_static(),
+            })?;
+
+        let old = cell.set_description(description);
+        self.bump_revision();
+
+        Ok(vec![
+            Operation::SetDescription {
+                cell_ref: CellRef::Index(idx),
+                description: old,
+            }
+        ])
+    }
+
+    /// Adds a tag to a `Cell`.
+    pub fn add_tag<'name, T>(&mut self, cell_ref: CellRef<'name>, tag: T)
+        -> Result<Vec<Operation>, PaletteError>
+        where T: Into<Cow<'static, str>>
+    {
+        let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+        self.check_unlocked(idx)?;
+
+        let cell = self.cells.get_mut(&idx)
+            .ok_or(PaletteError::UndefinedCellReference {
+                cell_ref: cell_ref.into_static(),
+            })?;
+
+        let tag = tag.into();
+        if !cell.add_tag(tag.clone()) { return Ok(Vec::new()); }
+        self.bump_revision();
+
+        Ok(vec![
+            Operation::RemoveTag {
+                cell_ref: CellRef::Index(idx),
+                tag,
+            }
+        ])
+    }
+
+    /// Removes a tag from a `Cell`.
+    pub fn remove_tag<'name>(&mut self, cell_ref: CellRef<'name>, tag: &str)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+        self.check_unlocked(idx)?;
+
+        let cell = self.cells.get_mut(&idx)
+            .ok_or(PaletteError::UndefinedCellReference {
+                cell_ref: cell_ref.into_static(),
+            })?;
+
+        if !cell.remove_tag(tag) { return Ok(Vec::new()); }
+        self.bump_revision();
+
+        Ok(vec![
+            Operation::AddTag {
+                cell_ref: CellRef::Index(idx),
+                tag: Cow::from(tag.to_owned()),
+            }
+        ])
+    }
+
+    /// Locks a `Cell`, preventing changes to its expression, description,
+    /// tags, override color, positions, and groups, and preventing it from
+    /// being removed, moved, or replaced.
+    pub fn lock_cell<'name>(&mut self, cell_ref: CellRef<'name>)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+
+        let cell = self.cells.get_mut(&idx)
+            .ok_or(PaletteError::UndefinedCellReference {
+                cell_ref: cell_ref.into_static(),
+            })?;
+
+        if cell.set_locked(true) { return Ok(Vec::new()); }
+        self.bump_revision();
+
+        Ok(vec![
+            Operation::UnlockCell { cell_ref: CellRef::Index(idx) }
+        ])
+    }
+
+    /// Unlocks a `Cell`.
+    pub fn unlock_cell<'name>(&mut self, cell_ref: CellRef<'name>)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+
+        let cell = self.cells.get_mut(&idx)
+            .ok_or(PaletteError::UndefinedCellReference {
+                cell_ref: cell_ref.into_static(),
+            })?;
+
+        if !cell.set_locked(false) { return Ok(Vec::new()); }
+        self.bump_revision();
+
+        Ok(vec![
+            Operation::LockCell { cell_ref: CellRef::Index(idx) }
+        ])
+    }
+
+    /// Sets the override color for a `Cell`. The override color is used as
+    /// the cell's color in place of its expression until it is cleared.
+    pub fn set_override<'name>(
+        &mut self,
+        cell_ref: CellRef<'name>,
+        color: Color)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+        self.check_unlocked(idx)?;
+
+        let cell = self.cells.get_mut(&idx)
+            .ok_or(PaletteError::UndefinedCellReference {
+                cell_ref: cell_ref.into_static(),
+            })?;
+
+        let old = cell.set_override_color(Some(color));
+        self.bump_revision();
+        Ok(vec![override_undo_op(idx, old)])
+    }
+
+    /// Clears the override color for a `Cell`, so that its color is computed
+    /// from its expression.
+    pub fn clear_override<'name>(&mut self, cell_ref: CellRef<'name>)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        let idx = BasicPalette::resolve_ref_to_index(&self, &cell_ref)?;
+        self.check_unlocked(idx)?;
+
+        let cell = self.cells.get_mut(&idx)
+            .ok_or(PaletteError::UndefinedCellReference {
+                cell_ref: cell_ref.into_static(),
+            })?;
+
+        match cell.set_override_color(None) {
+            None => Ok(Vec::new()),
+            old  => {
+                self.bump_revision();
+                Ok(vec![override_undo_op(idx, old)])
+            },
+        }
+    }
+
+    /// Returns an error if the cell at the given index is locked and locks
+    /// are not being ignored.
+    fn check_unlocked(&self, idx: u32) -> Result<(), PaletteError> {
+        match self.cells.get(&idx) {
+            Some(cell) if cell.is_locked() && !self.ignore_locks
+                => Err(PaletteError::CellLocked { idx }),
+            _   => Ok(()),
+        }
+    }
+
+    /// Inserts the colors computed from the given `RampExpr` as new cells
+    /// holding concrete colors, using the first unoccupied indices at or after
+    /// the given index. Returns the indices of the inserted cells.
+    pub fn explode_ramp(&mut self, ramp: &RampExpr, start: u32)
+        -> Result<(Vec<u32>, Vec<Operation>), PaletteError>
+    {
+        // Resolve all colors before inserting any cells.
+        let mut exprs = Vec::with_capacity(ramp.count.into());
+        for blend_expr in ramp.blend_exprs() {
+            let mut index_list = HashSet::new();
+            exprs.push(match blend_expr.color(self, &mut index_list)? {
+                Some(color) => Expr::Color(color),
+                None        => Expr::Empty,
+            });
+        }
+
+        let mut indices = Vec::with_capacity(exprs.len());
+        let mut ops = Vec::with_capacity(exprs.len());
+        let mut next = start;
+        for expr in exprs {
+            let idx = self.unoccupied_index_or_next(next)
+                .ok_or(PaletteError::InvalidInputValue {
+                    msg: "no free indices remain in the palette.".into(),
+                })?;
+            ops.extend(self.insert_cell(idx, Cell::new_with_expr(expr))?);
+            indices.push(idx);
+            next = idx.wrapping_add(1);
+        }
+        Ok((indices, ops))
+    }
+
+    /// Inserts colors harmonizing with the referenced cell's color as new
+    /// cells holding concrete colors. The colors are generated by rotating
+    /// the base color's HSV hue by each of the `HarmonyScheme`'s angles, and
+    /// are inserted using the first unoccupied indices following the base
+    /// cell's index.
+    pub fn generate_harmony<'name>(
+        &mut self,
+        base: &CellRef<'name>,
+        scheme: HarmonyScheme)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        let base_idx = self.resolve_ref_to_index(base)?;
+        let color = self.color(base)?
+            .ok_or_else(|| PaletteError::UndefinedColor {
+                cell_ref: base.clone().into_static(),
+                circular: false,
+                referrer: None,
+            })?;
+        let hue = color.hsv_components()[0];
+
+        let mut ops = Vec::with_capacity(scheme.angles().len());
+        let mut next = base_idx.wrapping_add(1);
+        for angle in scheme.angles() {
+            let idx = self.unoccupied_index_or_next(next)
+                .ok_or(PaletteError::InvalidInputValue {
+                    msg: "no free indices remain in the palette.".into(),
+                })?;
+            let expr = Expr::Color(color.with_hue(hue + angle));
+            ops.extend(self.insert_cell(idx, Cell::new_with_expr(expr))?);
+            next = idx.wrapping_add(1);
+        }
+        Ok(ops)
+    }
+
+    /// Maps the luminance of each selected cell's color onto the gradient
+    /// between the given cells' colors, and sets the cell to the resulting
+    /// color. Cells without colors are skipped.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error without modifying the palette if any selected cell
+    /// with a color is locked, or if any color cannot be resolved.
+    pub fn colorize<'a, 'b>(
+        &mut self,
+        gradient_from: &CellRef<'a>,
+        gradient_to: &CellRef<'b>,
+        selection: &CellIndexSelection)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        let from = self.color(gradient_from)?
+            .ok_or_else(|| PaletteError::UndefinedColor {
+                cell_ref: gradient_from.clone().into_static(),
+                circular: false,
+                referrer: None,
+            })?;
+        let to = self.color(gradient_to)?
+            .ok_or_else(|| PaletteError::UndefinedColor {
+                cell_ref: gradient_to.clone().into_static(),
+                circular: false,
+                referrer: None,
+            })?;
+
+        // Resolve all colors before modifying any cells, as the selected cells
+        // may reference eachother.
+        let mut colorized = Vec::new();
+        for idx in selection.iter() {
+            if let Some(color) = self.color(&CellRef::Index(idx))? {
+                self.check_unlocked(idx)?;
+                let amount = color.relative_luminance();
+                colorized.push((idx, Color::from(Color::rgb_linear_interpolate(
+                    from.clone(),
+                    to.clone(),
+                    amount))));
+            }
+        }
+
+        let mut ops = Vec::with_capacity(colorized.len());
+        for (idx, color) in colorized {
+            ops.extend(self.set_expr(CellRef::Index(idx), Expr::Color(color))?);
+        }
+        Ok(ops)
+    }
+
+    /// Parses the given text as an expression and sets it as the color
+    /// expression for a `Cell`.
+    pub fn set_expr_from_str<'name>(
+        &mut self,
+        cell_ref: CellRef<'name>,
+        text: &str)
+        -> Result<Vec<Operation>, CommandError>
+    {
+        let insert_expr: InsertExpr = text.parse()?;
+        let mut exprs = insert_expr.exprs(self)?;
+        if exprs.len() > 1 {
+            return Err(CommandError::UnsupportedExpr {
+                msg: "ramp expressions cannot be set on a single cell".into(),
+            });
+        }
+        let expr = exprs.pop()
+            .ok_or(CommandError::UnsupportedExpr {
+                msg: "no expression to set".into(),
+            })?;
+
+        self.set_expr(cell_ref, expr)
+            .map_err(CommandError::from)
+    }
+
+    /// Merges the cells of the given palette into the palette.
+    ///
+    /// The merged cells are re-homed to unoccupied indices, starting at the
+    /// palette's next free index, plus the given offset. Assigned positions
+    /// are moved to the pages following the last assigned page of the
+    /// palette, and cells in groups shared by both palettes are appended to
+    /// the existing groups. Any `CellRef`s within the merged cells' expressions
+    /// are updated accordingly.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error without modifying the palette if any names are
+    /// assigned in both palettes, or if there is insufficient room to re-home
+    /// the merged cells.
+    pub fn merge(&mut self, other: BasicPalette, offset: Option<u32>)
+        -> Result<Vec<Operation>, PaletteError>
+    {
+        // Check for name collisions before modifying the palette.
+        let collisions: Vec<&str> = other.names
+            .iter()
+            .map(|(name, _)| name)
+            .filter(|name| self.resolve_name_ci(name).is_some())
+            .map(|name| name.as_ref())
+            .collect();
+        if !collisions.is_empty() {
+            return Err(PaletteError::InvalidInputValue {
+                msg: format!("names are assigned in both palettes: {}",
+                    collisions.join(", ")).into(),
+            });
+        }
+
+        // Determine the page shift for the merged positions.
+        let page_shift = match self.assigned_position_range() {
+            Few::Two(_, last) |
+            Few::One(last)     => last.page.checked_add(1),
+            Few::Zero          => Some(0),
+        };
+        let max_page = match other.assigned_position_range() {
+            Few::Two(_, last) |
+            Few::One(last)     => last.page,
+            Few::Zero          => 0,
+        };
+        let page_shift = page_shift
+            .filter(|shift| max_page.checked_add(*shift).is_some())
+            .ok_or(PaletteError::AllPositionsAssigned)?;
+
+        // Shift the merged names' selectors.
+        let mut names = Vec::with_capacity(other.names.len());
+        for (name, selector) in other.names.iter() {
+            let mut selector = *selector;
+            selector.page = selector.page
+                .map(|page| page
+                    .checked_add(page_shift)
+                    .ok_or(PaletteError::AllPositionsAssigned))
+                .transpose()?;
+            names.push((name.clone(), selector));
+        }
+
+        // Map every index used by the merged palette to an unoccupied index.
+        let mut used = other.cells.keys().copied().collect::<Vec<_>>();
+        used.extend(other.positions.iter().map(|(_, idx)| *idx));
+        used.extend(other.groups.values().flat_map(|m| m.iter().copied()));
+        used.sort_unstable();
+        used.dedup();
+
+        let mut index_map = BTreeMap::new();
+        let mut next = Some(self.next_index
+            .checked_add(offset.unwrap_or(0))
+            .ok_or(PaletteError::InvalidInputValue {
+                msg: "merge offset overflows the palette indices.".into(),
+            })?);
+        for idx in used {
+            // New indices are strictly increasing, so they cannot collide.
+            let new_idx = next
+                .and_then(|next| self.unoccupied_index_or_next(next)
+                    .filter(|new_idx| *new_idx >= next))
+                .ok_or(PaletteError::InvalidInputValue {
+                    msg: "no free indices remain in the palette.".into(),
+                })?;
+            let _ = index_map.insert(idx, new_idx);
+            next = new_idx.checked_add(1);
+        }
+
+        // Determine the index shift for groups shared by both palettes.
+        let mut group_shifts = BTreeMap::new();
+        for group in other.groups.keys() {
+            if let Some(members) = self.groups.get(group) {
+                let shift = u32::try_from(members.len())
+                    .map_err(|_| PaletteError::InvalidInputValue {
+                        msg: format!("group {} is too large to merge.", group)
+                            .into(),
+                    })?;
+                let _ = group_shifts.insert(group.clone(), shift);
+            }
+        }
+
+        let remap = |cell_ref: &mut CellRef<'static>| match cell_ref {
+            CellRef::Index(idx) => if let Some(new_idx) = index_map.get(&*idx) {
+                *idx = *new_idx;
+            },
+            CellRef::Position(pos) => {
+                pos.page = pos.page.saturating_add(page_shift);
+            },
+            CellRef::Group { group, idx } => {
+                if let Some(shift) = group_shifts.get(&*group) {
+                    *idx = idx.saturating_add(*shift);
+                }
+            },
+            CellRef::Name(_) => (),
+        };
+
+        let mut ops = Vec::new();
+        for (idx, cell) in other.cells.iter() {
+            let mut expr = cell.expr().clone();
+            expr.for_each_cell_ref_mut(&remap);
+            ops.extend(self.insert_cell(
+                index_map[idx],
+                Cell::new_with_expr(expr)
+                    .with_description(cell.description().clone())
+                    .with_tags(cell.tags().clone())
+                    .with_override_color(cell.override_color().cloned()))?);
+        }
+
+        for (position, idx) in other.positions.iter() {
+            let mut position = *position;
+            position.page += page_shift;
+            ops.extend(self.assign_position(
+                position,
+                CellRef::Index(index_map[idx]))?);
+        }
+
+        for (name, selector) in names {
+            ops.extend(self.assign_name(name, selector, false)?);
+        }
+
+        for (group, members) in other.groups.iter() {
+            for idx in members {
+                ops.extend(self.assign_group(
+                    CellRef::Index(index_map[idx]),
+                    group.clone(),
+                    None)?);
+            }
+        }
+
+        // Lock the merged cells last, so that their positions and groups can
+        // be assigned.
+        for (idx, cell) in other.cells.iter().filter(|(_, c)| c.is_locked()) {
+            ops.extend(self.lock_cell(CellRef::Index(index_map[idx]))?);
+        }
+
+        Ok(ops)
+    }
+}
+
+impl Default for BasicPalette {
+    fn default() -> Self {
+        BasicPalette::new()
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// SortKey
+////////////////////////////////////////////////////////////////////////////////
+/// A key for sorting palette cells by their colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+pub enum SortKey {
+    /// Sort by cell index.
+    Index,
+    /// Sort by HSV hue.
+    Hue,
+    /// Sort by WCAG relative luminance.
+    Luminance,
+    /// Sort by HSV saturation.
+    Saturation,
+}
+
+impl SortKey {
+    /// Returns the sort value for the given color.
+    fn value(&self, color: &Color) -> f32 {
+        match self {
+            // Index order is preserved by the stable sort.
+            SortKey::Index      => 0.0,
+            SortKey::Hue        => color.hsv_components()[0],
+            SortKey::Luminance  => color.relative_luminance(),
+            SortKey::Saturation => color.hsv_components()[1],
+        }
+    }
+}
+
+impl Default for SortKey {
+    fn default() -> Self {
+        SortKey::Index
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// HarmonyScheme
+////////////////////////////////////////////////////////////////////////////////
+/// A color harmony scheme, given by the hue rotations of its colors relative
+/// to a base color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+pub enum HarmonyScheme {
+    /// The color opposite the base color: 180°.
+    Complementary,
+    /// The colors evenly spaced with the base color: 120° and 240°.
+    Triadic,
+    /// The colors adjacent to the base color: 30° and 330°.
+    Analogous,
+    /// The colors adjacent to the base color's complement: 150° and 210°.
+    SplitComplementary,
+    /// The colors forming a square with the base color: 90°, 180°, and 270°.
+    Tetradic,
+}
+
+impl HarmonyScheme {
+    /// Returns the hue rotations of the scheme's colors in degrees.
+    pub fn angles(&self) -> &'static [f32] {
+        match self {
+            HarmonyScheme::Complementary      => &[180.0],
+            HarmonyScheme::Triadic            => &[120.0, 240.0],
+            HarmonyScheme::Analogous          => &[30.0, 330.0],
+            HarmonyScheme::SplitComplementary => &[150.0, 210.0],
+            HarmonyScheme::Tetradic           => &[90.0, 180.0, 270.0],
+        }
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Aliases
+////////////////////////////////////////////////////////////////////////////////
+/// The references which resolve to a palette index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aliases {
+    /// The resolved index.
+    pub index: u32,
+    /// The names whose position selectors select the index's position.
+    pub names: Vec<Cow<'static, str>>,
+    /// The position assigned to the index. Positions are assigned one-to-one,
+    /// so there is at most one.
+    pub position: Option<Position>,
+    /// The groups containing the index, with the index's group position.
+    pub groups: Vec<(Cow<'static, str>, u32)>,
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PaletteWriteConfig
+////////////////////////////////////////////////////////////////////////////////
+/// Formatting options for writing a `BasicPalette` in the RON format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteWriteConfig {
+    /// Whether to pretty-print the output. If false, the palette is written
+    /// on a single line and the remaining options are ignored.
+    pub pretty: bool,
+    /// The maximum nesting depth at which values are written on separate
+    /// lines. More deeply nested values are written inline.
+    pub depth_limit: usize,
+    /// The string used for each level of indentation.
+    pub indentor: String,
+    /// Whether to annotate array elements with their indices.
+    pub enumerate_arrays: bool,
+}
+
+impl PaletteWriteConfig {
+    /// Returns a `PaletteWriteConfig` for compact, single-line output.
+    pub fn compact() -> Self {
+        PaletteWriteConfig {
+            pretty: false,
+            .. Default::default()
+        }
+    }
+}
+
+impl Default for PaletteWriteConfig {
+    fn default() -> Self {
+        PaletteWriteConfig {
+            pretty: true,
+            depth_limit: 2,
+            indentor: "    ".to_string(),
+            enumerate_arrays: false,
+        }
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// RonVersionProbe
+////////////////////////////////////////////////////////////////////////////////
+/// The version field of a serialized `BasicPalette`, read before the rest of
+/// the palette so that unsupported versions can be reported.
+#[derive(Deserialize)]
+pub(in crate::palette) struct RonVersionProbe {
+    /// The serialization format version.
+    #[serde(default)]
+    version: u32,
+}
+
+impl RonVersionProbe {
+    /// Returns an error if the version is newer than the latest supported
+    /// version.
+    pub(in crate::palette) fn check_supported(&self) -> Result<(), FileError> {
+        if self.version > RON_FORMAT_VERSION {
+            return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "unsupported palette format version"))
+                .with_context(|| format!(
+                    "Palette format version {} is newer than the latest \
+                    supported version {}; a newer version of atma is \
+                    required to read this file",
+                    self.version,
+                    RON_FORMAT_VERSION));
+        }
+        Ok(())
+    }
+
+    /// Returns true if the version stores cells as bare expressions.
+    pub(in crate::palette) fn is_legacy(&self) -> bool {
+        self.version < RON_CELL_STRUCT_VERSION
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// LegacyBasicPalette
+////////////////////////////////////////////////////////////////////////////////
+/// The RON representation of a `BasicPalette` before format version 2, in
+/// which each cell is serialized as its bare `Expr`.
+#[derive(Deserialize)]
+pub(in crate::palette) struct LegacyBasicPalette {
+    /// BasicPalette cells storage.
+    cells: BTreeMap<u32, Expr>,
+    /// A map of assigned names.
+    names: BiMap<Cow<'static, str>, PositionSelector>,
+    /// A map of assigned positions.
+    positions: BiMap<Position, u32>,
+    /// A map of names assigned to groups of cells.
+    groups: BTreeMap<Cow<'static, str>, Vec<u32>>,
+    /// The next free cell index.
+    next_index: u32,
+    /// The positioning cursor.
+    position_cursor: Position,
+    /// Whether names are resolved case-insensitively.
+    #[serde(default)]
+    case_insensitive_names: bool,
+    /// The number of operations successfully applied to the palette.
+    #[serde(default)]
+    revision: u64,
+}
+
+impl From<LegacyBasicPalette> for BasicPalette {
+    fn from(legacy: LegacyBasicPalette) -> Self {
+        let mut basic = BasicPalette::new();
+        basic.cells = legacy.cells
+            .into_iter()
+            .map(|(idx, expr)| (idx, Cell::new_with_expr(expr)))
+            .collect();
+        basic.names = legacy.names;
+        basic.positions = legacy.positions;
+        basic.groups = legacy.groups;
+        basic.next_index = legacy.next_index;
+        basic.position_cursor = legacy.position_cursor;
+        basic.case_insensitive_names = legacy.case_insensitive_names;
+        basic.revision = legacy.revision;
+        basic
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ColorMemo
+////////////////////////////////////////////////////////////////////////////////
+/// Colors resolved during a batch color resolution.
+#[derive(Debug, Clone, Default)]
+struct ColorMemo {
+    /// The resolved color of each index, with the number of references
+    /// followed below it while resolving it.
+    colors: HashMap<u32, (Option<Color>, usize)>,
+    /// The greatest reference depth reached by the current resolution.
+    deepest: usize,
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ReverseIndex
+////////////////////////////////////////////////////////////////////////////////
+/// Lookup tables mapping palette indices to their assigned names and groups.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(test, derive(PartialEq))]
+struct ReverseIndex {
+    /// The names whose position selectors select each index's position.
+    names: BTreeMap<u32, Vec<Cow<'static, str>>>,
+    /// The groups containing each index, with the index's group position.
+    groups: BTreeMap<u32, Vec<(Cow<'static, str>, u32)>>,
+}
+
+impl ReverseIndex {
+    /// Constructs a `ReverseIndex` for the given palette.
+    fn build(basic: &BasicPalette) -> Result<Self, PaletteError> {
+        let mut reverse_index = ReverseIndex::default();
+
+        for (name, pos_sel) in basic.names.iter() {
+            reverse_index.insert_name(&basic.positions, name, pos_sel);
+        }
+
+        for (group, members) in basic.groups.iter() {
+            reverse_index.update_group(group, &[], members)?;
+        }
+
+        Ok(reverse_index)
+    }
+
+    /// Applies the given update to the reverse index held in the given cell,
+    /// if it has been built. If the update fails, the index is discarded so
+    /// that it will be rebuilt when it is next used.
+    fn update<F>(reverse_index: &mut RefCell<Option<ReverseIndex>>, f: F)
+        where F: FnOnce(&mut ReverseIndex) -> Result<(), PaletteError>
+    {
+        let reverse_index = reverse_index.get_mut();
+        let res = reverse_index.as_mut().map_or(Ok(()), f);
+        if res.is_err() { *reverse_index = None; }
+    }
+
+    /// Returns the indices assigned to the positions selected by the given
+    /// position selector.
+    fn selected_indices(
+        positions: &BiMap<Position, u32>,
+        pos_sel: &PositionSelector)
+        -> Vec<u32>
+    {
+        if let Ok(pos) = Position::try_from(*pos_sel) {
+            // Full positions can be looked up directly.
+            return positions.get_left(&pos).copied().into_iter().collect();
+        }
+        positions.iter()
+            .filter(|(pos, _)| pos_sel.contains(pos))
+            .map(|(_, idx)| *idx)
+            .collect()
+    }
+
+    /// Adds the given name to the entries of the indices selected by the
+    /// given position selector.
+    fn insert_name(
+        &mut self,
+        positions: &BiMap<Position, u32>,
+        name: &Cow<'static, str>,
+        pos_sel: &PositionSelector)
+    {
+        for idx in ReverseIndex::selected_indices(positions, pos_sel) {
+            self.names
+                .entry(idx)
+                .or_default()
+                .push(name.clone());
+        }
+    }
+
+    /// Removes the given name from the entries of the indices selected by the
+    /// given position selector.
+    fn remove_name(
+        &mut self,
+        positions: &BiMap<Position, u32>,
+        name: &str,
+        pos_sel: &PositionSelector)
+    {
+        for idx in ReverseIndex::selected_indices(positions, pos_sel) {
+            let empty = match self.names.get_mut(&idx) {
+                Some(names) => {
+                    names.retain(|n| n != name);
+                    names.is_empty()
+                },
+                None => false,
+            };
+            if empty { let _ = self.names.remove(&idx); }
+        }
+    }
+
+    /// Replaces the given group's entries for the given removed indices and
+    /// the group's current members with entries for the current members.
+    fn update_group(
+        &mut self,
+        group: &Cow<'static, str>,
+        removed: &[u32],
+        members: &[u32])
+        -> Result<(), PaletteError>
+    {
+        for idx in removed.iter().chain(members.iter()) {
+            let empty = match self.groups.get_mut(idx) {
+                Some(groups) => {
+                    groups.retain(|(g, _)| g != group);
+                    groups.is_empty()
+                },
+                None => false,
+            };
+            if empty { let _ = self.groups.remove(idx); }
+        }
+
+        for (group_idx, idx) in members.iter().enumerate() {
+            self.groups
+                .entry(*idx)
+                .or_default()
+                .push((group.clone(), group_index(group, group_idx)?));
+        }
+        Ok(())
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// JsonBasicPalette
+////////////////////////////////////////////////////////////////////////////////
+/// The JSON representation of a `BasicPalette`.
+#[cfg(feature = "json")]
+#[derive(Serialize, Deserialize)]
+struct JsonBasicPalette {
+    /// BasicPalette cells storage.
+    cells: BTreeMap<u32, Cell>,
+    /// A map of assigned names.
+    names: BiMap<Cow<'static, str>, PositionSelector>,
+    /// A list of assigned positions and their indices.
+    positions: Vec<(Position, u32)>,
+    /// A map of names assigned to groups of cells.
+    groups: BTreeMap<Cow<'static, str>, Vec<u32>>,
+    /// The next free cell index.
+    next_index: u32,
+    /// The positioning cursor.
+    position_cursor: Position,
+    /// Whether names are resolved case-insensitively.
+    #[serde(default)]
+    case_insensitive_names: bool,
+    /// The number of operations successfully applied to the palette.
+    #[serde(default)]
+    revision: u64,
+}
+
+#[cfg(feature = "json")]
+impl<'a> From<&'a BasicPalette> for JsonBasicPalette {
+    fn from(basic: &'a BasicPalette) -> Self {
+        JsonBasicPalette {
+            cells: basic.cells.clone(),
+            names: basic.names.clone(),
+            positions: basic.positions
+                .iter()
+                .map(|(pos, idx)| (*pos, *idx))
+                .collect(),
+            groups: basic.groups.clone(),
+            next_index: basic.next_index,
+            position_cursor: basic.position_cursor,
+            case_insensitive_names: basic.case_insensitive_names,
+            revision: basic.revision,
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<JsonBasicPalette> for BasicPalette {
+    fn from(json: JsonBasicPalette) -> Self {
+        let mut positions = BiMap::new();
+        for (pos, idx) in json.positions {
+            let _ = positions.insert(pos, idx);
+        }
+        BasicPalette {
+            version: RON_FORMAT_VERSION,
+            cells: json.cells,
+            names: json.names,
+            positions,
+            groups: json.groups,
+            next_index: json.next_index,
+            position_cursor: json.position_cursor,
+            case_insensitive_names: json.case_insensitive_names,
+            revision: json.revision,
+            max_reference_depth: DEFAULT_MAX_REFERENCE_DEPTH,
+            ignore_locks: false,
+            reverse_index: RefCell::new(None),
+            color_memo: RefCell::new(None),
+            journal: None,
+        }
+    }
+}
+
+
+/// Returns the default maximum reference depth for deserialized palettes.
+fn default_max_reference_depth() -> usize {
+    DEFAULT_MAX_REFERENCE_DEPTH
+}
+
+/// Constructs an I/O error for invalid GIMP palette data.
+fn invalid_gpl_data(msg: &'static str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+}
+
+/// Returns an `InvalidData` I/O error wrapping the given palette error, for
+/// reporting palette construction failures while reading palette files.
+fn invalid_palette_data(error: PaletteError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+}
+
+/// Returns the given name if it is unassigned in the given palette, or else
+/// the name followed by the smallest number which makes it unassigned.
+fn unique_name(basic: &BasicPalette, name: String) -> String {
+    if basic.resolve_name_ci(&name).is_none() { return name; }
+    (2..=u32::MAX)
+        .map(|n| format!("{} {}", name, n))
+        .find(|candidate| basic.resolve_name_ci(candidate).is_none())
+        .unwrap_or(name)
+}
+
+/// Returns the position following the given one in a row-major grid with the
+/// given number of columns and lines per page, or None if the positions are
+/// exhausted.
+fn next_grid_position(pos: Position, columns: u16, lines: u16)
+    -> Option<Position>
+{
+    if pos.column.saturating_add(1) < columns {
+        Some(Position { column: pos.column + 1, .. pos })
+    } else if pos.line.saturating_add(1) < lines {
+        Some(Position { line: pos.line + 1, column: 0, .. pos })
+    } else {
+        pos.page.checked_add(1)
+            .map(|page| Position { page, line: 0, column: 0 })
+    }
+}
+
+/// Returns the rank of the match of the query within the given name, where
+/// lower ranks are better matches, or None if the name does not match.
+fn name_match_rank(name: &str, query: &str) -> Option<u8> {
+    if name == query {
+        Some(0)
+    } else if name.starts_with(query) {
+        Some(1)
+    } else if name.contains(query) {
+        Some(2)
+    } else {
+        let mut name_chars = name.chars();
+        if query.chars().all(|q| name_chars.any(|c| c == q)) {
+            Some(3)
+        } else {
+            None
+        }
+    }
+}
+
+/// Converts the position of a member within the given group into a group
+/// index.
+fn group_index(group: &str, group_idx: usize) -> Result<u32, PaletteError> {
+    u32::try_from(group_idx).map_err(|_| PaletteError::InvalidInputValue {
+        msg: format!("group {} has too many members.", group).into(),
+    })
+}
+
+/// Returns the operation which restores the given previous override color of
+/// the cell at the given index.
+fn override_undo_op(idx: u32, old: Option<Color>) -> Operation {
+    match old {
+        Some(color) => Operation::SetOverride {
+            cell_ref: CellRef::Index(idx),
+            color,
+        },
+        None => Operation::ClearOverride {
+            cell_ref: CellRef::Index(idx),
+        },
     }
 }
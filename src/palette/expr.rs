@@ -11,9 +11,14 @@
 // Local imports.
 use crate::cell::CellRef;
 use crate::color::Color;
+use crate::color::ColorExt as _;
+use crate::color::color_from_lab;
+use crate::color::ColorDeficiency;
+use crate::color::Hsl;
 use crate::color::Hsv;
 use crate::color::Rgb;
 use crate::error::PaletteError;
+use crate::error::ParseErrorKind;
 use crate::palette::BasicPalette;
 use crate::parse::AstExprMatch as _;
 use crate::parse::AtmaScanner;
@@ -44,29 +49,111 @@ pub enum Expr {
     Empty,
     /// A simple color expression.
     Color(Color),
+    /// A simple color expression, tagged with the encoding it was authored
+    /// in.
+    TaggedColor(TaggedColor),
     /// A reference to another cell.
     Reference(CellRef<'static>),
     /// A color blend expression.
     Blend(BlendExpr),
+    /// A color mix expression.
+    Mix(MixExpr),
 }
 
 impl Expr {
     /// Returns the Expr's color.
+    ///
+    /// The returned color is always clamped to the RGB gamut, so that out of
+    /// range channel values produced by constructors or blend calculations do
+    /// not propagate through references.
     pub fn color(
         &self,
         basic: &BasicPalette,
         index_list: &mut HashSet<u32>)
         -> Result<Option<Color>, PaletteError>
     {
-        match self {
+        let color = match self {
             Expr::Empty => Ok(None),
 
             Expr::Color(c) => Ok(Some(c.clone())),
 
+            Expr::TaggedColor(tagged) => Ok(Some(tagged.color.clone())),
+
             Expr::Reference(cell_ref) => basic
                 .cycle_detect_color(cell_ref, index_list),
 
             Expr::Blend(blend_expr) => blend_expr.color(basic, index_list),
+
+            Expr::Mix(mix_expr) => mix_expr.color(basic, index_list),
+        };
+        color.map(|c| c.map(|c| c.clamped()))
+    }
+
+    /// Returns the Expr's alpha (opacity) value, in the range [0.0, 1.0].
+    /// Colors without an alpha channel are fully opaque.
+    pub fn alpha(
+        &self,
+        basic: &BasicPalette,
+        index_list: &mut HashSet<u32>)
+        -> Result<f32, PaletteError>
+    {
+        match self {
+            Expr::TaggedColor(tagged) => Ok(tagged.alpha),
+
+            Expr::Reference(cell_ref) => basic
+                .cycle_detect_alpha(cell_ref, index_list),
+
+            Expr::Blend(blend_expr) => blend_expr.alpha(basic, index_list),
+
+            _ => Ok(1.0),
+        }
+    }
+
+    /// Calls the given function on each `CellRef` in the expression.
+    pub fn for_each_cell_ref<F>(&self, mut f: F)
+        where F: FnMut(&CellRef<'static>)
+    {
+        match self {
+            Expr::Reference(cell_ref) => f(cell_ref),
+
+            Expr::Blend(BlendExpr { blend_fn, .. }) => match blend_fn {
+                BlendFunction::Unary(un_fn)   => f(&un_fn.arg),
+                BlendFunction::Binary(bin_fn) => {
+                    f(&bin_fn.arg_0);
+                    f(&bin_fn.arg_1);
+                },
+                BlendFunction::Swizzle(sw_fn) => f(&sw_fn.arg),
+            },
+
+            Expr::Mix(MixExpr { refs, .. }) => for cell_ref in refs {
+                f(cell_ref);
+            },
+
+            _ => (),
+        }
+    }
+
+    /// Calls the given function on each mutable `CellRef` in the expression.
+    pub fn for_each_cell_ref_mut<F>(&mut self, mut f: F)
+        where F: FnMut(&mut CellRef<'static>)
+    {
+        match self {
+            Expr::Reference(cell_ref) => f(cell_ref),
+
+            Expr::Blend(BlendExpr { blend_fn, .. }) => match blend_fn {
+                BlendFunction::Unary(un_fn)   => f(&mut un_fn.arg),
+                BlendFunction::Binary(bin_fn) => {
+                    f(&mut bin_fn.arg_0);
+                    f(&mut bin_fn.arg_1);
+                },
+                BlendFunction::Swizzle(sw_fn) => f(&mut sw_fn.arg),
+            },
+
+            Expr::Mix(MixExpr { refs, .. }) => for cell_ref in refs {
+                f(cell_ref);
+            },
+
+            _ => (),
         }
     }
 }
@@ -89,12 +176,20 @@ pub enum InsertExpr {
     Ramp(RampExpr),
     /// Insert a color blend operation.
     Blend(BlendExpr),
+    /// Insert a color mix operation.
+    Mix(MixExpr),
+    /// Insert a range of colors sampled between two cells.
+    Gradient(GradientExpr),
     /// Insert a color.
-    Color(Color),
+    Color(TaggedColor),
     /// Insert a copy of the color from a cell.
     Copy(CellRef<'static>),
     /// Insert a reference to a cell.
     Reference(CellRef<'static>),
+    /// Insert a random RGB color. If a seed is given, repeated inserts will
+    /// yield the same color. Otherwise the seed is drawn from the system's
+    /// entropy sources.
+    RandomColor(Option<u64>),
 }
 
 impl InsertExpr {
@@ -103,8 +198,8 @@ impl InsertExpr {
         -> Result<Vec<Expr>, PaletteError>
     {
         match self {
-            InsertExpr::Ramp(ramp_expr) => Ok(ramp_expr.interpolate
-                .blend_exprs(ramp_expr.count, &ramp_expr.blend_fn)
+            InsertExpr::Ramp(ramp_expr) => Ok(ramp_expr
+                .blend_exprs()
                 .into_iter()
                 .map(Expr::Blend)
                 .collect()),
@@ -113,8 +208,22 @@ impl InsertExpr {
                 Expr::Blend(blend_expr.clone())
             ]),
 
-            InsertExpr::Color(color) => Ok(vec![
-                Expr::Color(color.clone())
+            InsertExpr::Mix(mix_expr) => Ok(vec![
+                Expr::Mix(mix_expr.clone())
+            ]),
+
+            InsertExpr::Gradient(gradient_expr) => Ok(gradient_expr
+                .colors(basic)?
+                .into_iter()
+                .map(Expr::Color)
+                .collect()),
+
+            InsertExpr::Color(tagged) => Ok(vec![
+                match tagged.encoding {
+                    ColorEncoding::Rgb if tagged.alpha == 1.0
+                        => Expr::Color(tagged.color.clone()),
+                    _   => Expr::TaggedColor(tagged.clone()),
+                }
             ]),
             
             // TODO: Config to generate default color instead of error?
@@ -123,16 +232,52 @@ impl InsertExpr {
                     .ok_or_else(|| PaletteError::UndefinedColor {
                         cell_ref: cell_ref.clone(),
                         circular: false,
+                        referrer: None,
                     })?)
             ]),
 
             InsertExpr::Reference(cell_ref) => Ok(vec![
                 Expr::Reference(cell_ref.clone())
             ]),
+
+            InsertExpr::RandomColor(seed) => Ok(vec![
+                Expr::Color(random_color(seed.unwrap_or_else(entropy_seed)))
+            ]),
         }
     }
 }
 
+/// Returns a random RGB color generated from the given seed.
+fn random_color(seed: u64) -> Color {
+    // SplitMix64 is used so that a given seed yields the same color on every
+    // platform.
+    let mut state = seed;
+    let mut next_octet = || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        ((z ^ (z >> 31)) >> 56) as u8
+    };
+    let (r, g, b) = (next_octet(), next_octet(), next_octet());
+    Color::from(Rgb::from([r, g, b]))
+}
+
+/// Returns a seed drawn from the system's entropy sources.
+fn entropy_seed() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::BuildHasher as _;
+    use std::hash::Hasher as _;
+
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(elapsed) = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+    {
+        hasher.write_u128(elapsed.as_nanos());
+    }
+    hasher.finish()
+}
+
 impl std::str::FromStr for InsertExpr {
     type Err = FailureOwned<Lf>;
 
@@ -145,16 +290,99 @@ impl std::str::FromStr for InsertExpr {
 
         // Perform parse.
         let ast = ast_expr(lexer)
-            .finish()?;
+            .finish()
+            .map_err(|failure| ParseErrorKind::InvalidSyntax.attach(failure))?;
 
         InsertExpr::match_expr(ast, column_metrics)
             .map_err(|parse_error| FailureOwned {
                 parse_error: parse_error.into_owned(),
-                source: None,
+                source: Some(Box::new(ParseErrorKind::InvalidInsertExpr)),
             })
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// TaggedColor
+////////////////////////////////////////////////////////////////////////////////
+/// A color tagged with the encoding it was authored in.
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct TaggedColor {
+    /// The color.
+    pub color: Color,
+    /// The encoding the color was authored in.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub encoding: ColorEncoding,
+    /// The alpha (opacity) value of the color, in the range [0.0, 1.0].
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    pub alpha: f32,
+}
+
+impl From<Color> for TaggedColor {
+    fn from(color: Color) -> Self {
+        TaggedColor {
+            color,
+            encoding: ColorEncoding::default(),
+            alpha: 1.0,
+        }
+    }
+}
+
+impl std::fmt::Display for TaggedColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.encoding {
+            ColorEncoding::Rgb if self.alpha != 1.0 => {
+                let [r, g, b] = self.color.rgb_ratios();
+                write!(f, "rgba({:?}, {:?}, {:?}, {:?})", r, g, b, self.alpha)
+            },
+            ColorEncoding::Rgb => {
+                let [r, g, b] = self.color.rgb_ratios();
+                write!(f, "rgb({:?}, {:?}, {:?})", r, g, b)
+            },
+            ColorEncoding::Hsl => {
+                let [h, s, l] = self.color.hsl_components();
+                write!(f, "hsl({:?}, {:?}, {:?})", h, s, l)
+            },
+            ColorEncoding::Hsv => {
+                let [h, s, v] = self.color.hsv_components();
+                write!(f, "hsv({:?}, {:?}, {:?})", h, s, v)
+            },
+            ColorEncoding::Cmyk => {
+                let [c, m, y, k] = self.color.cmyk_components();
+                write!(f, "cmyk({:?}, {:?}, {:?}, {:?})", c, m, y, k)
+            },
+            ColorEncoding::Xyz => {
+                let [x, y, z] = self.color.xyz_components();
+                write!(f, "xyz({:?}, {:?}, {:?})", x, y, z)
+            },
+        }
+    }
+}
+
+
+/// The encoding a color was authored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+pub enum ColorEncoding {
+    /// RGB encoding.
+    Rgb,
+    /// HSL encoding.
+    Hsl,
+    /// HSV encoding.
+    Hsv,
+    /// CMYK encoding.
+    Cmyk,
+    /// XYZ encoding.
+    Xyz,
+}
+
+impl Default for ColorEncoding {
+    fn default() -> Self {
+        ColorEncoding::Rgb
+    }
+}
+
+
 ////////////////////////////////////////////////////////////////////////////////
 // RampExpr
 ////////////////////////////////////////////////////////////////////////////////
@@ -167,7 +395,25 @@ pub struct RampExpr {
     /// The ramp blend function.
     pub blend_fn: BlendFunction,
     /// The range of values to interpolate over.
+    #[serde(default, skip_serializing_if = "is_default")]
     pub interpolate: InterpolateRange,
+    /// Explicit interpolation amounts for each color in the ramp. If given,
+    /// these are used instead of evenly spacing the colors over the
+    /// interpolation range.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amounts: Option<Vec<f32>>,
+}
+
+impl RampExpr {
+    /// Compute the `BlendExpr`s for the ramp.
+    pub fn blend_exprs(&self) -> Vec<BlendExpr> {
+        match &self.amounts {
+            Some(amounts) => self.interpolate
+                .blend_exprs_with_amounts(amounts, &self.blend_fn),
+            None          => self.interpolate
+                .blend_exprs(self.count, &self.blend_fn),
+        }
+    }
 }
 
 impl std::str::FromStr for RampExpr {
@@ -182,12 +428,13 @@ impl std::str::FromStr for RampExpr {
 
         // Perform parse.
         let ast = ast_expr(lexer)
-            .finish()?;
+            .finish()
+            .map_err(|failure| ParseErrorKind::InvalidSyntax.attach(failure))?;
 
         RampExpr::match_expr(ast, column_metrics)
             .map_err(|parse_error| FailureOwned {
                 parse_error: parse_error.into_owned(),
-                source: None,
+                source: Some(Box::new(ParseErrorKind::InvalidRampExpr)),
             })
     }
 }
@@ -202,6 +449,7 @@ pub struct BlendExpr {
     /// The blend function.
     pub blend_fn: BlendFunction,
     /// The blend interpolation.
+    #[serde(default, skip_serializing_if = "is_default")]
     pub interpolate: Interpolate,
 }
 
@@ -216,6 +464,17 @@ impl BlendExpr {
     {
         self.blend_fn.apply(basic, index_list, &self.interpolate)
     }
+
+    /// Resolves the source and target references and returns the alpha value
+    /// of their blended result.
+    pub fn alpha(
+        &self,
+        basic: &BasicPalette,
+        index_list: &mut HashSet<u32>)
+        -> Result<f32, PaletteError>
+    {
+        self.blend_fn.alpha(basic, index_list, &self.interpolate)
+    }
 }
 
 impl std::str::FromStr for BlendExpr {
@@ -230,12 +489,160 @@ impl std::str::FromStr for BlendExpr {
 
         // Perform parse.
         let ast = ast_expr(lexer)
-            .finish()?;
+            .finish()
+            .map_err(|failure| ParseErrorKind::InvalidSyntax.attach(failure))?;
 
         BlendExpr::match_expr(ast, column_metrics)
             .map_err(|parse_error| FailureOwned {
                 parse_error: parse_error.into_owned(),
-                source: None,
+                source: Some(Box::new(ParseErrorKind::InvalidBlendExpr)),
+            })
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// MixExpr
+////////////////////////////////////////////////////////////////////////////////
+/// A color mix function, producing the (optionally weighted) average of a set
+/// of colors.
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct MixExpr {
+    /// References to the colors to mix.
+    pub refs: Vec<CellRef<'static>>,
+    /// The weight of each color. If None, the colors are weighted equally.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<Vec<f32>>,
+    /// The color space to mix the colors in.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub color_space: ColorSpace,
+}
+
+impl MixExpr {
+    /// Resolves the referenced colors and returns their weighted average.
+    /// Returns None if there are no references or any referenced cell has no
+    /// color.
+    pub fn color(
+        &self,
+        basic: &BasicPalette,
+        index_list: &mut HashSet<u32>)
+        -> Result<Option<Color>, PaletteError>
+    {
+        if let Some(weights) = &self.weights {
+            if weights.len() != self.refs.len() {
+                return Err(PaletteError::InvalidInputValue {
+                    msg: format!("mix requires {} weights, {} provided.",
+                        self.refs.len(),
+                        weights.len()).into(),
+                });
+            }
+            // Weights loaded from a file are not checked by the parser.
+            if let Some(weight) = weights.iter()
+                .find(|w| !(w.is_finite() && **w >= 0.0))
+            {
+                return Err(PaletteError::InvalidInputValue {
+                    msg: format!("mix weight {} must be finite and not \
+                        negative.", weight).into(),
+                });
+            }
+        }
+
+        let mut colors = Vec::with_capacity(self.refs.len());
+        for (i, cell_ref) in self.refs.iter().enumerate() {
+            // Each reference is resolved using a copy of the index list so
+            // that repeated references do not cause a false cycle.
+            let weight = self.weights.as_ref().map_or(1.0, |w| w[i]);
+            match basic.cycle_detect_color(
+                cell_ref,
+                &mut index_list.clone())?
+            {
+                Some(color) => colors.push((color, weight)),
+                None        => return Ok(None),
+            }
+        }
+
+        if colors.is_empty() { return Ok(None); }
+        Color::mix_many(&colors[..], self.color_space)
+            .map(Some)
+            .ok_or_else(|| PaletteError::InvalidInputValue {
+                msg: "mix weights must have a positive sum.".into(),
+            })
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// GradientExpr
+////////////////////////////////////////////////////////////////////////////////
+/// A color gradient expression. Unlike a `RampExpr`, the gradient is resolved
+/// to static colors when inserted, so later changes to its endpoints do not
+/// affect the inserted colors.
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct GradientExpr {
+    /// Reference to the start color of the gradient.
+    pub from: CellRef<'static>,
+    /// Reference to the end color of the gradient.
+    pub to: CellRef<'static>,
+    /// The number of colors in the gradient. Must be at least 2.
+    pub count: u8,
+    /// The color space to interpolate the colors in.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub color_space: ColorSpace,
+}
+
+impl GradientExpr {
+    /// Resolves the endpoint colors and returns the evenly spaced colors
+    /// sampled between them, including both endpoints.
+    pub fn colors(&self, basic: &BasicPalette)
+        -> Result<Vec<Color>, PaletteError>
+    {
+        if self.count < 2 {
+            return Err(PaletteError::InvalidInputValue {
+                msg: "gradient requires a count of at least 2.".into(),
+            });
+        }
+
+        let resolve = |cell_ref: &CellRef<'static>| basic.color(cell_ref)?
+            .ok_or_else(|| PaletteError::UndefinedColor {
+                cell_ref: cell_ref.clone(),
+                circular: false,
+                referrer: None,
+            });
+        let from = resolve(&self.from)?;
+        let to = resolve(&self.to)?;
+
+        let steps = f32::from(self.count - 1);
+        Ok((0..self.count)
+            .map(|i| self.color_space.interpolate_channels(
+                from,
+                to,
+                InterpolateFunction::Linear,
+                f32::from(i) / steps))
+            .collect())
+    }
+}
+
+impl std::str::FromStr for GradientExpr {
+    type Err = FailureOwned<Lf>;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        // Setup parser.
+        let scanner = AtmaScanner::new();
+        let column_metrics = Lf::with_tab_width(4);
+        let mut lexer = Lexer::new(scanner, text, column_metrics);
+        lexer.set_filter_fn(|tok| *tok != AtmaToken::Whitespace);
+
+        // Perform parse.
+        let ast = ast_expr(lexer)
+            .finish()
+            .map_err(|failure| ParseErrorKind::InvalidSyntax.attach(failure))?;
+
+        GradientExpr::match_expr(ast, column_metrics)
+            .map_err(|parse_error| FailureOwned {
+                parse_error: parse_error.into_owned(),
+                source: Some(Box::new(ParseErrorKind::InvalidInsertExpr)),
             })
     }
 }
@@ -252,6 +659,8 @@ pub enum BlendFunction {
     Unary(UnaryBlendFunction),
     /// A binary blend function.
     Binary(BinaryBlendFunction),
+    /// A channel swizzle function.
+    Swizzle(SwizzleFunction),
 }
 
 impl BlendFunction {
@@ -270,6 +679,24 @@ impl BlendFunction {
         match self {
             Unary(un_fn)   => un_fn.apply(basic, index_list, int),
             Binary(bin_fn) => bin_fn.apply(basic, index_list, int),
+            Swizzle(sw_fn) => sw_fn.apply(basic, index_list, int),
+        }
+    }
+
+    /// Resolves the source and target references and returns the alpha value
+    /// of their blended result.
+    pub fn alpha(
+        &self,
+        basic: &BasicPalette,
+        index_list: &mut HashSet<u32>,
+        int: &Interpolate)
+        -> Result<f32, PaletteError>
+    {
+        use BlendFunction::*;
+        match self {
+            Unary(un_fn)   => basic.cycle_detect_alpha(&un_fn.arg, index_list),
+            Binary(bin_fn) => bin_fn.alpha(basic, index_list, int),
+            Swizzle(sw_fn) => basic.cycle_detect_alpha(&sw_fn.arg, index_list),
         }
     }
 }
@@ -286,12 +713,13 @@ impl std::str::FromStr for BlendFunction {
 
         // Perform parse.
         let ast = ast_expr(lexer)
-            .finish()?;
+            .finish()
+            .map_err(|failure| ParseErrorKind::InvalidSyntax.attach(failure))?;
 
         BlendFunction::match_expr(ast, column_metrics)
             .map_err(|parse_error| FailureOwned {
                 parse_error: parse_error.into_owned(),
-                source: None,
+                source: Some(Box::new(ParseErrorKind::InvalidBlendFunction)),
             })
     }
 }
@@ -361,12 +789,14 @@ impl std::str::FromStr for UnaryBlendFunction {
 
         // Perform parse.
         let ast = ast_expr(lexer)
-            .finish()?;
+            .finish()
+            .map_err(|failure| ParseErrorKind::InvalidSyntax.attach(failure))?;
 
         UnaryBlendFunction::match_expr(ast, column_metrics)
             .map_err(|parse_error| FailureOwned {
                 parse_error: parse_error.into_owned(),
-                source: None,
+                source: Some(Box::new(
+                    ParseErrorKind::InvalidUnaryBlendFunction)),
             })
     }
 }
@@ -394,6 +824,21 @@ pub enum UnaryBlendMethod {
     Lighten,
     /// Darken the source color.
     Darken,
+    /// Invert the RGB channels of the source color. The value gives the
+    /// amount to mix the source color toward its inverse.
+    Invert,
+    /// Convert the source color to gray using Rec. 709 luminance weights. The
+    /// value gives the amount to mix the source color toward the gray.
+    Grayscale,
+    /// Simulate the source color as seen with protanopia. The value gives the
+    /// amount to mix the source color toward the simulated color.
+    SimulateProtanopia,
+    /// Simulate the source color as seen with deuteranopia. The value gives
+    /// the amount to mix the source color toward the simulated color.
+    SimulateDeuteranopia,
+    /// Simulate the source color as seen with tritanopia. The value gives the
+    /// amount to mix the source color toward the simulated color.
+    SimulateTritanopia,
 }
 
 impl UnaryBlendMethod {
@@ -414,49 +859,77 @@ impl UnaryBlendMethod {
                 Color::from(Rgb::from([rgb[0], rgb[1], value]))
             },
 
-            HueShift   => {
-                let hsv = arg.hsv_components();
-                Color::from(Hsv::from([hsv[0] + value, hsv[1], hsv[2]]))
-            },
-            SetHue     => {
-                let hsv = arg.hsv_components();
-                Color::from(Hsv::from([value, hsv[1], hsv[2]]))
-            },
-            Saturate   => {
-                let hsv = arg.hsv_components();
-                Color::from(Hsv::from([hsv[0], hsv[1] + value, hsv[2]]))
-            },
-            Desaturate => {
-                let hsv = arg.hsv_components();
-                Color::from(Hsv::from([hsv[0], hsv[1] - value, hsv[2]]))
+            HueShift   => arg.with_hue(arg.hsv_components()[0] + value),
+            SetHue     => arg.with_hue(value),
+            Saturate   => arg
+                .with_saturation(arg.hsv_components()[1] + value),
+            Desaturate => arg
+                .with_saturation(arg.hsv_components()[1] - value),
+            Lighten    => arg.with_value(arg.hsv_components()[2] + value),
+            Darken     => arg.with_value(arg.hsv_components()[2] - value),
+            Invert     => {
+                let [r, g, b] = arg.rgb_ratios();
+                Color::from(Rgb::from([
+                    r + (1.0 - 2.0 * r) * value,
+                    g + (1.0 - 2.0 * g) * value,
+                    b + (1.0 - 2.0 * b) * value,
+                ]))
             },
-            Lighten    => {
-                let hsv = arg.hsv_components();
-                Color::from(Hsv::from([hsv[0], hsv[1], hsv[2] + value]))
-            },
-            Darken     => {
-                let hsv = arg.hsv_components();
-                Color::from(Hsv::from([hsv[0], hsv[1], hsv[2] - value]))
+            Grayscale  => {
+                let [r, g, b] = arg.rgb_ratios();
+                let gray = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+                Color::from(Rgb::from([
+                    r + (gray - r) * value,
+                    g + (gray - g) * value,
+                    b + (gray - b) * value,
+                ]))
             },
+            SimulateProtanopia   => mix_rgb(arg,
+                &arg.simulate_deficiency(ColorDeficiency::Protanopia),
+                value),
+            SimulateDeuteranopia => mix_rgb(arg,
+                &arg.simulate_deficiency(ColorDeficiency::Deuteranopia),
+                value),
+            SimulateTritanopia   => mix_rgb(arg,
+                &arg.simulate_deficiency(ColorDeficiency::Tritanopia),
+                value),
         }
     }
 }
 
+/// Mixes the RGB channels of the source color toward the target color by the
+/// given amount. The source color is returned unchanged for an amount of 0.0.
+fn mix_rgb(source: &Color, target: &Color, amount: f32) -> Color {
+    if amount == 0.0 { return source.clone(); }
+    let [r, g, b] = source.rgb_ratios();
+    let [tr, tg, tb] = target.rgb_ratios();
+    Color::from(Rgb::from([
+        r + (tr - r) * amount,
+        g + (tg - g) * amount,
+        b + (tb - b) * amount,
+    ]))
+}
+
 impl std::str::FromStr for UnaryBlendMethod {
     type Err = InvalidBlendMethod;
 
     fn from_str(text: &str) -> Result<Self, Self::Err> {
         match text {
-            "set_red"    => Ok(UnaryBlendMethod::SetRed),
-            "set_green"  => Ok(UnaryBlendMethod::SetGreen),
-            "set_blue"   => Ok(UnaryBlendMethod::SetBlue),
-            "hue_shift"  => Ok(UnaryBlendMethod::HueShift),
-            "set_hue"    => Ok(UnaryBlendMethod::SetHue),
-            "saturate"   => Ok(UnaryBlendMethod::Saturate),
-            "desaturate" => Ok(UnaryBlendMethod::Desaturate),
-            "lighten"    => Ok(UnaryBlendMethod::Lighten),
-            "darken"     => Ok(UnaryBlendMethod::Darken),
-            _            => Err(InvalidBlendMethod),
+            "set_red"      => Ok(UnaryBlendMethod::SetRed),
+            "set_green"    => Ok(UnaryBlendMethod::SetGreen),
+            "set_blue"     => Ok(UnaryBlendMethod::SetBlue),
+            "hue_shift"    => Ok(UnaryBlendMethod::HueShift),
+            "set_hue"      => Ok(UnaryBlendMethod::SetHue),
+            "saturate"     => Ok(UnaryBlendMethod::Saturate),
+            "desaturate"   => Ok(UnaryBlendMethod::Desaturate),
+            "lighten"      => Ok(UnaryBlendMethod::Lighten),
+            "darken"       => Ok(UnaryBlendMethod::Darken),
+            "invert"       => Ok(UnaryBlendMethod::Invert),
+            "grayscale"    => Ok(UnaryBlendMethod::Grayscale),
+            "protanopia"   => Ok(UnaryBlendMethod::SimulateProtanopia),
+            "deuteranopia" => Ok(UnaryBlendMethod::SimulateDeuteranopia),
+            "tritanopia"   => Ok(UnaryBlendMethod::SimulateTritanopia),
+            _              => Err(InvalidBlendMethod),
         }
     }
 }
@@ -464,20 +937,133 @@ impl std::str::FromStr for UnaryBlendMethod {
 impl std::fmt::Display for UnaryBlendMethod {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
-            UnaryBlendMethod::SetRed     => "set_red",
-            UnaryBlendMethod::SetGreen   => "set_green",
-            UnaryBlendMethod::SetBlue    => "set_blue",
-            UnaryBlendMethod::HueShift   => "hue_shift",
-            UnaryBlendMethod::SetHue     => "set_hue",
-            UnaryBlendMethod::Saturate   => "saturate",
-            UnaryBlendMethod::Desaturate => "desaturate",
-            UnaryBlendMethod::Lighten    => "lighten",
-            UnaryBlendMethod::Darken     => "darken",
+            UnaryBlendMethod::SetRed               => "set_red",
+            UnaryBlendMethod::SetGreen             => "set_green",
+            UnaryBlendMethod::SetBlue              => "set_blue",
+            UnaryBlendMethod::HueShift             => "hue_shift",
+            UnaryBlendMethod::SetHue               => "set_hue",
+            UnaryBlendMethod::Saturate             => "saturate",
+            UnaryBlendMethod::Desaturate           => "desaturate",
+            UnaryBlendMethod::Lighten              => "lighten",
+            UnaryBlendMethod::Darken               => "darken",
+            UnaryBlendMethod::Invert               => "invert",
+            UnaryBlendMethod::Grayscale            => "grayscale",
+            UnaryBlendMethod::SimulateProtanopia   => "protanopia",
+            UnaryBlendMethod::SimulateDeuteranopia => "deuteranopia",
+            UnaryBlendMethod::SimulateTritanopia   => "tritanopia",
         })
     }
 }
 
 
+////////////////////////////////////////////////////////////////////////////////
+// SwizzleFunction
+////////////////////////////////////////////////////////////////////////////////
+/// A function which permutes the RGB channels of a color.
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct SwizzleFunction {
+    /// The order of the output channels.
+    pub order: ChannelOrder,
+    /// The argument of the swizzle.
+    pub arg: CellRef<'static>,
+}
+
+impl SwizzleFunction {
+    /// Resolves the argument reference and returns its swizzled result.
+    pub fn apply(
+        &self,
+        basic: &BasicPalette,
+        index_list: &mut HashSet<u32>,
+        int: &Interpolate)
+        -> Result<Option<Color>, PaletteError>
+    {
+        match basic.cycle_detect_color(&self.arg, index_list)? {
+            Some(color) => {
+                let swizzled = self.order.apply(&color);
+                Ok(Some(int.apply(color, swizzled)))
+            },
+            _ => Ok(None),
+        }
+    }
+}
+
+/// An ordering of the RGB channels of a color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+pub enum ChannelOrder {
+    /// Red, green, blue. (The identity order.)
+    Rgb,
+    /// Red, blue, green.
+    Rbg,
+    /// Green, red, blue.
+    Grb,
+    /// Green, blue, red.
+    Gbr,
+    /// Blue, red, green.
+    Brg,
+    /// Blue, green, red.
+    Bgr,
+}
+
+impl ChannelOrder {
+    /// Returns the color with its RGB channels rearranged into the order.
+    pub fn apply(&self, color: &Color) -> Color {
+        use ChannelOrder::*;
+        let [r, g, b] = color.rgb_ratios();
+        Color::from(Rgb::from(match self {
+            Rgb => [r, g, b],
+            Rbg => [r, b, g],
+            Grb => [g, r, b],
+            Gbr => [g, b, r],
+            Brg => [b, r, g],
+            Bgr => [b, g, r],
+        }))
+    }
+}
+
+impl std::str::FromStr for ChannelOrder {
+    type Err = InvalidChannelOrder;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "rgb" => Ok(ChannelOrder::Rgb),
+            "rbg" => Ok(ChannelOrder::Rbg),
+            "grb" => Ok(ChannelOrder::Grb),
+            "gbr" => Ok(ChannelOrder::Gbr),
+            "brg" => Ok(ChannelOrder::Brg),
+            "bgr" => Ok(ChannelOrder::Bgr),
+            _     => Err(InvalidChannelOrder),
+        }
+    }
+}
+
+impl std::fmt::Display for ChannelOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ChannelOrder::Rgb => "rgb",
+            ChannelOrder::Rbg => "rbg",
+            ChannelOrder::Grb => "grb",
+            ChannelOrder::Gbr => "gbr",
+            ChannelOrder::Brg => "brg",
+            ChannelOrder::Bgr => "bgr",
+        })
+    }
+}
+
+/// An invalid channel order was provided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InvalidChannelOrder;
+
+impl std::fmt::Display for InvalidChannelOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for InvalidChannelOrder {}
+
+
 ////////////////////////////////////////////////////////////////////////////////
 // BinaryBlendFunction
 ////////////////////////////////////////////////////////////////////////////////
@@ -486,6 +1072,7 @@ impl std::fmt::Display for UnaryBlendMethod {
 #[derive(Serialize, Deserialize)]
 pub struct BinaryBlendFunction {
     /// The color space in which to apply the blend method.
+    #[serde(default, skip_serializing_if = "is_default")]
     pub color_space: ColorSpace,
     /// The blend method.
     pub blend_method: BinaryBlendMethod,
@@ -505,13 +1092,24 @@ impl BinaryBlendFunction {
         int: &Interpolate)
         -> Result<Option<Color>, PaletteError>
     {
+        // Alpha values are resolved using copies of the index list so that
+        // the color resolution below does not detect a false cycle.
+        let (alpha_a, alpha_b) = match self.blend_method {
+            BinaryBlendMethod::Blend => (
+                basic.cycle_detect_alpha(&self.arg_0, &mut index_list.clone())?,
+                basic.cycle_detect_alpha(&self.arg_1,
+                    &mut index_list.clone())?),
+            _ => (1.0, 1.0),
+        };
+
         let mut index_list_2 = index_list.clone();
         match (
             basic.cycle_detect_color(&self.arg_0, index_list)?,
             basic.cycle_detect_color(&self.arg_1, &mut index_list_2)?)
         {
             (Some(a), Some(b)) => {
-                let blend_fn = |a, b| self.blend_method.apply(a, b);
+                let blend_fn = |a, b| self.blend_method
+                    .apply_with_alpha(a, b, alpha_a, alpha_b);
                 let blended = self
                     .color_space
                     .map_channels_binary(a, b, blend_fn);
@@ -520,6 +1118,25 @@ impl BinaryBlendFunction {
             _ => Ok(None),
         }
     }
+
+    /// Resolves the arg_1 and arg_2 references and returns the alpha value of
+    /// their blended result.
+    pub fn alpha(
+        &self,
+        basic: &BasicPalette,
+        index_list: &mut HashSet<u32>,
+        int: &Interpolate)
+        -> Result<f32, PaletteError>
+    {
+        let mut index_list_2 = index_list.clone();
+        let alpha_a = basic.cycle_detect_alpha(&self.arg_0, index_list)?;
+        let alpha_b = basic.cycle_detect_alpha(&self.arg_1, &mut index_list_2)?;
+        let blended = match self.blend_method {
+            BinaryBlendMethod::Blend => alpha_b + alpha_a * (1.0 - alpha_b),
+            _                        => alpha_a,
+        };
+        Ok(alpha_a + (blended - alpha_a) * int.amount)
+    }
 }
 
 impl std::str::FromStr for BinaryBlendFunction {
@@ -534,12 +1151,14 @@ impl std::str::FromStr for BinaryBlendFunction {
 
         // Perform parse.
         let ast = ast_expr(lexer)
-            .finish()?;
+            .finish()
+            .map_err(|failure| ParseErrorKind::InvalidSyntax.attach(failure))?;
 
         BinaryBlendFunction::match_expr(ast, column_metrics)
             .map_err(|parse_error| FailureOwned {
                 parse_error: parse_error.into_owned(),
-                source: None,
+                source: Some(Box::new(
+                    ParseErrorKind::InvalidBinaryBlendFunction)),
             })
     }
 }
@@ -626,6 +1245,23 @@ impl BinaryBlendMethod {
             LinearLight => 2.0 * a + b - 1.0,
         }
     }
+
+    /// Applies the blend calculation to the given channel values, using the
+    /// given alpha values of the colors they are taken from. The `Blend`
+    /// method performs source-over compositing of `b` over `a`. Other methods
+    /// ignore the alpha values.
+    pub fn apply_with_alpha(&self, a: f32, b: f32, alpha_a: f32, alpha_b: f32)
+        -> f32
+    {
+        match self {
+            BinaryBlendMethod::Blend => {
+                let alpha = alpha_b + alpha_a * (1.0 - alpha_b);
+                if alpha <= 0.0 { return 0.0; }
+                (b * alpha_b + a * alpha_a * (1.0 - alpha_b)) / alpha
+            },
+            _ => self.apply(a, b),
+        }
+    }
 }
 
 
@@ -686,28 +1322,190 @@ impl std::fmt::Display for BinaryBlendMethod {
 pub enum ColorSpace {
     /// RGB color space.
     Rgb,
+    /// HSV color space.
+    Hsv,
+    /// HSL color space.
+    Hsl,
+    /// CIELab color space.
+    Lab,
 }
 
 impl ColorSpace {
     /// Applies the given binary closure to the channels of the given colors.
+    ///
+    /// For the HSV and HSL color spaces, the hue channel is passed to the
+    /// closure as a ratio in the range [0.0, 1.0], and the result is wrapped
+    /// back into that range.
+    ///
+    /// Results of the closure are sanitized before the color is constructed,
+    /// so that blend methods which divide by a channel value cannot produce
+    /// invalid colors: NaN results are replaced with 0.0, infinite hue
+    /// results are replaced with 0.0, and all other channels are clamped to
+    /// the range [0.0, 1.0].
     pub fn map_channels_binary<A, B, F>(&self, a: A, b: B, f: F) -> Color
         where
             A: Into<Color> + Sized,
             B: Into<Color> + Sized,
             F: Fn(f32, f32) -> f32,
     {
+        let [xa, ya, za] = self.channels(a.into());
+        let [xb, yb, zb] = self.channels(b.into());
+        let x = (f)(xa, xb);
+        self.color_from_channels([
+            if self.has_hue() {
+                if x.is_finite() { x } else { 0.0 }
+            } else {
+                clamp_channel(x)
+            },
+            clamp_channel((f)(ya, yb)),
+            clamp_channel((f)(za, zb)),
+        ])
+    }
+
+    /// Interpolates the channels of the given colors using the given
+    /// interpolation function. Hue channels are interpolated along the
+    /// shortest path around the hue circle.
+    fn interpolate_channels(
+        &self,
+        a: Color,
+        b: Color,
+        interpolate_fn: InterpolateFunction,
+        amount: f32)
+        -> Color
+    {
+        let mut ca = self.channels(a);
+        let mut cb = self.channels(b);
+        if self.has_hue() {
+            // Take the shortest path around the hue circle.
+            let d = cb[0] - ca[0];
+            if d > 0.5 { ca[0] += 1.0; }
+            if d < -0.5 { cb[0] += 1.0; }
+        }
+
+        let mut res = [0.0; 3];
+        for (r, (x, y)) in res.iter_mut().zip(ca.iter().zip(cb.iter())) {
+            *r = match interpolate_fn {
+                InterpolateFunction::Cubic(m0, m1)
+                    => cubic_hermite(*x, *y, m0, m1, amount),
+                _   => x + (y - x) * interpolate_fn.ease(amount),
+            };
+        }
+        self.color_from_channels(res)
+    }
+
+    /// Returns the mean of the given colors in the color space, or None if no
+    /// colors are given. Hue channels are averaged around the hue circle.
+    pub fn mean(&self, colors: &[Color]) -> Option<Color> {
+        let weighted: Vec<_> = colors
+            .iter()
+            .map(|color| (*color, 1.0))
+            .collect();
+        Color::mix_many(&weighted[..], *self)
+    }
+
+    /// Returns the weighted mean of the given colors in the color space, or
+    /// None if any weight is not finite or the weights do not have a positive
+    /// sum. Hue channels are averaged around the hue circle.
+    pub(crate) fn weighted_mean(&self, colors: &[(Color, f32)])
+        -> Option<Color>
+    {
+        if colors.iter().any(|(_, w)| !w.is_finite()) { return None; }
+        let total: f32 = colors.iter().map(|(_, w)| w).sum();
+        if !total.is_finite() || total <= 0.0 { return None; }
+
+        let mut res = [0.0; 3];
+        let (mut hue_x, mut hue_y) = (0.0, 0.0);
+        for (color, weight) in colors {
+            let channels = self.channels(*color);
+            let w = weight / total;
+            if self.has_hue() {
+                let angle = channels[0] * std::f32::consts::PI * 2.0;
+                hue_x += angle.cos() * w;
+                hue_y += angle.sin() * w;
+            } else {
+                res[0] += channels[0] * w;
+            }
+            res[1] += channels[1] * w;
+            res[2] += channels[2] * w;
+        }
+        if self.has_hue() {
+            res[0] = hue_y.atan2(hue_x) / (std::f32::consts::PI * 2.0);
+        }
+        Some(self.color_from_channels(res))
+    }
+
+    /// Returns true if the first channel of the color space is a hue.
+    fn has_hue(&self) -> bool {
+        match self {
+            ColorSpace::Rgb => false,
+            ColorSpace::Hsv => true,
+            ColorSpace::Hsl => true,
+            ColorSpace::Lab => false,
+        }
+    }
+
+    /// Returns the channels of the given color in the color space. Hue
+    /// channels are normalized to the range [0.0, 1.0], and CIELab channels
+    /// are scaled to approximately the same range.
+    fn channels(&self, color: Color) -> [f32; 3] {
         match self {
-            ColorSpace::Rgb => {
-                let [ra, ga, ba] = a.into().rgb_ratios();
-                let [rb, gb, bb] = b.into().rgb_ratios();
-                Rgb::from([
-                    (f)(ra, rb),
-                    (f)(ga, gb),
-                    (f)(ba, bb),
-                ]).into()
+            ColorSpace::Rgb => color.rgb_ratios(),
+            ColorSpace::Hsv => {
+                let [h, s, v] = color.hsv_components();
+                [h / 360.0, s, v]
+            },
+            ColorSpace::Hsl => {
+                let [h, s, l] = color.hsl_components();
+                [h / 360.0, s, l]
+            },
+            ColorSpace::Lab => {
+                let [l, a, b] = color.lab_components();
+                [l / 100.0, a / 256.0 + 0.5, b / 256.0 + 0.5]
             },
         }
     }
+
+    /// Constructs a color from the given channels in the color space. Hue
+    /// channels are wrapped into the range [0.0, 1.0].
+    fn color_from_channels(&self, channels: [f32; 3]) -> Color {
+        let [x, y, z] = channels;
+        match self {
+            ColorSpace::Rgb => Rgb::from([x, y, z]).into(),
+            ColorSpace::Hsv => Hsv::from([
+                x.rem_euclid(1.0) * 360.0,
+                y,
+                z,
+            ]).into(),
+            ColorSpace::Hsl => Hsl::from([
+                x.rem_euclid(1.0) * 360.0,
+                y,
+                z,
+            ]).into(),
+            ColorSpace::Lab => color_from_lab([
+                x * 100.0,
+                (y - 0.5) * 256.0,
+                (z - 0.5) * 256.0,
+            ]),
+        }
+    }
+}
+
+/// Clamps the given channel value to the range [0.0, 1.0], replacing NaN with
+/// 0.0.
+fn clamp_channel(value: f32) -> f32 {
+    if value.is_nan() { return 0.0; }
+    value.max(0.0).min(1.0)
+}
+
+/// Performs a cubic hermite interpolation between two values with the given
+/// boundary derivatives.
+fn cubic_hermite(a: f32, b: f32, m0: f32, m1: f32, t: f32) -> f32 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (2.0 * t3 - 3.0 * t2 + 1.0) * a
+        + (t3 - 2.0 * t2 + t) * m0
+        + (-2.0 * t3 + 3.0 * t2) * b
+        + (t3 - t2) * m1
 }
 
 impl Default for ColorSpace {
@@ -729,12 +1527,13 @@ impl std::str::FromStr for ColorSpace {
 
         // Perform parse.
         let ast = ast_expr(lexer)
-            .finish()?;
+            .finish()
+            .map_err(|failure| ParseErrorKind::InvalidSyntax.attach(failure))?;
 
         ColorSpace::match_expr(ast, column_metrics)
             .map_err(|parse_error| FailureOwned {
                 parse_error: parse_error.into_owned(),
-                source: None,
+                source: Some(Box::new(ParseErrorKind::InvalidColorSpace)),
             })
     }
 }
@@ -744,6 +1543,9 @@ impl std::fmt::Display for ColorSpace {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
             ColorSpace::Rgb => "RGB",
+            ColorSpace::Hsv => "HSV",
+            ColorSpace::Hsl => "HSL",
+            ColorSpace::Lab => "LAB",
         })
     }
 }
@@ -752,27 +1554,57 @@ impl std::fmt::Display for ColorSpace {
 ////////////////////////////////////////////////////////////////////////////////
 // Interpolate
 ////////////////////////////////////////////////////////////////////////////////
+/// The default tolerance used when validating interpolation amounts. This
+/// allows for rounding errors in parsed values near the bounds of the
+/// interpolation range.
+pub const INTERPOLATE_EPSILON: f32 = 0.000_001;
+
 /// Interpolation of colors.
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Interpolate {
     /// The color space whose channels to apply the interpolation to.
+    #[serde(skip_serializing_if = "is_default")]
     pub color_space: ColorSpace,
     /// The interpolate function.
+    #[serde(skip_serializing_if = "is_default")]
     pub interpolate_fn: InterpolateFunction,
     /// The interpolation amount.
+    #[serde(skip_serializing_if = "is_one")]
     pub amount: f32,
+    /// Whether to interpolate gamma-encoded RGB channels as linear light
+    /// values. This only affects the RGB color space, and defaults to false,
+    /// which interpolates the gamma-encoded channels directly.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub linear_light: bool,
 }
 
 impl Interpolate {
-    /// Validates the interpolation.
+    /// Validates the interpolation using the default tolerance,
+    /// [`INTERPOLATE_EPSILON`].
+    ///
+    /// [`INTERPOLATE_EPSILON`]: constant.INTERPOLATE_EPSILON.html
     pub fn validate(self) -> Result<Self, PaletteError> {
-        if self.amount < 0.0 || self.amount > 1.0 {
+        self.validate_with_epsilon(INTERPOLATE_EPSILON)
+    }
+
+    /// Validates the interpolation using the given tolerance. Amounts which
+    /// lie outside of the range [0.0, 1.0] by no more than the tolerance are
+    /// clamped into the range. Infinite and NaN amounts are rejected.
+    pub fn validate_with_epsilon(mut self, epsilon: f32)
+        -> Result<Self, PaletteError>
+    {
+        if self.amount.is_nan()
+            || self.amount < -epsilon
+            || self.amount > 1.0 + epsilon
+        {
             Err(PaletteError::InvalidInputValue {
                 msg: format!("interpolate value {} must lie within the \
                     range [0.0, 1.0].", self.amount).into()
             })
         } else {
+            self.amount = self.amount.max(0.0).min(1.0);
             Ok(self)
         }
     }
@@ -783,6 +1615,17 @@ impl Interpolate {
             A: Into<Color> + Sized,
             B: Into<Color> + Sized,
     {
+        if self.linear_light && self.color_space == ColorSpace::Rgb {
+            let a: Color = a.into();
+            let b: Color = b.into();
+            return self.interpolate_fn
+                .apply(
+                    self.color_space,
+                    a.srgb_to_linear(),
+                    b.srgb_to_linear(),
+                    self.amount)
+                .linear_to_srgb();
+        }
         self.interpolate_fn.apply(self.color_space, a, b, self.amount)
     }
 }
@@ -793,6 +1636,7 @@ impl Default for Interpolate {
             color_space: ColorSpace::default(),
             interpolate_fn: InterpolateFunction::default(),
             amount: 1.0,
+            linear_light: false,
         }
     }
 }
@@ -809,12 +1653,13 @@ impl std::str::FromStr for Interpolate {
 
         // Perform parse.
         let ast = ast_expr(lexer)
-            .finish()?;
+            .finish()
+            .map_err(|failure| ParseErrorKind::InvalidSyntax.attach(failure))?;
 
         Interpolate::match_expr(ast, column_metrics)
             .map_err(|parse_error| FailureOwned {
                 parse_error: parse_error.into_owned(),
-                source: None,
+                source: Some(Box::new(ParseErrorKind::InvalidInterpolate)),
             })
     }
 }
@@ -823,26 +1668,36 @@ impl std::str::FromStr for Interpolate {
 /// Interpolation range for ramps.
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct InterpolateRange {
     /// The color space whose channels to apply the interpolation to.
+    #[serde(skip_serializing_if = "is_default")]
     pub color_space: ColorSpace,
     /// The interpolate function.
+    #[serde(skip_serializing_if = "is_default")]
     pub interpolate_fn: InterpolateFunction,
     /// The start point of the range.
+    #[serde(skip_serializing_if = "is_default")]
     pub start: f32,
     /// The end point of the range.
+    #[serde(skip_serializing_if = "is_one")]
     pub end: f32,
+    /// Whether to interpolate gamma-encoded RGB channels as linear light
+    /// values. This only affects the RGB color space, and defaults to false.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub linear_light: bool,
 }
 
 impl InterpolateRange {
-    /// Validates the interpolation ranges.
+    /// Validates the interpolation ranges. Infinite and NaN range values are
+    /// rejected.
     pub fn validate(self) -> Result<Self, PaletteError> {
-        if self.start < 0.0 || self.start > 1.0 {
+        if !(0.0..=1.0).contains(&self.start) {
             Err(PaletteError::InvalidInputValue {
                 msg: format!("interpolate start value {} must lie within the \
                     range [0.0, 1.0].", self.start).into()
             })
-        } else if self.end < 0.0 || self.end > 1.0 {
+        } else if !(0.0..=1.0).contains(&self.end) {
             Err(PaletteError::InvalidInputValue {
                 msg: format!("interpolate end value {} must lie within the \
                     range [0.0, 1.0].", self.end).into()
@@ -867,12 +1722,36 @@ impl InterpolateRange {
                     color_space: self.color_space,
                     interpolate_fn: self.interpolate_fn,
                     amount,
+                    linear_light: self.linear_light,
                 },
             });
             amount += inc;
         }
         exprs
     }
+
+    /// Compute the `BlendExpr`s for a ramp using the given `BinaryBlendFunction`
+    /// and an explicit interpolation amount for each `BlendExpr`. The start
+    /// and end of the range are ignored.
+    pub fn blend_exprs_with_amounts(
+        &self,
+        amounts: &[f32],
+        blend_fn: &BlendFunction)
+        -> Vec<BlendExpr>
+    {
+        amounts
+            .iter()
+            .map(|amount| BlendExpr {
+                blend_fn: blend_fn.clone(),
+                interpolate: Interpolate {
+                    color_space: self.color_space,
+                    interpolate_fn: self.interpolate_fn,
+                    amount: *amount,
+                    linear_light: self.linear_light,
+                },
+            })
+            .collect()
+    }
 }
 
 impl Default for InterpolateRange {
@@ -882,6 +1761,7 @@ impl Default for InterpolateRange {
             interpolate_fn: InterpolateFunction::default(),
             start: 0.0,
             end: 1.0,
+            linear_light: false,
         }
     }
 }
@@ -898,12 +1778,13 @@ impl std::str::FromStr for InterpolateRange {
 
         // Perform parse.
         let ast = ast_expr(lexer)
-            .finish()?;
+            .finish()
+            .map_err(|failure| ParseErrorKind::InvalidSyntax.attach(failure))?;
 
         InterpolateRange::match_expr(ast, column_metrics)
             .map_err(|parse_error| FailureOwned {
                 parse_error: parse_error.into_owned(),
-                source: None,
+                source: Some(Box::new(ParseErrorKind::InvalidInterpolateRange)),
             })
     }
 }
@@ -917,6 +1798,11 @@ pub enum InterpolateFunction {
     Linear,
     /// Cubic interpolation with the given boundary derivatives.
     Cubic(f32, f32),
+    /// Linear interpolation with the amount eased by the smoothstep function.
+    Smoothstep,
+    /// Linear interpolation with the amount eased by a quadratic ease-in-out
+    /// function.
+    EaseInOut,
 }
 
 impl InterpolateFunction {
@@ -930,6 +1816,13 @@ impl InterpolateFunction {
         use ColorSpace::*;
         use InterpolateFunction::*;
 
+        match self {
+            Smoothstep |
+            EaseInOut  => return Linear
+                .apply(color_space, a, b, self.ease(amount)),
+            _ => (),
+        }
+
         match (color_space, self) {
             (Rgb, Linear) => Color::rgb_linear_interpolate(
                     a.into(),
@@ -943,7 +1836,38 @@ impl InterpolateFunction {
                     *m0,
                     *m1,
                     amount)
-                .into()
+                .into(),
+
+            (Lab, Linear) => a.into().lab_linear_interpolate(
+                &b.into(),
+                amount),
+
+            (Hsv, _) |
+            (Hsl, _) |
+            (Lab, _) => color_space.interpolate_channels(
+                a.into(),
+                b.into(),
+                *self,
+                amount),
+
+            // Eased functions are handled above.
+            (Rgb, _) => unreachable!(),
+        }
+    }
+
+    /// Returns the eased interpolation amount for the function. The easing
+    /// functions map 0.0 and 1.0 to themselves exactly. Functions without
+    /// easing return the amount unchanged.
+    pub fn ease(&self, amount: f32) -> f32 {
+        use InterpolateFunction::*;
+        match self {
+            Smoothstep => amount * amount * (3.0 - 2.0 * amount),
+            EaseInOut if amount < 0.5 => 2.0 * amount * amount,
+            EaseInOut  => {
+                let t = -2.0 * amount + 2.0;
+                1.0 - t * t / 2.0
+            },
+            _          => amount,
         }
     }
 }
@@ -966,12 +1890,35 @@ impl std::str::FromStr for InterpolateFunction {
 
         // Perform parse.
         let ast = ast_expr(lexer)
-            .finish()?;
+            .finish()
+            .map_err(|failure| ParseErrorKind::InvalidSyntax.attach(failure))?;
 
         InterpolateFunction::match_expr(ast, column_metrics)
             .map_err(|parse_error| FailureOwned {
                 parse_error: parse_error.into_owned(),
-                source: None,
+                source: Some(Box::new(
+                    ParseErrorKind::InvalidInterpolateFunction)),
             })
     }
 }
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Serialization helpers
+////////////////////////////////////////////////////////////////////////////////
+/// Returns true if the given value is equal to its default value.
+fn is_default<T>(value: &T) -> bool
+    where T: Default + PartialEq
+{
+    *value == T::default()
+}
+
+/// Returns true if the given value is equal to 1.0.
+fn is_one(value: &f32) -> bool {
+    *value == 1.0
+}
+
+/// Returns 1.0.
+fn one() -> f32 {
+    1.0
+}
@@ -22,6 +22,8 @@ use crate::error::FileError;
 use crate::error::FileErrorContext as _;
 use crate::error::PaletteError;
 use crate::palette::BasicPalette;
+use crate::palette::basic::LegacyBasicPalette;
+use crate::palette::basic::RonVersionProbe;
 use crate::palette::History;
 use crate::palette::InsertExpr;
 use crate::palette::Operation;
@@ -176,42 +178,92 @@ impl Palette {
 
     /// Constructs a new `Palette` by parsing data from the given file.
     pub fn read_from_file(file: &mut File) -> Result<Self, FileError> {
-        Palette::parse_ron_from_file(file)
-    }
-
-    /// Parses a `Palette` from a file using the RON format.
-    fn parse_ron_from_file(file: &mut File) -> Result<Self, FileError> {
         let len = file.metadata()
             .context("Failed to read file metadata")?
             .len();
         let mut buf = Vec::with_capacity(len as usize);
         let _ = file.read_to_end(&mut buf)
             .context("Failed to read palette file")?;
+        Palette::parse_ron_from_bytes(&buf)
+    }
+
+    /// Constructs a new `Palette` by parsing data from the given reader.
+    pub fn read_from_reader<R>(reader: &mut R) -> Result<Self, FileError>
+        where R: Read
+    {
+        let mut buf = Vec::new();
+        let _ = reader.read_to_end(&mut buf)
+            .context("Failed to read palette data")?;
+        Palette::parse_ron_from_bytes(&buf)
+    }
 
+    /// Parses a `Palette` from the given bytes using the RON format.
+    ///
+    /// Palettes written before format version 2 are upgraded, and their
+    /// command history is discarded.
+    fn parse_ron_from_bytes(buf: &[u8]) -> Result<Self, FileError> {
         use ron::de::Deserializer;
         let mut d = Deserializer::from_bytes(&buf)
             .context("Failed deserializing RON file")?;
-        let palette = Palette::deserialize(&mut d)
+        let probe = PaletteVersionProbe::deserialize(&mut d)
             .context("Failed parsing RON file")?;
+        probe.inner.check_supported()?;
+
+        let mut d = Deserializer::from_bytes(&buf)
+            .context("Failed deserializing RON file")?;
+        let mut palette = if probe.inner.is_legacy() {
+            let legacy = LegacyPalette::deserialize(&mut d)
+                .context("Failed parsing RON file")?;
+            Palette {
+                load_status: LoadStatus::default(),
+                inner: legacy.inner.into(),
+                history: None,
+            }
+        } else {
+            Palette::deserialize(&mut d)
+                .context("Failed parsing RON file")?
+        };
         d.end()
             .context("Failed parsing RON file")?;
+        palette.inner.migrate();
         Ok(palette)
     }
     
     /// Writes the `Palette` to the given file.
+    ///
+    /// ### Output stability
+    ///
+    /// The generated output is deterministic: cells, names, positions, and
+    /// groups are always written in sorted key order. Reading a palette and
+    /// immediately writing it back produces byte-identical output, provided
+    /// that it was originally written by this method.
     pub fn write_to_file(&self, file: &mut File) -> Result<(), FileError> {
-        self.generate_ron_into_file(file)
+        self.generate_ron_into_writer(file)
     }
 
-    /// Generates a RON formatted `Palette` by serializing into the given file.
-    fn generate_ron_into_file(&self, file: &mut File) -> Result<(), FileError> {
+    /// Writes the `Palette` to the given writer. The output is identical to
+    /// that of [`write_to_file`].
+    ///
+    /// [`write_to_file`]: Palette::write_to_file
+    pub fn write_to_writer<W>(&self, writer: &mut W) -> Result<(), FileError>
+        where W: Write
+    {
+        self.generate_ron_into_writer(writer)
+    }
+
+    /// Generates a RON formatted `Palette` by serializing into the given
+    /// writer.
+    fn generate_ron_into_writer<W>(&self, writer: &mut W)
+        -> Result<(), FileError>
+        where W: Write
+    {
         tracing::debug!("Serializing & writing Palette file.");
         let pretty = PrettyConfig::new()
             .with_depth_limit(3)
             .with_extensions(ron::extensions::Extensions::IMPLICIT_SOME);
         let s = to_string_pretty(self, pretty)?;
 
-        file.write_all(s.as_bytes())
+        writer.write_all(s.as_bytes())
             .map_err(FileError::from)
     }
 
@@ -483,8 +535,9 @@ impl Palette {
     /// Changes the palette's history setting.
     pub fn set_history_option(&mut self, setting: HistorySetOption) {
         match setting {
-            HistorySetOption::Clear => {
-                self.history = Some(History::new());
+            HistorySetOption::Clear => match self.history.as_mut() {
+                Some(history) => history.clear(),
+                None          => self.history = Some(History::new()),
             },
 
             HistorySetOption::Enable => if self.history.is_none() {
@@ -622,6 +675,32 @@ impl Palette {
             _             => 0,
         }
     }
+
+    /// Unapplies the latest set of operations, returning an error if the
+    /// history cannot be applied to the palette.
+    ///
+    /// Returns the number of undo operations successfully performed. This may
+    /// be fewer than the number provided if there are fewer undo operations
+    /// recorded than requested.
+    pub fn try_undo(&mut self, count: usize) -> Result<usize, PaletteError> {
+        match self.history.as_mut() {
+            Some(history) => self.inner.try_undo(history, count),
+            _             => Ok(0),
+        }
+    }
+
+    /// Reapplies the latest set of undone operations, returning an error if
+    /// the history cannot be applied to the palette.
+    ///
+    /// Returns the number of redo operations successfully performed. This may
+    /// be fewer than the number provided if there are fewer redo operations
+    /// recorded than requested.
+    pub fn try_redo(&mut self, count: usize) -> Result<usize, PaletteError> {
+        match self.history.as_mut() {
+            Some(history) => self.inner.try_redo(history, count),
+            _             => Ok(0),
+        }
+    }
 }
 
 impl Default for Palette {
@@ -637,3 +716,26 @@ impl PartialEq for Palette {
         // NOTE: This comparison ignores the command history.
     }
 }
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PaletteVersionProbe
+////////////////////////////////////////////////////////////////////////////////
+/// The format version of a serialized `Palette`'s inner `BasicPalette`.
+#[derive(Deserialize)]
+struct PaletteVersionProbe {
+    /// The inner palette's version.
+    inner: RonVersionProbe,
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// LegacyPalette
+////////////////////////////////////////////////////////////////////////////////
+/// The RON representation of a `Palette` before format version 2. The command
+/// history is not read, as its operations hold cells in the legacy format.
+#[derive(Deserialize)]
+struct LegacyPalette {
+    /// The internal palette data.
+    inner: LegacyBasicPalette,
+}
@@ -49,6 +49,10 @@ pub struct History {
     /// The state of the cursor.
     #[serde(skip)]
     cursor_state: CursorState,
+    /// The maximum number of undo operations to retain, or None if the
+    /// history is unbounded.
+    #[serde(default)]
+    max_depth: Option<usize>,
 }
 
 
@@ -60,9 +64,70 @@ impl History {
             ops: Vec::with_capacity(8),
             cursor: 0,
             cursor_state: CursorState::default(),
+            max_depth: None,
         }
     }
 
+    /// Returns the maximum number of undo operations retained by the history,
+    /// or None if the history is unbounded.
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Sets the maximum number of undo operations retained by the history.
+    /// If the history currently holds more undo operations than the given
+    /// depth, the oldest are discarded. Redo operations are unaffected.
+    pub fn set_max_depth(&mut self, max_depth: Option<usize>) {
+        assert_eq!(self.cursor_state, CursorState::Valid);
+        self.max_depth = max_depth;
+        self.evict_oldest();
+    }
+
+    /// Discards the oldest undo operations beyond the maximum depth.
+    fn evict_oldest(&mut self) {
+        if let Some(max_depth) = self.max_depth {
+            if self.cursor > max_depth {
+                let excess = self.cursor - max_depth;
+                let _ = self.ops.drain(..excess);
+                self.cursor -= excess;
+            }
+        }
+    }
+
+    /// Removes all undo and redo operations from the history.
+    pub fn clear(&mut self) {
+        assert_eq!(self.cursor_state, CursorState::Valid);
+        self.ops.clear();
+        self.cursor = 0;
+    }
+
+    /// Removes all redo operations from the history.
+    pub fn truncate_redo(&mut self) {
+        assert_eq!(self.cursor_state, CursorState::Valid);
+        self.ops.truncate(self.cursor);
+    }
+
+    /// Returns true if there are any undo operations available.
+    pub fn can_undo(&self) -> bool {
+        self.undo_count() > 0
+    }
+
+    /// Returns true if there are any redo operations available.
+    pub fn can_redo(&self) -> bool {
+        self.redo_count() > 0
+    }
+
+    /// Returns the number of undo operations retained by the history. This
+    /// never exceeds the maximum depth.
+    pub fn undo_len(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns the number of redo operations retained by the history.
+    pub fn redo_len(&self) -> usize {
+        self.ops.len() - self.cursor
+    }
+
     /// Returns the number of undo operations currently available.
     pub fn undo_count(&self) -> usize {
         self.cursor
@@ -74,20 +139,15 @@ impl History {
     }
 
     /// Pushes a new set of undo operations onto the history at the current
-    /// cursor position. This will truncate the history if there are any ops
-    /// beyond the cursor.
+    /// cursor position. Any redo operations are discarded.
     pub fn push_undo_ops(&mut self, ops: Vec<Operation>) {
         assert_eq!(self.cursor_state, CursorState::Valid);
         tracing::trace!("History: cursor: {}, len: {}", self.cursor, self.ops.len());
 
-        if self.cursor >= self.ops.len() {
-            self.ops.push(ops);
-            self.cursor = self.ops.len();
-        } else {
-            self.ops[self.cursor] = ops;
-            self.ops.truncate(self.cursor);
-            self.cursor += 1;
-        }
+        self.truncate_redo();
+        self.ops.push(ops);
+        self.cursor = self.ops.len();
+        self.evict_oldest();
     }
 
     /// Performs a complete undo using the given operation transform function.
@@ -136,6 +196,70 @@ impl History {
         }
     }
 
+    /// Performs a complete undo using the given fallible operation transform
+    /// function. Returns true if an undo was performed.
+    ///
+    /// If the function returns an error, the history is restored to its
+    /// previous state and the error is returned.
+    ///
+    /// ### Parameters
+    /// + `f`: The operation transform function. This function is responsible
+    ///   for receiving the undo operations and returning the redo operations
+    ///   for them. The function will only be called if there are available
+    ///   undo ops.
+    pub fn try_undo_with<F, E>(&mut self, f: F) -> Result<bool, E>
+        where F: FnOnce(&[Operation]) -> Result<Vec<Operation>, E>
+    {
+        let redo_ops = match self.pop_undo_ops() {
+            Some(undo_ops) => (f)(undo_ops),
+            None           => return Ok(false),
+        };
+
+        match redo_ops {
+            Ok(redo_ops) => {
+                self.set_current_redo_ops(redo_ops);
+                Ok(true)
+            },
+            Err(e) => {
+                self.cursor += 1;
+                self.cursor_state = CursorState::Valid;
+                Err(e)
+            },
+        }
+    }
+
+    /// Performs a complete redo using the given fallible operation transform
+    /// function. Returns true if a redo was performed.
+    ///
+    /// If the function returns an error, the history is restored to its
+    /// previous state and the error is returned.
+    ///
+    /// ### Parameters
+    /// + `f`: The operation transform function. This function is responsible
+    ///   for receiving the redo operations and returning the undo operations
+    ///   for them. The function will only be called if there are available
+    ///   redo ops.
+    pub fn try_redo_with<F, E>(&mut self, f: F) -> Result<bool, E>
+        where F: FnOnce(&[Operation]) -> Result<Vec<Operation>, E>
+    {
+        let undo_ops = match self.pop_redo_ops() {
+            Some(redo_ops) => (f)(redo_ops),
+            None           => return Ok(false),
+        };
+
+        match undo_ops {
+            Ok(undo_ops) => {
+                self.set_current_undo_ops(undo_ops);
+                Ok(true)
+            },
+            Err(e) => {
+                self.cursor -= 1;
+                self.cursor_state = CursorState::Valid;
+                Err(e)
+            },
+        }
+    }
+
     /// Retrieves the next set of undo operations and moves the cursor. This
     /// operation must be followed by `set_current_redo_ops` to ensure that the
     /// history remains in a valid state.
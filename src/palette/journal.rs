@@ -0,0 +1,115 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Append-only palette operation journal.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::error::FileError;
+use crate::error::FileErrorContext as _;
+use crate::palette::Operation;
+
+// External library imports.
+use ron::ser::to_string;
+
+// Standard library imports.
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+
+////////////////////////////////////////////////////////////////////////////////
+// SharedJournal
+////////////////////////////////////////////////////////////////////////////////
+/// A `PaletteJournal` which can be attached to a palette to record each
+/// operation it applies. See [`BasicPalette::set_journal`].
+///
+/// [`BasicPalette::set_journal`]: crate::palette::BasicPalette::set_journal
+pub type SharedJournal = Arc<Mutex<PaletteJournal<Box<dyn Write + Send>>>>;
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PaletteJournal
+////////////////////////////////////////////////////////////////////////////////
+/// An append-only journal of applied palette `Operation`s.
+///
+/// Each operation is written as a single line of RON and flushed immediately,
+/// so that the journal is complete up to the last applied operation if the
+/// process exits unexpectedly. A journal can be replayed onto the palette it
+/// was started from using [`BasicPalette::replay_journal`].
+///
+/// [`BasicPalette::replay_journal`]: crate::palette::BasicPalette::replay_journal
+pub struct PaletteJournal<W> {
+    /// The journal output.
+    writer: W,
+    /// The number of operations written to the journal.
+    len: usize,
+}
+
+impl<W> PaletteJournal<W> where W: Write {
+    /// Constructs a new `PaletteJournal` which appends to the given writer.
+    pub fn new(writer: W) -> Self {
+        PaletteJournal {
+            writer,
+            len: 0,
+        }
+    }
+
+    /// Appends the given operation to the journal.
+    pub fn append(&mut self, op: &Operation) -> Result<(), FileError> {
+        let mut line = to_string(op)
+            .context("Failed to serialize journal entry")?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())
+            .context("Failed to write journal entry")?;
+        self.writer.flush()
+            .context("Failed to flush journal")?;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Appends each of the given operations to the journal.
+    pub fn append_all(&mut self, ops: &[Operation]) -> Result<(), FileError> {
+        for op in ops {
+            self.append(op)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of operations written to the journal.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no operations have been written to the journal.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Consumes the journal, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W> PaletteJournal<W> where W: Write + Send + 'static {
+    /// Converts the journal into a `SharedJournal`.
+    pub fn into_shared(self) -> SharedJournal {
+        Arc::new(Mutex::new(PaletteJournal {
+            writer: Box::new(self.writer),
+            len: self.len,
+        }))
+    }
+}
+
+impl<W> std::fmt::Debug for PaletteJournal<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PaletteJournal")
+            .field("len", &self.len)
+            .finish()
+    }
+}
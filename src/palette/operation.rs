@@ -13,6 +13,7 @@ use crate::cell::Cell;
 use crate::cell::CellRef;
 use crate::cell::Position;
 use crate::cell::PositionSelector;
+use crate::color::Color;
 use crate::palette::Expr;
 
 // External library imports.
@@ -48,6 +49,24 @@ pub enum Operation {
         cell_ref: CellRef<'static>,
     },
 
+    /// Swaps the `Cell`s at the referenced indices. Positions, names, and
+    /// groups remain assigned to their indices.
+    SwapCells {
+        /// A reference to the first `Cell` to swap.
+        a: CellRef<'static>,
+        /// A reference to the second `Cell` to swap.
+        b: CellRef<'static>,
+    },
+
+    /// Moves the referenced `Cell` to an unoccupied index. The cell's
+    /// position and group memberships are moved with it.
+    MoveCell {
+        /// A reference to the `Cell` to move.
+        from: CellRef<'static>,
+        /// The index to move the cell to.
+        to: u32,
+    },
+
     ////////////////////////////////////////////////////////////////////////////
     // Name operations
     ////////////////////////////////////////////////////////////////////////////
@@ -112,6 +131,14 @@ pub enum Operation {
         cell_ref: CellRef<'static>,
     },
 
+    /// Renames a group, preserving the order of its members.
+    RenameGroup {
+        /// The group to rename.
+        from: Cow<'static, str>,
+        /// The new name of the group.
+        to: Cow<'static, str>,
+    },
+
     ////////////////////////////////////////////////////////////////////////////
     // Expr operations
     ////////////////////////////////////////////////////////////////////////////
@@ -125,6 +152,57 @@ pub enum Operation {
     },
 
 
+    /// Sets the description for a cell.
+    SetDescription {
+        /// A reference to the `Cell` to set the description for.
+        cell_ref: CellRef<'static>,
+        /// The description to set.
+        description: Cow<'static, str>,
+    },
+
+    /// Adds a tag to a cell.
+    AddTag {
+        /// A reference to the `Cell` to add the tag to.
+        cell_ref: CellRef<'static>,
+        /// The tag to add.
+        tag: Cow<'static, str>,
+    },
+
+    /// Removes a tag from a cell.
+    RemoveTag {
+        /// A reference to the `Cell` to remove the tag from.
+        cell_ref: CellRef<'static>,
+        /// The tag to remove.
+        tag: Cow<'static, str>,
+    },
+
+    /// Locks a cell, preventing modifications to it.
+    LockCell {
+        /// A reference to the `Cell` to lock.
+        cell_ref: CellRef<'static>,
+    },
+
+    /// Unlocks a cell.
+    UnlockCell {
+        /// A reference to the `Cell` to unlock.
+        cell_ref: CellRef<'static>,
+    },
+
+    /// Sets the override color for a cell.
+    SetOverride {
+        /// A reference to the `Cell` to set the override color for.
+        cell_ref: CellRef<'static>,
+        /// The override color to set.
+        color: Color,
+    },
+
+    /// Clears the override color for a cell.
+    ClearOverride {
+        /// A reference to the `Cell` to clear the override color for.
+        cell_ref: CellRef<'static>,
+    },
+
+
     ////////////////////////////////////////////////////////////////////////////
     // Positioning operations
     ////////////////////////////////////////////////////////////////////////////
@@ -19,7 +19,9 @@ use crate::parse::AstExprMatch as _;
 use crate::parse::AtmaScanner;
 use crate::parse::AtmaToken;
 use crate::parse::cell_ref;
+use crate::parse::cell_selection;
 use crate::parse::color;
+use crate::parse::position_selector;
 use crate::parse::string;
 use crate::parse::uint;
 
@@ -171,6 +173,32 @@ impl<'text> PrimaryExpr<'text> {
 // Stmt Parsers
 ////////////////////////////////////////////////////////////////////////////////
 
+/// Parses a complete script, consisting of statements separated by
+/// semicolons, up to the end of the text. If a statement fails to parse, the
+/// returned error is spanned at the failing statement.
+pub fn script<'text, Cm>(lexer: Lexer<'text, AtmaScanner, Cm>)
+    -> ParseResult<'text, AtmaScanner, Cm, Vec<Stmt>>
+    where Cm: ColumnMetrics,
+{
+    let span = span!(Level::DEBUG, "script");
+    let _enter = span.enter();
+
+    let (stmts, succ) = stmts
+        (lexer)?
+        .take_value();
+
+    // Try to parse end-of-text, and if it fails, return the error from
+    // a stmt parse.
+    match end_of_text
+        (succ.lexer)
+    {
+        Ok(succ)  => Ok(succ).map_value(|_| stmts),
+        Err(fail) => stmt
+            (fail.lexer)
+            .map_value(|_| stmts),
+    }
+}
+
 pub fn stmts<'text, Cm>(mut lexer: Lexer<'text, AtmaScanner, Cm>)
     -> ParseResult<'text, AtmaScanner, Cm, Vec<Stmt>>
     where Cm: ColumnMetrics,
@@ -217,6 +245,16 @@ pub fn stmt<'text, Cm>(mut lexer: Lexer<'text, AtmaScanner, Cm>)
     }
     event!(Level::TRACE, "header_stmt failed");
 
+    // command statements
+    match command_stmt
+        (lexer.clone())
+        .trace_result(Level::TRACE, "command_stmt")
+    {
+        Ok(mut stmt) => return Ok(stmt),
+        Err(_) => (),
+    }
+    event!(Level::TRACE, "command_stmt failed");
+
     // expr statement
     match expr_stmt
         (lexer.clone())
@@ -232,7 +270,7 @@ pub fn stmt<'text, Cm>(mut lexer: Lexer<'text, AtmaScanner, Cm>)
         Some(_) => Err(Failure {
             parse_error: ParseError::new("unrecognized statement")
                 .with_span(
-                    "expected 'palette', 'page', 'line' or expression",
+                    "expected header, command, or expression",
                     lexer.parse_span(),
                     lexer.column_metrics()),
             lexer,
@@ -244,7 +282,7 @@ pub fn stmt<'text, Cm>(mut lexer: Lexer<'text, AtmaScanner, Cm>)
             Err(Failure {
                 parse_error: ParseError::new("empty statement")
                     .with_span(
-                    "expected 'palette', 'page', 'line' or expression",
+                    "expected header, command, or expression",
                     lexer.end_span(),
                     lexer.column_metrics()),
                 lexer,
@@ -290,6 +328,89 @@ pub fn expr_stmt<'text, Cm>(lexer: Lexer<'text, AtmaScanner, Cm>)
     }
 }
 
+pub fn command_stmt<'text, Cm>(lexer: Lexer<'text, AtmaScanner, Cm>)
+    -> ParseResult<'text, AtmaScanner, Cm, Stmt>
+    where Cm: ColumnMetrics,
+{
+    let span = span!(Level::DEBUG, "command_stmt");
+    let _enter = span.enter();
+
+    use AtmaToken::*;
+
+    // introducer
+    let (keyword, succ) = text(one(Ident))
+        (lexer)?
+        .take_value();
+    event!(Level::TRACE, "introducer: {:?}", keyword);
+
+    match keyword {
+        "insert" => left(
+                ast_expr,
+                one(Semicolon))
+            (succ.lexer.sublexer())
+            .and_then(match_insert_expr)
+            .map_value(|expr| Stmt::Expr { expr }),
+
+        "set" => left(
+                both(cell_ref, ast_expr),
+                one(Semicolon))
+            (succ.lexer.sublexer())
+            .and_then(|succ| {
+                let Success { value: (cell_ref, ast), lexer } = succ;
+                match_insert_expr(Success { value: ast, lexer })
+                    .map_value(|expr| Stmt::Set {
+                        cell_ref: cell_ref.into_static(),
+                        expr,
+                    })
+            }),
+
+        "name" => left(
+                both(string, position_selector),
+                one(Semicolon))
+            (succ.lexer)
+            .map_value(|(name, selector)| Stmt::Name {
+                name: name.into_owned().into(),
+                selector,
+            }),
+
+        "group" => left(
+                both(string, cell_selection),
+                one(Semicolon))
+            (succ.lexer)
+            .map_value(|(group, selection)| Stmt::Group {
+                group: group.into_owned().into(),
+                selection: selection.into_static(),
+            }),
+
+        _ => Err(Failure {
+            parse_error: ParseError::new("invalid command statement")
+                .with_span(
+                    "expected 'insert', 'set', 'name', or 'group'",
+                    succ.lexer.parse_span(),
+                    succ.lexer.column_metrics()),
+            lexer: succ.lexer,
+            source: None,
+        }),
+    }
+}
+
+/// Matches the parsed `AstExpr` as an `InsertExpr`.
+fn match_insert_expr<'text, Cm>(
+    succ: Success<'text, AtmaScanner, Cm, AstExpr<'text>>)
+    -> ParseResult<'text, AtmaScanner, Cm, InsertExpr>
+    where Cm: ColumnMetrics,
+{
+    let Success { lexer, value } = succ;
+    match InsertExpr::match_expr(value, lexer.column_metrics()) {
+        Ok(expr) => Ok(Success { lexer, value: expr }),
+        Err(parse_error) => Err(Failure {
+            parse_error,
+            lexer,
+            source: None,
+        }),
+    }
+}
+
 pub fn header_stmt<'text, Cm>(mut lexer: Lexer<'text, AtmaScanner, Cm>)
     -> ParseResult<'text, AtmaScanner, Cm, Stmt>
     where Cm: ColumnMetrics,
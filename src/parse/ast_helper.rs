@@ -33,6 +33,14 @@ use tephra::result::Spanned;
 pub struct Ident(pub String);
 
 
+////////////////////////////////////////////////////////////////////////////////
+// Argument types
+////////////////////////////////////////////////////////////////////////////////
+/// An AST matcher for parsing a variable-length argument list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarArgs<T>(pub Vec<T>);
+
+
 ////////////////////////////////////////////////////////////////////////////////
 // Selection types
 ////////////////////////////////////////////////////////////////////////////////
@@ -15,12 +15,17 @@ use crate::color::Xyz;
 use crate::color::Hsl;
 use crate::color::Hsv;
 use crate::color::Cmyk;
+#[cfg(feature = "named-colors")]
+use crate::color::named_color;
 use crate::parse::AstExpr;
 use crate::parse::PrimaryExpr;
 use crate::parse::UnaryExpr;
 use crate::parse::CallExpr;
 use crate::parse::Ident;
+use crate::parse::VarArgs;
 use crate::cell::CellRef;
+use crate::palette::ColorEncoding;
+use crate::palette::TaggedColor;
 
 // External library imports.
 use tephra::position::ColumnMetrics;
@@ -375,6 +380,39 @@ tuple_impls! {
 }
 
 
+impl<T> AstExprMatch for VarArgs<T> where T: AstExprMatch {
+    fn match_expr<'text, Cm>(ast_expr: AstExpr<'text>, metrics: Cm)
+        -> Result<Self, ParseError<'text, Cm>>
+        where Cm: ColumnMetrics
+    {
+        let span = span!(Level::DEBUG, "VarArgs::match_expr");
+        let _enter = span.enter();
+
+        let AstExpr::Unary(Spanned { span, value }) = ast_expr;
+        let ast_span = span;
+
+        match value {
+            UnaryExpr::Call(
+                CallExpr::Primary(
+                    PrimaryExpr::Tuple(tuple))) =>
+            {
+                let mut res = Vec::with_capacity(tuple.len());
+
+                for elem in tuple.into_iter() {
+                    res.push(T::match_expr(elem, metrics)?);
+                }
+
+                Ok(VarArgs(res))
+            },
+
+            _ => Err(ParseError::new("expected argument list")
+                .with_span("not a valid argument list",
+                    ast_span,
+                    metrics)),
+        }
+    }    
+}
+
 
 ////////////////////////////////////////////////////////////////////////////////
 // Array matcher
@@ -425,7 +463,17 @@ impl AstExprMatch for Color {
         -> Result<Self, ParseError<'text, Cm>>
         where Cm: ColumnMetrics
     {
-        let span = span!(Level::DEBUG, "Color::match_expr");
+        TaggedColor::match_expr(ast_expr, metrics)
+            .map(|tagged| tagged.color)
+    }    
+}
+
+impl AstExprMatch for TaggedColor {
+    fn match_expr<'text, Cm>(ast_expr: AstExpr<'text>, metrics: Cm)
+        -> Result<Self, ParseError<'text, Cm>>
+        where Cm: ColumnMetrics
+    {
+        let span = span!(Level::DEBUG, "TaggedColor::match_expr");
         let _enter = span.enter();
 
         let AstExpr::Unary(Spanned { span, value }) = ast_expr;
@@ -436,7 +484,14 @@ impl AstExprMatch for Color {
 
         match value {
             UnaryExpr::Call(CallExpr::Primary(PrimaryExpr::Color(color))) => {
-                Ok(color)
+                Ok(TaggedColor::from(color))
+            },
+
+            #[cfg(feature = "named-colors")]
+            UnaryExpr::Call(CallExpr::Primary(PrimaryExpr::Ident(ident))) => {
+                named_color(ident)
+                    .map(TaggedColor::from)
+                    .ok_or(default_error)
             },
 
             UnaryExpr::Call(CallExpr::Call { operand, args }) => {
@@ -450,35 +505,73 @@ impl AstExprMatch for Color {
                             PrimaryExpr::Tuple(args),
                             ast_span,
                             metrics)?;
-                        Ok(Color::from(Rgb::from([r, g, b])))
+                        Ok(TaggedColor {
+                            color: Color::from(Rgb::from([r, g, b])),
+                            encoding: ColorEncoding::Rgb,
+                            alpha: 1.0,
+                        })
+                    },
+                    "rgba" => {
+                        let (r, g, b, a) = <(f32, f32, f32, f32)>::match_primary_expr(
+                            PrimaryExpr::Tuple(args),
+                            ast_span,
+                            metrics)?;
+                        if a < 0.0 || a > 1.0 {
+                            return Err(ParseError::new("invalid RGBA color")
+                                .with_span(
+                                    "alpha value out of allowed range [0.0, 1.0]",
+                                    ast_span,
+                                    metrics));
+                        }
+                        Ok(TaggedColor {
+                            color: Color::from(Rgb::from([r, g, b])),
+                            encoding: ColorEncoding::Rgb,
+                            alpha: a,
+                        })
                     },
                     "xzy"  => {
                         let (x, y, z) = <(f32, f32, f32)>::match_primary_expr(
                             PrimaryExpr::Tuple(args),
                             ast_span,
                             metrics)?;
-                        Ok(Color::from(Xyz::from([x, y, z])))
+                        Ok(TaggedColor {
+                            color: Color::from(Xyz::from([x, y, z])),
+                            encoding: ColorEncoding::Xyz,
+                            alpha: 1.0,
+                        })
                     },
                     "hsl"  => {
                         let (h, s, l) = <(f32, f32, f32)>::match_primary_expr(
                             PrimaryExpr::Tuple(args),
                             ast_span,
                             metrics)?;
-                        Ok(Color::from(Hsl::from([h, s, l])))
+                        Ok(TaggedColor {
+                            color: Color::from(Hsl::from([h, s, l])),
+                            encoding: ColorEncoding::Hsl,
+                            alpha: 1.0,
+                        })
                     },
                     "hsv"  => {
                         let (h, s, v) = <(f32, f32, f32)>::match_primary_expr(
                             PrimaryExpr::Tuple(args),
                             ast_span,
                             metrics)?;
-                        Ok(Color::from(Hsv::from([h, s, v])))
+                        Ok(TaggedColor {
+                            color: Color::from(Hsv::from([h, s, v])),
+                            encoding: ColorEncoding::Hsv,
+                            alpha: 1.0,
+                        })
                     },
                     "cmyk" => {
                         let (c, m, y, k) = <(f32, f32, f32, f32)>::match_primary_expr(
                             PrimaryExpr::Tuple(args),
                             ast_span,
                             metrics)?;
-                        Ok(Color::from(Cmyk::from([c, m, y, k])))
+                        Ok(TaggedColor {
+                            color: Color::from(Cmyk::from([c, m, y, k])),
+                            encoding: ColorEncoding::Cmyk,
+                            alpha: 1.0,
+                        })
                     },
                     _      => Err(default_error)
                 }
@@ -94,17 +94,29 @@ pub fn rgb_hex_code<'text, Cm>(lexer: Lexer<'text, AtmaScanner, Cm>)
         (lexer)?
         .take_value();
 
-    if val.len() == 4 || val.len() == 7 {
-        let rgb = Rgb::from_hex_code(val).unwrap();
+    // The alpha channel of 8 digit codes is accepted, but discarded.
+    let digits = &val[1..];
+    let octets = match digits.len() {
+        3 => digits
+            .chars()
+            .map(|d| d.to_digit(16).map(|d| (d * 17) as u8))
+            .collect::<Option<Vec<u8>>>(),
+        6 | 8 => (0..3)
+            .map(|i| u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16).ok())
+            .collect::<Option<Vec<u8>>>(),
+        _ => None,
+    };
+
+    if let Some(octets) = octets {
         Ok(Success {
             lexer: succ.lexer,
-            value: rgb,
+            value: Rgb::from([octets[0], octets[1], octets[2]]),
         })
     } else {
         Err(Failure {
             parse_error: ParseError::new("invalid color code")
                 .with_span(
-                    format!("3 or 6 digits required, {} provided",
+                    format!("3, 6, or 8 digits required, {} provided",
                         val.len() - 1),
                     succ.lexer.token_span(),
                     succ.lexer.column_metrics()),
@@ -228,15 +228,30 @@ pub fn escaped_string<'text, Cm>(
         _ => unreachable!(),
     };
 
-    bracket_dynamic(
+    let (val, succ) = bracket_dynamic(
         any(&[StringOpenSingle, StringOpenDouble]),
         text(one(StringText)),
         corresponding)
-        (lexer)
-        .map_value(unescape)
+        (lexer)?
+        .take_value();
+
+    match unescape(val) {
+        Ok(val) => Ok(succ.map_value(|_| val)),
+        Err(msg) => Err(Failure {
+            parse_error: ParseError::new("invalid string escape")
+                .with_span(
+                    msg,
+                    succ.lexer.parse_span(),
+                    succ.lexer.column_metrics()),
+            lexer: succ.lexer,
+            source: None,
+        })
+    }
 }
 
-fn unescape<'text>(input: &'text str) -> Cow<'text, str> {
+fn unescape<'text>(input: &'text str)
+    -> Result<Cow<'text, str>, &'static str>
+{
     let span = span!(Level::DEBUG, "unescape");
     let _enter = span.enter();
 
@@ -265,10 +280,29 @@ fn unescape<'text>(input: &'text str) -> Cow<'text, str> {
                         _    => unreachable!(),
                     });
                 },
-                Some((_, 'u'))  => unimplemented!("unicode escapes unsupported"),
-                // TODO: Make this an error instead.
+                Some((_, 'u'))  => {
+                    if owned.is_none() {
+                        owned = Some(String::with_capacity(input.len()));
+                        owned.as_mut().unwrap().push_str(&input[0..i]);
+                    }
+
+                    // Check the opening brace and collect the hex digits.
+                    if chars.next().map(|(_, c)| c) != Some('{') {
+                        return Err("invalid unicode escape");
+                    }
+                    let hex: String = chars
+                        .by_ref()
+                        .take_while(|(_, c)| *c != '}')
+                        .map(|(_, c)| c)
+                        .collect();
+                    let c = u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(std::char::from_u32)
+                        .ok_or("invalid unicode escape")?;
+                    owned.as_mut().unwrap().push(c);
+                },
                 Some(_)    |
-                None       => panic!("invalid escape character"),
+                None       => return Err("invalid escape character"),
             }
         } else if let Some(owned) = owned.as_mut() {
             owned.push(c);
@@ -276,7 +310,7 @@ fn unescape<'text>(input: &'text str) -> Cow<'text, str> {
     }
 
     match owned {
-        Some(s) => s.into(),
-        None    => input.into(),
+        Some(s) => Ok(s.into()),
+        None    => Ok(input.into()),
     }
 }
@@ -22,14 +22,19 @@ use crate::palette::RampExpr;
 use crate::palette::InsertExpr;
 use crate::palette::BlendFunction;
 use crate::palette::BlendExpr;
+use crate::palette::MixExpr;
+use crate::palette::GradientExpr;
 use crate::palette::Interpolate;
 use crate::palette::UnaryBlendFunction;
 use crate::palette::UnaryBlendMethod;
 use crate::palette::BinaryBlendFunction;
 use crate::palette::BinaryBlendMethod;
+use crate::palette::ChannelOrder;
+use crate::palette::SwizzleFunction;
 use crate::palette::ColorSpace;
 use crate::palette::InterpolateFunction;
 use crate::palette::InterpolateRange;
+use crate::palette::TaggedColor;
 use crate::parse::AstExpr;
 use crate::parse::AstExprMatch;
 use crate::parse::AtmaScanner;
@@ -37,6 +42,7 @@ use crate::parse::AtmaToken;
 use crate::parse::cell_ref;
 use crate::parse::FunctionCall;
 use crate::parse::Ident;
+use crate::parse::VarArgs;
 use crate::parse::PositionOrIndex;
 use crate::parse::string;
 use crate::parse::uint;
@@ -57,6 +63,7 @@ use tephra::result::ParseError;
 use tephra::result::ParseResult;
 use tephra::result::Spanned;
 use tephra::result::ParseResultExt as _;
+use tephra::span::Span;
 use tephra::position::ColumnMetrics;
 use tracing::event;
 use tracing::Level;
@@ -94,9 +101,23 @@ impl AstExprMatch for InsertExpr {
         }
         event!(Level::TRACE, "InsertExpr match (Blend) fails.");
 
+        // Mix
+        match MixExpr::match_expr(ast_expr.clone(), metrics) {
+            Ok(expr) => return Ok(InsertExpr::Mix(expr)),
+            Err(_) => (),
+        }
+        event!(Level::TRACE, "InsertExpr match (Mix) fails.");
+
+        // Gradient
+        match GradientExpr::match_expr(ast_expr.clone(), metrics) {
+            Ok(expr) => return Ok(InsertExpr::Gradient(expr)),
+            Err(_) => (),
+        }
+        event!(Level::TRACE, "InsertExpr match (Gradient) fails.");
+
         // Color
-        match Color::match_expr(ast_expr.clone(), metrics) {
-            Ok(color) => return Ok(InsertExpr::Color(color)),
+        match TaggedColor::match_expr(ast_expr.clone(), metrics) {
+            Ok(tagged) => return Ok(InsertExpr::Color(tagged)),
             Err(_) => (),
         }
         event!(Level::TRACE, "InsertExpr match (Color) fails.");
@@ -113,6 +134,33 @@ impl AstExprMatch for InsertExpr {
         }
         event!(Level::TRACE, "InsertExpr match (Copy) fails.");
 
+        // RandomColor
+        match Ident::match_expr(ast_expr.clone(), metrics) {
+            Ok(Ident(i)) if i == "random" => {
+                return Ok(InsertExpr::RandomColor(None));
+            },
+            _ => (),
+        }
+        match <FunctionCall<Ident, ()>>::match_expr(
+            ast_expr.clone(),
+            metrics)
+        {
+            Ok(FunctionCall { operand: Ident(i), .. }) if i == "random" => {
+                return Ok(InsertExpr::RandomColor(None));
+            },
+            _ => (),
+        }
+        match <FunctionCall<Ident, (u64,)>>::match_expr(
+            ast_expr.clone(),
+            metrics)
+        {
+            Ok(FunctionCall { operand: Ident(i), args }) if i == "random" => {
+                return Ok(InsertExpr::RandomColor(Some(args.0)));
+            },
+            _ => (),
+        }
+        event!(Level::TRACE, "InsertExpr match (RandomColor) fails.");
+
         // Reference
         match <CellRef<'static>>::match_expr(ast_expr.clone(), metrics) {
             Ok(cell_ref) => return Ok(InsertExpr::Reference(cell_ref)),
@@ -153,6 +201,7 @@ impl AstExprMatch for RampExpr {
                     count: args.0,
                     blend_fn: args.1,
                     interpolate: InterpolateRange::default(),
+                    amounts: None,
                 });
             },
             _ => (),
@@ -162,7 +211,7 @@ impl AstExprMatch for RampExpr {
                 u8,
                 BlendFunction,
                 InterpolateRange)>>::match_expr(
-            ast_expr,
+            ast_expr.clone(),
             metrics)
         {
             Ok(FunctionCall { operand: Ident(i), args }) if i == "ramp" => {
@@ -171,6 +220,49 @@ impl AstExprMatch for RampExpr {
                     count: args.0,
                     blend_fn: args.1,
                     interpolate: args.2,
+                    amounts: None,
+                });
+            },
+            _ => (),
+        }
+
+        match <FunctionCall<Ident, (
+                u8,
+                BlendFunction,
+                Vec<f32>)>>::match_expr(
+            ast_expr,
+            metrics)
+        {
+            Ok(FunctionCall { operand: Ident(i), args }) if i == "ramp" => {
+                let (count, blend_fn, amounts) = args;
+                if amounts.len() != usize::from(count) {
+                    return Err(ParseError::new("invalid ramp function")
+                        .with_span(format!(
+                                "ramp requires {} interpolation amounts, {} \
+                                provided",
+                                count,
+                                amounts.len()),
+                            ast_span,
+                            metrics));
+                }
+                if let Some(amount) = amounts
+                    .iter()
+                    .find(|a| !(0.0..=1.0).contains(*a))
+                {
+                    return Err(ParseError::new("invalid ramp function")
+                        .with_span(format!(
+                                "interpolation amount {} must lie within the \
+                                range [0.0, 1.0]",
+                                amount),
+                            ast_span,
+                            metrics));
+                }
+                event!(Level::TRACE, "RampExpr match succeeds (3).");
+                return Ok(RampExpr {
+                    count,
+                    blend_fn,
+                    interpolate: InterpolateRange::default(),
+                    amounts: Some(amounts),
                 });
             },
             _ => (),
@@ -328,6 +420,45 @@ impl AstExprMatch for BlendExpr {
             _ => (),
         }
 
+        match <FunctionCall<Ident, (
+                CellRef<'static>,
+                ChannelOrder)>>::match_expr(
+            ast_expr.clone(),
+            metrics)
+        {
+            Ok(FunctionCall { operand: Ident(i), args }) if i == "swizzle" => {
+                event!(Level::TRACE, "BlendExpr match succeeds (7).");
+                return Ok(BlendExpr {
+                    blend_fn: BlendFunction::Swizzle(SwizzleFunction {
+                        order: args.1,
+                        arg: args.0,
+                    }),
+                    interpolate: Interpolate::default(),
+                });
+            },
+            _ => (),
+        }
+
+        match <FunctionCall<Ident, (
+                CellRef<'static>,
+                ChannelOrder,
+                Interpolate)>>::match_expr(
+            ast_expr.clone(),
+            metrics)
+        {
+            Ok(FunctionCall { operand: Ident(i), args }) if i == "swizzle" => {
+                event!(Level::TRACE, "BlendExpr match succeeds (8).");
+                return Ok(BlendExpr {
+                    blend_fn: BlendFunction::Swizzle(SwizzleFunction {
+                        order: args.1,
+                        arg: args.0,
+                    }),
+                    interpolate: args.2,
+                });
+            },
+            _ => (),
+        }
+
         event!(Level::TRACE, "BlendExpr match fails.");
         Err(ParseError::new("invalid blend function")
             .with_span("unrecognized blend function",
@@ -336,6 +467,177 @@ impl AstExprMatch for BlendExpr {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// MixExpr
+////////////////////////////////////////////////////////////////////////////////
+
+impl AstExprMatch for MixExpr {
+    fn match_expr<'text, Cm>(ast_expr: AstExpr<'text>, metrics: Cm)
+        -> Result<Self, ParseError<'text, Cm>>
+        where Cm: ColumnMetrics
+    {
+        let span = span!(Level::DEBUG, "MixExpr::match_expr");
+        let _enter = span.enter();
+
+        let ast_span = ast_expr.span();
+
+        match <FunctionCall<Ident, VarArgs<CellRef<'static>>>>::match_expr(
+            ast_expr.clone(),
+            metrics)
+        {
+            Ok(FunctionCall { operand: Ident(i), args }) if i == "mix" => {
+                event!(Level::TRACE, "MixExpr match succeeds (1).");
+                return Ok(MixExpr {
+                    refs: args.0,
+                    weights: None,
+                    color_space: ColorSpace::default(),
+                });
+            },
+            _ => (),
+        }
+
+        match <FunctionCall<Ident, (
+                Vec<CellRef<'static>>,
+                Vec<f32>)>>::match_expr(
+            ast_expr.clone(),
+            metrics)
+        {
+            Ok(FunctionCall { operand: Ident(i), args }) if i == "mix" => {
+                event!(Level::TRACE, "MixExpr match succeeds (2).");
+                return mix_expr_weighted(
+                    args.0,
+                    args.1,
+                    ColorSpace::default(),
+                    ast_span,
+                    metrics);
+            },
+            _ => (),
+        }
+
+        match <FunctionCall<Ident, (
+                Vec<CellRef<'static>>,
+                Vec<f32>,
+                ColorSpace)>>::match_expr(
+            ast_expr,
+            metrics)
+        {
+            Ok(FunctionCall { operand: Ident(i), args }) if i == "mix" => {
+                event!(Level::TRACE, "MixExpr match succeeds (3).");
+                return mix_expr_weighted(
+                    args.0,
+                    args.1,
+                    args.2,
+                    ast_span,
+                    metrics);
+            },
+            _ => (),
+        }
+
+        event!(Level::TRACE, "MixExpr match fails.");
+        Err(ParseError::new("invalid mix function")
+            .with_span("unrecognized mix function",
+                ast_span,
+                metrics))
+    }
+}
+
+/// Constructs a weighted `MixExpr`, validating the weights.
+fn mix_expr_weighted<'text, Cm>(
+    refs: Vec<CellRef<'static>>,
+    weights: Vec<f32>,
+    color_space: ColorSpace,
+    ast_span: Span<'text>,
+    metrics: Cm)
+    -> Result<MixExpr, ParseError<'text, Cm>>
+    where Cm: ColumnMetrics
+{
+    if weights.len() != refs.len() {
+        return Err(ParseError::new("invalid mix function")
+            .with_span(format!(
+                    "mix requires {} weights, {} provided",
+                    refs.len(),
+                    weights.len()),
+                ast_span,
+                metrics));
+    }
+    if let Some(weight) = weights.iter()
+        .find(|w| !(w.is_finite() && **w >= 0.0))
+    {
+        return Err(ParseError::new("invalid mix function")
+            .with_span(format!(
+                    "mix weight {} must be finite and not negative",
+                    weight),
+                ast_span,
+                metrics));
+    }
+    Ok(MixExpr {
+        refs,
+        weights: Some(weights),
+        color_space,
+    })
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// GradientExpr
+////////////////////////////////////////////////////////////////////////////////
+
+impl AstExprMatch for GradientExpr {
+    fn match_expr<'text, Cm>(ast_expr: AstExpr<'text>, metrics: Cm)
+        -> Result<Self, ParseError<'text, Cm>>
+        where Cm: ColumnMetrics
+    {
+        let span = span!(Level::DEBUG, "GradientExpr::match_expr");
+        let _enter = span.enter();
+
+        let ast_span = ast_expr.span();
+
+        let (from, to, count, color_space) = match <FunctionCall<Ident, (
+                CellRef<'static>,
+                CellRef<'static>,
+                u8)>>::match_expr(
+            ast_expr.clone(),
+            metrics)
+        {
+            Ok(FunctionCall { operand: Ident(i), args }) if i == "gradient"
+                => (args.0, args.1, args.2, ColorSpace::default()),
+            _ => match <FunctionCall<Ident, (
+                    CellRef<'static>,
+                    CellRef<'static>,
+                    u8,
+                    ColorSpace)>>::match_expr(
+                ast_expr,
+                metrics)
+            {
+                Ok(FunctionCall { operand: Ident(i), args })
+                    if i == "gradient"
+                    => (args.0, args.1, args.2, args.3),
+                _ => {
+                    event!(Level::TRACE, "GradientExpr match fails.");
+                    return Err(ParseError::new("invalid gradient function")
+                        .with_span("unrecognized gradient function",
+                            ast_span,
+                            metrics));
+                },
+            },
+        };
+
+        if count < 2 {
+            return Err(ParseError::new("invalid gradient function")
+                .with_span(format!(
+                        "gradient requires a count of at least 2, {} \
+                        provided",
+                        count),
+                    ast_span,
+                    metrics));
+        }
+
+        event!(Level::TRACE, "GradientExpr match succeeds.");
+        Ok(GradientExpr { from, to, count, color_space })
+    }
+}
+
+
 ////////////////////////////////////////////////////////////////////////////////
 // BlendFunction
 ////////////////////////////////////////////////////////////////////////////////
@@ -367,6 +669,15 @@ impl AstExprMatch for BlendFunction {
             _ => (),
         }
 
+        // Swizzle
+        match <SwizzleFunction>::match_expr(
+            ast_expr.clone(),
+            metrics)
+        {
+            Ok(swizzle) => return Ok(BlendFunction::Swizzle(swizzle)),
+            _ => (),
+        }
+
         Err(ParseError::new("invalid blend function")
             .with_span("unrecognized blend function",
                 ast_span,
@@ -408,6 +719,39 @@ impl AstExprMatch for UnaryBlendFunction {
 }
 
 
+impl AstExprMatch for SwizzleFunction {
+    fn match_expr<'text, Cm>(ast_expr: AstExpr<'text>, metrics: Cm)
+        -> Result<Self, ParseError<'text, Cm>>
+        where Cm: ColumnMetrics
+    {
+        let span = span!(Level::DEBUG, "SwizzleFunction::match_expr");
+        let _enter = span.enter();
+
+        let ast_span = ast_expr.span();
+
+        match <FunctionCall<
+                Ident,
+                (CellRef<'static>, ChannelOrder)>>::match_expr(
+            ast_expr.clone(),
+            metrics)
+        {
+            Ok(FunctionCall { operand: Ident(i), args }) if i == "swizzle" => {
+                return Ok(SwizzleFunction {
+                    order: args.1,
+                    arg: args.0,
+                });
+            },
+            _ => (),
+        }
+
+        Err(ParseError::new("invalid blend function")
+            .with_span("unrecognized blend function",
+                ast_span,
+                metrics))
+    }
+}
+
+
 impl AstExprMatch for BinaryBlendFunction {
     fn match_expr<'text, Cm>(ast_expr: AstExpr<'text>, metrics: Cm)
         -> Result<Self, ParseError<'text, Cm>>
@@ -516,6 +860,31 @@ impl AstExprMatch for BinaryBlendMethod {
     }
 }
 
+impl AstExprMatch for ChannelOrder {
+    fn match_expr<'text, Cm>(ast_expr: AstExpr<'text>, metrics: Cm)
+        -> Result<Self, ParseError<'text, Cm>>
+        where Cm: ColumnMetrics
+    {
+        let span = span!(Level::DEBUG, "ChannelOrder::match_expr");
+        let _enter = span.enter();
+
+        let ast_span = ast_expr.span();
+        
+        match Ident::match_expr(ast_expr, metrics) {
+            Ok(Ident(i)) => match ChannelOrder::from_str(i.as_ref()) {
+                Ok(order) => return Ok(order),
+                Err(_)    => (),
+            },
+            _ => (),
+        }
+
+        Err(ParseError::new("invalid channel order")
+            .with_span("expected a permutation of 'rgb'",
+                ast_span,
+                metrics))
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Interpolate
 ////////////////////////////////////////////////////////////////////////////////
@@ -532,16 +901,15 @@ impl AstExprMatch for Interpolate {
 
         match f32::match_expr(ast_expr.clone(), metrics) {
             Ok(amount) => {
-                if amount > 1.0 || amount < 0.0 {
-                    return Err(ParseError::new("invalid interpolate value")
+                return Interpolate {
+                        amount,
+                        .. Default::default()
+                    }
+                    .validate()
+                    .map_err(|_| ParseError::new("invalid interpolate value")
                         .with_span("value must lie in the range [0.0, 1.0]",
                             ast_span,
                             metrics));
-                }
-                return Ok(Interpolate {
-                    amount,
-                    .. Default::default()
-                });
             },
             _ => (),
         }
@@ -551,17 +919,16 @@ impl AstExprMatch for Interpolate {
             metrics)
         {
             Ok(FunctionCall { operand, args }) => {
-                if args.0 > 1.0 || args.0 < 0.0 {
-                    return Err(ParseError::new("invalid interpolate value")
+                return Interpolate {
+                        interpolate_fn: operand,
+                        amount: args.0,
+                        .. Default::default()
+                    }
+                    .validate()
+                    .map_err(|_| ParseError::new("invalid interpolate value")
                         .with_span("value must lie in the range [0.0, 1.0]",
                             ast_span,
                             metrics));
-                }
-                return Ok(Interpolate {
-                    interpolate_fn: operand,
-                    amount: args.0,
-                    .. Default::default()
-                });
             },
             _ => (),
         }
@@ -571,18 +938,38 @@ impl AstExprMatch for Interpolate {
             metrics)
         {
             Ok(FunctionCall { operand, args }) => {
-                if args.0 > 1.0 || args.0 < 0.0 {
-                    return Err(ParseError::new("invalid interpolate value")
+                return Interpolate {
+                        interpolate_fn: operand,
+                        amount: args.0,
+                        color_space: args.1,
+                        .. Default::default()
+                    }
+                    .validate()
+                    .map_err(|_| ParseError::new("invalid interpolate value")
+                        .with_span("value must lie in the range [0.0, 1.0]",
+                            ast_span,
+                            metrics));
+            },
+            _ => (),
+        }
+
+        match <FunctionCall<InterpolateFunction, (f32, ColorSpace, Ident)>>
+            ::match_expr(ast_expr.clone(), metrics)
+        {
+            Ok(FunctionCall { operand, args }) => {
+                return Interpolate {
+                        interpolate_fn: operand,
+                        amount: args.0,
+                        color_space: args.1,
+                        linear_light: linear_light_flag(args.2,
+                            ast_span,
+                            metrics)?,
+                    }
+                    .validate()
+                    .map_err(|_| ParseError::new("invalid interpolate value")
                         .with_span("value must lie in the range [0.0, 1.0]",
                             ast_span,
                             metrics));
-                }
-                return Ok(Interpolate {
-                    interpolate_fn: operand,
-                    amount: args.0,
-                    color_space: args.1,
-                    .. Default::default()
-                });
             },
             _ => (),
         }
@@ -662,6 +1049,49 @@ impl AstExprMatch for InterpolateRange {
             _ => (),
         }
 
+        match <FunctionCall<
+                InterpolateFunction,
+                (Vec<f32>, ColorSpace, Ident)>>
+            ::match_expr(ast_expr.clone(), metrics)
+        {
+            Ok(FunctionCall { operand, args }) if args.0.len() != 2 => {
+                return Err(ParseError::new("expected [f32, f32] value")
+                    .with_span("wrong number of arguments", ast_span, metrics));
+            },
+            Ok(FunctionCall { operand, args }) => {
+                valid_unit_range(args.0[0], args.0[1])
+                    .map_err(|e| e.with_span("invalid range value",
+                        ast_span,
+                        metrics))?;
+                return Ok(InterpolateRange {
+                    interpolate_fn: operand,
+                    start: args.0[0],
+                    end: args.0[1],
+                    color_space: args.1,
+                    linear_light: linear_light_flag(args.2,
+                        ast_span,
+                        metrics)?,
+                });
+            },
+            _ => (),
+        }
+
+        match <FunctionCall<InterpolateFunction, (ColorSpace, Ident)>>
+            ::match_expr(ast_expr.clone(), metrics)
+        {
+            Ok(FunctionCall { operand, args }) => {
+                return Ok(InterpolateRange {
+                    color_space: args.0,
+                    interpolate_fn: operand,
+                    linear_light: linear_light_flag(args.1,
+                        ast_span,
+                        metrics)?,
+                    .. Default::default()
+                });
+            },
+            _ => (),
+        }
+
         match <FunctionCall<InterpolateFunction, (ColorSpace,)>>::match_expr(
             ast_expr,
             metrics)
@@ -681,11 +1111,24 @@ impl AstExprMatch for InterpolateRange {
     }
 }
 
+/// Returns the linear light flag for the given interpolation option.
+fn linear_light_flag<'text, Cm>(ident: Ident, span: Span<'text>, metrics: Cm)
+    -> Result<bool, ParseError<'text, Cm>>
+    where Cm: ColumnMetrics,
+{
+    match ident {
+        Ident(ident) if ident == "linear_light" => Ok(true),
+        Ident(ident) if ident == "srgb"         => Ok(false),
+        _ => Err(ParseError::new("expected interpolate option")
+            .with_span("expected 'linear_light' or 'srgb'", span, metrics)),
+    }
+}
+
 fn valid_unit_range<'text, Cm>(l: f32, r: f32)
     -> Result<(), ParseError<'text, Cm>>
     where Cm: ColumnMetrics,
 {
-    if l < 0.0 || l > 1.0 || r < 0.0 || r > 1.0 || r < l {
+    if !(0.0..=1.0).contains(&l) || !(0.0..=1.0).contains(&r) || r < l {
         Err(ParseError::new("value must lie in the range [0.0, 1.0]"))
     } else {
         Ok(())
@@ -713,6 +1156,12 @@ impl AstExprMatch for InterpolateFunction {
             Ok(Ident(ident)) if ident == "cubic" => return Ok(
                 InterpolateFunction::Cubic(0.0, 0.0)
             ),
+            Ok(Ident(ident)) if ident == "smoothstep" => return Ok(
+                InterpolateFunction::Smoothstep
+            ),
+            Ok(Ident(ident)) if ident == "ease_in_out" => return Ok(
+                InterpolateFunction::EaseInOut
+            ),
             _ => (),
         }
 
@@ -745,6 +1194,9 @@ impl AstExprMatch for ColorSpace {
         let ast_span = ast_expr.span();
         match Ident::match_expr(ast_expr, metrics) {
             Ok(Ident(ident)) if ident == "rgb" => Ok(ColorSpace::Rgb),
+            Ok(Ident(ident)) if ident == "hsv" => Ok(ColorSpace::Hsv),
+            Ok(Ident(ident)) if ident == "hsl" => Ok(ColorSpace::Hsl),
+            Ok(Ident(ident)) if ident == "lab" => Ok(ColorSpace::Lab),
 
             _ => Err(ParseError::new("expected color space")
             .with_span("unrecognized color space", ast_span, metrics))
@@ -100,6 +100,13 @@ pub enum AtmaToken {
     /// A string with potential escape characters.
     StringText,
 
+    /// A regex open character '/'.
+    RegexOpen,
+    /// A regex close character '/'.
+    RegexClose,
+    /// A regex pattern, in which '/' must be escaped as '\/'.
+    RegexText,
+
     /// A semicolon character ';'.
     Semicolon,
     /// A colon character ':'.
@@ -169,6 +176,9 @@ impl std::fmt::Display for AtmaToken {
             StringOpenDouble  => write!(f, "open quote '\"'"),
             StringCloseDouble => write!(f, "close quote '\"'"),
             StringText        => write!(f, "string source"),
+            RegexOpen         => write!(f, "open regex '/'"),
+            RegexClose        => write!(f, "close regex '/'"),
+            RegexText         => write!(f, "regex source"),
             Semicolon         => write!(f, "';'"),
             Colon             => write!(f, "':'"),
             Comma             => write!(f, "','"),
@@ -193,6 +203,7 @@ impl std::fmt::Display for AtmaToken {
 pub struct AtmaScanner {
     open: Option<AtmaToken>,
     depth: u64,
+    after_colon: bool,
 }
 
 impl AtmaScanner {
@@ -200,6 +211,7 @@ impl AtmaScanner {
         AtmaScanner {
             open: None,
             depth: 0,
+            after_colon: false,
         }
     }
 
@@ -310,15 +322,21 @@ impl AtmaScanner {
             .position_after_chars_matching(source, base,
                 |c| c.is_digit(10))?;
 
-        // Parse decimal point, fail if not found.
-        let end = metrics
-            .position_after_str(source, end, ".")?;
+        // Parse decimal point and digits.
+        let (end, has_decimal) = match metrics
+            .position_after_str(source, end, ".")
+        {
+            Some(end) => (metrics
+                .position_after_chars_matching(source, end,
+                    |c| c.is_digit(10))
+                .unwrap_or(end), true),
+            None => (end, false),
+        };
 
-        // Parse digits.
-        let end = metrics
-            .position_after_chars_matching(source, end,
-                |c| c.is_digit(10))
-            .unwrap_or(end);
+        // A float without a decimal point must have an exponent, otherwise it
+        // is a Uint. These are not scanned after a reference prefix, so that
+        // `:1e2` is not mistaken for a Float.
+        if !has_decimal && self.after_colon { return None; }
 
         // Parse exponent.
         if let Some(end) = metrics
@@ -337,8 +355,10 @@ impl AtmaScanner {
                     |c| c.is_digit(10))?;
 
             Some((AtmaToken::Float, end))
-        } else {
+        } else if has_decimal {
             Some((AtmaToken::Float, end))
+        } else {
+            None
         }
     }
 
@@ -449,7 +469,34 @@ impl AtmaScanner {
                     Some(("t",  adv2)) |
                     Some(("r",  adv2)) |
                     Some(("n",  adv2)) => end = adv2,
-                    Some(("u",  adv2)) => unimplemented!("unicode escapes unsupported"),
+                    Some(("u",  _))    => {
+                        // Parse a unicode escape of the form \u{XXXX}.
+                        match col_iter.next() {
+                            Some(("{", _)) => (),
+                            _              => return None,
+                        }
+                        let mut hex = String::with_capacity(6);
+                        loop {
+                            match col_iter.next() {
+                                Some(("}", adv3)) => {
+                                    end = adv3;
+                                    break;
+                                },
+                                Some((d, _)) if hex.len() < 6 && d
+                                    .chars()
+                                    .all(|c| c.is_ascii_hexdigit()) =>
+                                {
+                                    hex.push_str(d);
+                                },
+                                _ => return None,
+                            }
+                        }
+                        let valid = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(std::char::from_u32)
+                            .is_some();
+                        if !valid { return None; }
+                    },
                     _                  => return None,
                 },
                 
@@ -465,6 +512,36 @@ impl AtmaScanner {
         Some((AtmaToken::StringText, end))
     }
 
+    /// Parses a RegexText token.
+    fn parse_regex_text<Cm>(&mut self, source: &str, base: Pos, metrics: Cm)
+        -> Option<(AtmaToken, Pos)>
+        where Cm: ColumnMetrics,
+    {
+        let span = span!(Level::TRACE, "AtmaScanner::parse_regex_text");
+        let _enter = span.enter();
+
+        let mut end = base;
+        let mut col_iter = metrics.iter_columns(source, end);
+
+        while let Some((next, adv)) = col_iter.next() {
+            event!(Level::TRACE, "next: {:?}, adv: {}", next, adv);
+            match next {
+                "\\" => match col_iter.next() {
+                    Some((_, adv2)) => end = adv2,
+                    None            => return None,
+                },
+                "/"  => break,
+                _    => end = adv,
+            }
+        }
+
+        if end.byte == base.byte {
+            None
+        } else {
+            Some((AtmaToken::RegexText, end))
+        }
+    }
+
     /// Parses a Whitespace token.
     fn parse_whitespace<Cm>(&mut self, source: &str, base: Pos, metrics: Cm)
         -> Option<(AtmaToken, Pos)>
@@ -625,6 +702,19 @@ impl AtmaScanner {
                 panic!("invalid scanner state");
             },
 
+            Some(RegexOpen) => {
+                return_if_some!(self
+                    .parse_str(source, base, metrics, "/", RegexClose));
+                if let Some(parse) = self
+                    .parse_regex_text(source, base, metrics)
+                {
+                    // Keep this open to prioritize the close.
+                    self.open = Some(RegexOpen);
+                    return Some(parse);
+                }
+                None
+            },
+
             Some(Hash) => {
                 // HexDigits can only come after Hash.
                 return_if_some!(self.parse_hex_digits(source, base, metrics));
@@ -691,6 +781,14 @@ impl AtmaScanner {
                     self.depth = 1;
                     return Some(parse);
                 }
+                // RegexOpen must be parsed after the comment openers.
+                #[cfg(feature = "name-regex")]
+                if let Some(parse) = self
+                    .parse_str(source, base, metrics, "/", RegexOpen)
+                {
+                    self.open = Some(RegexOpen);
+                    return Some(parse);
+                }
 
                 // RawStringOpen must be parsed before Hash.
                 if let Some(parse) = self
@@ -773,6 +871,13 @@ impl Scanner for AtmaScanner {
         
         let res = self.parse_token(source, base, metrics);
 
+        // Track whether a reference prefix precedes the next token.
+        match res {
+            Some((AtmaToken::Colon, _))      => self.after_colon = true,
+            Some((AtmaToken::Whitespace, _)) => (),
+            _                                => self.after_colon = false,
+        }
+
         event!(Level::DEBUG,
             "scan result: {:?}",
             res.map(|(tok, end)| (tok, &source[base.byte..end.byte])));
@@ -18,6 +18,10 @@ use crate::cell::CellSelection;
 use crate::cell::CellSelector;
 use crate::cell::Position;
 use crate::cell::PositionSelector;
+use crate::cell::REF_GROUPED_KEYWORD;
+use crate::cell::REF_NAMED_KEYWORD;
+#[cfg(feature = "name-regex")]
+use crate::cell::name_regex;
 use crate::parse::AtmaScanner;
 use crate::parse::AtmaToken;
 use crate::parse::PositionOrIndex;
@@ -367,6 +371,83 @@ pub fn cell_selector<'text, Cm>(mut lexer: Lexer<'text, AtmaScanner, Cm>)
             }
         },
 
+        Some(Mult) => {
+            let (keyword, succ) = exact(
+                right(one(Mult), text(one(Ident))))
+                (lexer)?
+                .take_value();
+            match keyword {
+                REF_NAMED_KEYWORD   => Ok(succ).map_value(|_| AllNamed),
+                REF_GROUPED_KEYWORD => Ok(succ).map_value(|_| AllGrouped),
+                _ => Err(Failure {
+                    parse_error: ParseError::new("invalid cell selector")
+                        .with_span(
+                            "expected 'named' or 'grouped'",
+                            succ.lexer.token_span(),
+                            succ.lexer.column_metrics()),
+                    lexer: succ.lexer,
+                    source: None,
+                }),
+            }
+        },
+
+        Some(Hash) => {
+            // Tags beginning with hex digits are scanned as HexDigits, which
+            // may be followed by the remainder of the tag as an Ident.
+            let (tag, succ) = right(one(Hash), text(both(
+                    any(&[HexDigits, Ident]),
+                    maybe(one(Ident)))))
+                (lexer)?
+                .take_value();
+            Ok(succ).map_value(|_| Tag(Cow::from(tag)))
+        },
+
+        #[cfg(feature = "name-regex")]
+        Some(RegexOpen) => {
+            let (pattern, succ) = bracket(
+                    one(RegexOpen),
+                    text(one(RegexText)),
+                    one(RegexClose))
+                (lexer)?
+                .take_value();
+            let pattern: Cow<'text, str> = if pattern.contains("\\/") {
+                Cow::from(pattern.replace("\\/", "/"))
+            } else {
+                Cow::from(pattern)
+            };
+            // Validate the pattern here so that resolution cannot fail.
+            if let Err(e) = name_regex(&pattern, false) {
+                return Err(Failure {
+                    parse_error: ParseError::new("invalid name regex")
+                        .with_span(
+                            "invalid regular expression",
+                            succ.lexer.parse_span(),
+                            succ.lexer.column_metrics()),
+                    lexer: succ.lexer,
+                    source: Some(Box::new(e)),
+                });
+            }
+            Ok(succ).map_value(|_| NameRegex(pattern))
+        },
+
+        Some(Minus) => {
+            let (val, succ) = right(one(Minus), cell_selector)
+                (lexer)?
+                .take_value();
+            if val.is_subtractive() {
+                return Err(Failure {
+                    parse_error: ParseError::new("invalid cell selector")
+                        .with_span(
+                            "subtractive selectors cannot be nested",
+                            succ.lexer.token_span(),
+                            succ.lexer.column_metrics()),
+                    lexer: succ.lexer,
+                    source: None,
+                });
+            }
+            Ok(succ).map_value(|_| Subtract(Box::new(val)))
+        },
+
         // TODO: Give a better error message.
         _ => fail
             (lexer)
@@ -0,0 +1,95 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Blend method tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::color::Color;
+use crate::color::ColorExt as _;
+use crate::color::Rgb;
+use crate::palette::BinaryBlendMethod;
+use crate::palette::ColorSpace;
+
+
+/// All binary blend methods.
+const BINARY_BLEND_METHODS: [BinaryBlendMethod; 15] = [
+    BinaryBlendMethod::Blend,
+    BinaryBlendMethod::Multiply,
+    BinaryBlendMethod::Divide,
+    BinaryBlendMethod::Subtract,
+    BinaryBlendMethod::Difference,
+    BinaryBlendMethod::Screen,
+    BinaryBlendMethod::Overlay,
+    BinaryBlendMethod::HardLight,
+    BinaryBlendMethod::SoftLight,
+    BinaryBlendMethod::ColorDodge,
+    BinaryBlendMethod::ColorBurn,
+    BinaryBlendMethod::VividLight,
+    BinaryBlendMethod::LinearDodge,
+    BinaryBlendMethod::LinearBurn,
+    BinaryBlendMethod::LinearLight,
+];
+
+
+/// Blends the given colors using each binary blend method in the RGB color
+/// space and checks that every result channel is finite and in range.
+fn check_binary_blend_in_range(a: Color, b: Color) {
+    for method in BINARY_BLEND_METHODS.iter() {
+        let color = ColorSpace::Rgb.map_channels_binary(
+            a.clone(),
+            b.clone(),
+            |x, y| method.apply(x, y));
+        for channel in color.rgb_ratios().iter() {
+            assert!(channel.is_finite(),
+                "{:?} produced non-finite channel {}", method, channel);
+            assert!(*channel >= 0.0 && *channel <= 1.0,
+                "{:?} produced out of range channel {}", method, channel);
+        }
+    }
+}
+
+
+/// Tests that blending `a = 1.0` with `b = 0.0` produces valid channels for
+/// every binary blend method.
+#[test]
+fn binary_blend_white_over_black_in_range() {
+    let white = Color::from(Rgb::from([0xFF, 0xFF, 0xFF]));
+    let black = Color::from(Rgb::from([0x00, 0x00, 0x00]));
+    check_binary_blend_in_range(white, black);
+}
+
+
+/// Tests that blending `a = 0.0` with `b = 1.0` produces valid channels for
+/// every binary blend method.
+#[test]
+fn binary_blend_black_over_white_in_range() {
+    let white = Color::from(Rgb::from([0xFF, 0xFF, 0xFF]));
+    let black = Color::from(Rgb::from([0x00, 0x00, 0x00]));
+    check_binary_blend_in_range(black, white);
+}
+
+
+/// Tests that dividing by a zero channel and burning with a zero channel are
+/// clamped rather than producing infinite or NaN channels.
+#[test]
+fn binary_blend_divide_color_burn_by_zero() {
+    let white = Color::from(Rgb::from([0xFF, 0xFF, 0xFF]));
+    let black = Color::from(Rgb::from([0x00, 0x00, 0x00]));
+
+    let divide = ColorSpace::Rgb.map_channels_binary(
+        white.clone(),
+        black.clone(),
+        |x, y| BinaryBlendMethod::Divide.apply(x, y));
+    assert_eq!(divide.rgb_ratios(), [1.0, 1.0, 1.0]);
+
+    let burn = ColorSpace::Rgb.map_channels_binary(
+        white,
+        black,
+        |x, y| BinaryBlendMethod::ColorBurn.apply(x, y));
+    assert_eq!(burn.rgb_ratios(), [0.0, 0.0, 0.0]);
+}
@@ -0,0 +1,61 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Palette clearing tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::Cell;
+use crate::cell::CellRef;
+use crate::cell::Position;
+use crate::cell::PositionSelector;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+use crate::palette::Expr;
+
+
+/// Tests that applying the operations returned by `clear_all` restores the
+/// palette's cells, names, positions, groups, and position cursor.
+#[test]
+fn clear_all_reversible() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_cell(0, Cell::new_with_expr(
+            Expr::Color(Color::from(Rgb::from([0xFF, 0x00, 0x00]))))
+        .with_description("red")
+        .with_tags(vec!["warm"]))
+        .unwrap();
+    let _ = basic.insert_reference(1, CellRef::Index(0)).unwrap();
+    let _ = basic.insert_color(2, Color::from(Rgb::from([0x00, 0x00, 0xFF])))
+        .unwrap();
+
+    let _ = basic.assign_name(
+            "first",
+            PositionSelector::new(0, 0, None),
+            false)
+        .unwrap();
+    for (column, idx) in [0, 1, 2].iter().enumerate() {
+        let _ = basic.assign_position(
+                Position { page: 0, line: 0, column: column as u16 },
+                CellRef::Index(*idx))
+            .unwrap();
+    }
+    let _ = basic.assign_group(CellRef::Index(2), "ends", None).unwrap();
+    let _ = basic.assign_group(CellRef::Index(0), "ends", None).unwrap();
+    let _ = basic.assign_group(CellRef::Index(1), "middle", None).unwrap();
+    let _ = basic.set_position_cursor(Position { page: 0, line: 0, column: 3 });
+    let _ = basic.lock_cell(CellRef::Index(0)).unwrap();
+    let original = basic.clone();
+
+    let ops = basic.clear_all();
+    assert!(basic.cell(&CellRef::Index(0)).is_err());
+    assert_eq!(basic.position_cursor(), Position::ZERO);
+
+    basic.apply_operations(&ops, None).unwrap();
+    assert_eq!(basic, original);
+    assert!(basic.cell(&CellRef::Index(0)).unwrap().is_locked());
+}
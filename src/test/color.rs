@@ -0,0 +1,239 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Color extension tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellIndexSelection;
+use crate::cell::CellRef;
+use crate::cell::CellSelection;
+use crate::color::Color;
+use crate::color::ColorExt as _;
+use crate::color::Hsv;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+use crate::palette::ColorSpace;
+use crate::palette::HarmonyScheme;
+
+
+/// Tests packing colors into `u32`s and unpacking them.
+#[test]
+fn color_packed_u32_round_trip() {
+    let black = Color::from(Rgb::from([0x00, 0x00, 0x00]));
+    let white = Color::from(Rgb::from([0xFF, 0xFF, 0xFF]));
+    assert_eq!(black.to_rgb_u32(), 0x00_000000);
+    assert_eq!(white.to_rgb_u32(), 0x00_FFFFFF);
+    assert_eq!(black.to_argb_u32(), 0xFF_000000);
+    assert_eq!(white.to_argb_u32(), 0xFF_FFFFFF);
+
+    for rgb in [0x000000, 0xFFFFFF, 0xFF0000, 0x00FF00, 0x0000FF, 0x123456]
+        .iter()
+    {
+        let color = Color::from_rgb_u32(*rgb);
+        assert_eq!(color.to_rgb_u32(), *rgb);
+        assert_eq!(Color::from_argb_u32(color.to_argb_u32()), color);
+    }
+}
+
+
+/// Tests that unequal mix weights are normalized by their sum.
+#[test]
+fn color_mix_many_unequal_weights() {
+    let black = Color::from(Rgb::from([0x00, 0x00, 0x00]));
+    let white = Color::from(Rgb::from([0xFF, 0xFF, 0xFF]));
+
+    let mixed = Color::mix_many(
+            &[(black.clone(), 3.0), (white.clone(), 1.0)],
+            ColorSpace::Rgb)
+        .unwrap();
+    let normalized = Color::mix_many(
+            &[(black.clone(), 0.75), (white.clone(), 0.25)],
+            ColorSpace::Rgb)
+        .unwrap();
+
+    for (a, b) in mixed.rgb_ratios().iter()
+        .zip(normalized.rgb_ratios().iter())
+    {
+        assert!((a - 0.25).abs() < 1e-5);
+        assert!((a - b).abs() < 1e-5);
+    }
+
+    assert_eq!(Color::mix_many(
+            &[(black.clone(), 1.0), (white.clone(), std::f32::NAN)],
+            ColorSpace::Rgb),
+        None);
+    assert_eq!(Color::mix_many(
+            &[(black, 1.0), (white, std::f32::INFINITY)],
+            ColorSpace::Rgb),
+        None);
+}
+
+
+/// Tests that dark colors suggest white text and light colors suggest black
+/// text.
+#[test]
+fn color_readable_text_color() {
+    let white = 0xFFFFFF;
+    let black = 0x000000;
+    for (rgb, text) in [
+            (0x000000, white),
+            (0x1A237E, white),
+            (0x404040, white),
+            (0xFFFFFF, black),
+            (0xFFEB3B, black),
+            (0xC0C0C0, black),
+        ].iter()
+    {
+        assert_eq!(
+            Color::from_rgb_u32(*rgb).readable_text_color().to_rgb_u32(),
+            *text);
+    }
+
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from_rgb_u32(0x101010)).unwrap();
+    let _ = basic.insert_color(1, Color::from_rgb_u32(0xF0F0F0)).unwrap();
+    let selection: CellSelection<'static> = ":*".parse().unwrap();
+    let text_colors: Vec<(u32, u32)> = basic.text_colors(&selection)
+        .into_iter()
+        .map(|(idx, color)| (idx, color.to_rgb_u32()))
+        .collect();
+    assert_eq!(text_colors, vec![(0, white), (1, black)]);
+}
+
+
+/// Tests that clamping a color clamps each channel into the range [0.0, 1.0].
+#[test]
+fn color_clamped() {
+    let color = Color::from(Rgb::from([2.0, -1.0, 0.5]));
+    assert_eq!(color.clamped().rgb_ratios(), [1.0, 0.0, 0.5]);
+
+    let color = Color::from(Rgb::from([std::f32::NAN, 0.25, 1.0]));
+    assert_eq!(color.clamped().rgb_ratios(), [0.0, 0.25, 1.0]);
+}
+
+
+/// Tests comparing the resolved colors of cells within a tolerance.
+#[test]
+fn colors_equal() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from_rgb_u32(0x808080)).unwrap();
+    let _ = basic.insert_reference(1, CellRef::Index(0)).unwrap();
+    let _ = basic.insert_color(2, Color::from_rgb_u32(0x818080)).unwrap();
+    let _ = basic.insert_color(3, Color::from_rgb_u32(0x80FF80)).unwrap();
+
+    let (a, b, c, d) = (
+        CellRef::Index(0),
+        CellRef::Index(1),
+        CellRef::Index(2),
+        CellRef::Index(3));
+    assert!(basic.colors_equal(&a, &b, 0.0).unwrap());
+    assert!(!basic.colors_equal(&a, &c, 0.0).unwrap());
+    assert!(basic.colors_equal(&a, &c, 0.01).unwrap());
+    assert!(!basic.colors_equal(&a, &d, 0.01).unwrap());
+    assert!(basic.colors_equal(&a, &CellRef::Index(4), 1.0).is_err());
+}
+
+
+/// Tests that colorizing maps black and white onto the ends of the gradient.
+#[test]
+fn colorize_black_white_gradient() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from_rgb_u32(0x0000FF)).unwrap();
+    let _ = basic.insert_color(1, Color::from_rgb_u32(0xFF0000)).unwrap();
+    let _ = basic.insert_color(2, Color::from_rgb_u32(0x000000)).unwrap();
+    let _ = basic.insert_color(3, Color::from_rgb_u32(0xFFFFFF)).unwrap();
+    let original = basic.clone();
+    let selection: CellIndexSelection = [2, 3].iter().copied().collect();
+
+    let undo = basic
+        .colorize(&CellRef::Index(0), &CellRef::Index(1), &selection)
+        .unwrap();
+    assert!(basic.colors_equal(&CellRef::Index(2), &CellRef::Index(0), 1e-3)
+        .unwrap());
+    assert!(basic.colors_equal(&CellRef::Index(3), &CellRef::Index(1), 1e-3)
+        .unwrap());
+
+    basic.apply_operations(&undo, None).unwrap();
+    assert_eq!(basic, original);
+}
+
+
+/// Tests that compositing at alpha 1 returns the source color, at alpha 0
+/// returns the background color, and in between mixes in linear RGB.
+#[test]
+fn blend_over_alpha_bounds() {
+    let source = Color::from_rgb_u32(0xFF8000);
+    let background = Color::from_rgb_u32(0x2040C0);
+
+    assert_eq!(source.blend_over(&background, 1.0), source);
+    assert_eq!(source.blend_over(&background, 0.0), background);
+    assert_eq!(source.blend_over(&background, 2.0), source);
+    assert_eq!(source.blend_over(&background, -1.0), background);
+
+    // Half white over black is brighter than 0.5 when mixed in linear light.
+    let white = Color::from_rgb_u32(0xFFFFFF);
+    let black = Color::from_rgb_u32(0x000000);
+    for channel in white.blend_over(&black, 0.5).rgb_ratios().iter() {
+        assert!((channel - 0.735).abs() < 1e-2, "channel {}", channel);
+    }
+}
+
+
+/// Tests that a complementary harmony inserts the color 180° around the hue
+/// wheel at the next unoccupied index, and that the insertion is undone.
+#[test]
+fn generate_harmony_complementary() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from_rgb_u32(0xFF0000)).unwrap();
+    let _ = basic.insert_color(1, Color::from_rgb_u32(0x000000)).unwrap();
+    let original = basic.clone();
+
+    let undo = basic
+        .generate_harmony(&CellRef::Index(0), HarmonyScheme::Complementary)
+        .unwrap();
+    assert_eq!(basic.occupied_cell_count(), 3);
+    let color = basic.color(&CellRef::Index(2)).unwrap().unwrap();
+    assert!((color.hsv_components()[0] - 180.0).abs() < 1e-3);
+    assert_eq!(color.to_rgb_u32(), 0x00FFFF);
+
+    basic.apply_operations(&undo, None).unwrap();
+    assert_eq!(basic, original);
+}
+
+
+/// Tests averaging black and white to gray, averaging two hues across the
+/// 0°/360° boundary, and that unresolvable cells are skipped.
+#[test]
+fn average_color_gray_and_hue_wrap() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from_rgb_u32(0x000000)).unwrap();
+    let _ = basic.insert_color(1, Color::from_rgb_u32(0xFFFFFF)).unwrap();
+    let _ = basic.insert_reference(2, CellRef::Index(2)).unwrap();
+    let _ = basic.insert_color(3, Color::from(Hsv::from([350.0, 1.0, 1.0])))
+        .unwrap();
+    let _ = basic.insert_color(4, Color::from(Hsv::from([10.0, 1.0, 1.0])))
+        .unwrap();
+
+    let selection: CellSelection<'static> = ":0, :1, :2".parse().unwrap();
+    let gray = basic.average_color(&selection, ColorSpace::Rgb)
+        .unwrap()
+        .unwrap();
+    for channel in gray.rgb_ratios().iter() {
+        assert!((channel - 0.5).abs() < 1e-3, "channel {}", channel);
+    }
+
+    let selection: CellSelection<'static> = ":3, :4".parse().unwrap();
+    let red = basic.average_color(&selection, ColorSpace::Hsv)
+        .unwrap()
+        .unwrap();
+    let hue = red.hsv_components()[0].rem_euclid(360.0);
+    assert!(hue.min(360.0 - hue) < 1e-2, "hue {}", hue);
+
+    let selection: CellSelection<'static> = ":2".parse().unwrap();
+    assert_eq!(basic.average_color(&selection, ColorSpace::Rgb).unwrap(), None);
+}
@@ -0,0 +1,143 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Command execution tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellRef;
+use crate::cell::Position;
+use crate::color::Color;
+use crate::color::ColorExt as _;
+use crate::command::execute;
+use crate::error::CommandError;
+use crate::palette::BasicPalette;
+use crate::palette::History;
+
+
+/// Constructs a palette holding the given colors at sequential indices.
+fn palette_with_colors(colors: &[u32]) -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    for (idx, color) in colors.iter().enumerate() {
+        let _ = basic.insert_color(idx as u32, Color::from_rgb_u32(*color))
+            .unwrap();
+    }
+    basic
+}
+
+/// Returns the packed RGB color of the cell at the given index, if any.
+fn rgb_u32_at(basic: &BasicPalette, idx: u32) -> Option<u32> {
+    basic.color(&CellRef::Index(idx))
+        .ok()
+        .flatten()
+        .map(|color| color.to_rgb_u32())
+}
+
+
+/// Tests that `insert` inserts a color at the first unoccupied index.
+#[test]
+fn execute_insert() {
+    let mut basic = palette_with_colors(&[0x000000]);
+    execute(&mut basic, None, "insert #FF0000").unwrap();
+
+    assert_eq!(basic.occupied_cell_count(), 2);
+    assert_eq!(rgb_u32_at(&basic, 1), Some(0xFF0000));
+}
+
+/// Tests that `delete` removes the selected cells.
+#[test]
+fn execute_delete() {
+    let mut basic = palette_with_colors(&[0x000000, 0x808080, 0xFFFFFF]);
+    execute(&mut basic, None, "delete :0, :2").unwrap();
+
+    assert_eq!(basic.occupied_indices().collect::<Vec<_>>(), vec![1]);
+}
+
+/// Tests that `name` assigns a name to a position selector.
+#[test]
+fn execute_name() {
+    let mut basic = palette_with_colors(&[0xFF0000]);
+    let _ = basic.assign_position(Position::ZERO, CellRef::Index(0)).unwrap();
+    execute(&mut basic, None, "name 'red' :0.0.0;").unwrap();
+
+    assert_eq!(
+        basic.resolve_ref_to_index(&CellRef::Name("red".into())).unwrap(),
+        0);
+}
+
+/// Tests that `group` adds the selected cells to a group in index order.
+#[test]
+fn execute_group() {
+    let mut basic = palette_with_colors(&[0x000000, 0x808080, 0xFFFFFF]);
+    execute(&mut basic, None, "group 'ends' :2, :0").unwrap();
+
+    for (group_idx, idx) in [0, 2].iter().enumerate() {
+        assert_eq!(
+            basic.resolve_ref_to_index(&CellRef::Group {
+                group: "ends".into(),
+                idx: group_idx as u32,
+            }).unwrap(),
+            *idx);
+    }
+}
+
+/// Tests that `set` replaces a cell's expression.
+#[test]
+fn execute_set() {
+    let mut basic = palette_with_colors(&[0x000000, 0x808080]);
+    execute(&mut basic, None, "set :1 #00FF00").unwrap();
+
+    assert_eq!(rgb_u32_at(&basic, 0), Some(0x000000));
+    assert_eq!(rgb_u32_at(&basic, 1), Some(0x00FF00));
+}
+
+/// Tests that `undo` reverses the previously executed command.
+#[test]
+fn execute_undo() {
+    let mut basic = palette_with_colors(&[0x000000]);
+    let original = basic.clone();
+    let mut history = History::new();
+    execute(&mut basic, Some(&mut history), "insert #FF0000").unwrap();
+    execute(&mut basic, Some(&mut history), "undo").unwrap();
+
+    assert_eq!(basic, original);
+}
+
+/// Tests that `redo` reapplies the given number of undone commands.
+#[test]
+fn execute_redo() {
+    let mut basic = palette_with_colors(&[0x000000]);
+    let mut history = History::new();
+    execute(&mut basic, Some(&mut history), "insert #FF0000").unwrap();
+    execute(&mut basic, Some(&mut history), "insert #0000FF").unwrap();
+    let inserted = basic.clone();
+    execute(&mut basic, Some(&mut history), "undo 2").unwrap();
+    assert_eq!(basic.occupied_cell_count(), 1);
+    execute(&mut basic, Some(&mut history), "redo 2").unwrap();
+
+    assert_eq!(basic, inserted);
+}
+
+/// Tests that `undo` without a history returns an error.
+#[test]
+fn execute_undo_without_history() {
+    let mut basic = BasicPalette::new();
+    match execute(&mut basic, None, "undo") {
+        Err(CommandError::InvalidCommand { .. }) => (),
+        res => panic!("expected InvalidCommand error, got {:?}", res),
+    }
+}
+
+/// Tests that `redo` without a history returns an error.
+#[test]
+fn execute_redo_without_history() {
+    let mut basic = BasicPalette::new();
+    match execute(&mut basic, None, "redo 2") {
+        Err(CommandError::InvalidCommand { .. }) => (),
+        res => panic!("expected InvalidCommand error, got {:?}", res),
+    }
+}
@@ -0,0 +1,123 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Palette diff tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellRef;
+use crate::cell::Position;
+use crate::cell::PositionSelector;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+use crate::palette::Expr;
+use crate::palette::History;
+
+
+/// Returns a palette to be transformed by a diff.
+fn source_palette() -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0xFF, 0x00, 0x00])))
+        .unwrap();
+    let _ = basic.insert_color(1, Color::from(Rgb::from([0x00, 0xFF, 0x00])))
+        .unwrap();
+    let _ = basic.insert_reference(2, CellRef::Index(0)).unwrap();
+    let _ = basic.insert_color(3, Color::from(Rgb::from([0x00, 0x00, 0xFF])))
+        .unwrap();
+    let _ = basic.assign_position(
+            Position { page: 0, line: 0, column: 0 },
+            CellRef::Index(0))
+        .unwrap();
+    let _ = basic.assign_position(
+            Position { page: 0, line: 0, column: 1 },
+            CellRef::Index(1))
+        .unwrap();
+    let _ = basic.assign_name("row", PositionSelector::new(0, 0, None), false)
+        .unwrap();
+    let _ = basic.assign_group(CellRef::Index(0), "g", None).unwrap();
+    let _ = basic.assign_group(CellRef::Index(1), "g", None).unwrap();
+    let _ = basic.lock_cell(CellRef::Index(1)).unwrap();
+    basic
+}
+
+/// Returns the palette a diff should transform the source palette into.
+fn target_palette() -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0xFF, 0x00, 0x00])))
+        .unwrap();
+    let _ = basic.set_description(CellRef::Index(0), "primary").unwrap();
+    let _ = basic.insert_color(1, Color::from(Rgb::from([0xFF, 0xFF, 0x00])))
+        .unwrap();
+    let _ = basic.insert_color(3, Color::from(Rgb::from([0x00, 0x00, 0xFF])))
+        .unwrap();
+    let _ = basic.add_tag(CellRef::Index(3), "cool").unwrap();
+    let _ = basic.insert_reference(4, CellRef::Index(3)).unwrap();
+    let _ = basic.assign_position(
+            Position { page: 0, line: 0, column: 1 },
+            CellRef::Index(0))
+        .unwrap();
+    let _ = basic.assign_position(
+            Position { page: 0, line: 0, column: 0 },
+            CellRef::Index(3))
+        .unwrap();
+    let _ = basic.assign_name("line", PositionSelector::new(0, 0, None), false)
+        .unwrap();
+    let _ = basic.assign_name("first", PositionSelector::new(0, 0, 0), false)
+        .unwrap();
+    let _ = basic.assign_group(CellRef::Index(3), "g", None).unwrap();
+    let _ = basic.assign_group(CellRef::Index(0), "g", None).unwrap();
+    let _ = basic.assign_group(CellRef::Index(4), "h", None).unwrap();
+    let _ = basic.lock_cell(CellRef::Index(1)).unwrap();
+    let _ = basic.set_position_cursor(Position { page: 0, line: 1, column: 0 });
+    basic
+}
+
+
+/// Tests that applying a diff reproduces the other palette in both directions,
+/// and that the recorded history undoes the diff.
+#[test]
+fn diff_round_trip() {
+    let source = source_palette();
+    let target = target_palette();
+
+    let mut history = History::new();
+    let mut patched = source.clone();
+    patched.apply_operations(&source.diff(&target).unwrap(), Some(&mut history))
+        .unwrap();
+    assert_eq!(patched, target);
+
+    let mut reversed = target.clone();
+    reversed.apply_operations(&target.diff(&source).unwrap(), None).unwrap();
+    assert_eq!(reversed, source);
+
+    assert_eq!(patched.undo(&mut history, 1), 1);
+    assert_eq!(patched, source);
+}
+
+/// Tests that applying each operation yielded by the diff iterator, as it is
+/// yielded, transforms the source palette into the target palette.
+#[test]
+fn diff_operations_iter_reproduces_target() {
+    let source = source_palette();
+    let target = target_palette();
+
+    let mut patched = source.clone();
+    let mut count = 0;
+    for op in source.diff_operations_iter(&target) {
+        let _ = patched.apply_operation(&op.unwrap()).unwrap();
+        count += 1;
+    }
+    assert!(count > 0);
+    assert_eq!(patched, target);
+    assert_eq!(
+        patched.cell(&CellRef::Index(4)).unwrap().expr(),
+        &Expr::Reference(CellRef::Index(3)));
+
+    // Diffing identical palettes yields no operations.
+    assert_eq!(target.diff_operations_iter(&target).count(), 0);
+}
@@ -0,0 +1,45 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Error rendering tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellRef;
+use crate::error::PaletteError;
+use crate::palette::BasicPalette;
+
+
+/// Tests rendering a circular `UndefinedColor` error.
+#[test]
+fn undefined_color_circular_display_chain() {
+    let error = PaletteError::UndefinedColor {
+        cell_ref: CellRef::Index(3),
+        circular: true,
+        referrer: None,
+    };
+    assert_eq!(
+        error.display_chain(),
+        "color is undefined for cell :3 (circular reference)");
+
+    let error = error.with_referrer(&CellRef::Index(3));
+    assert_eq!(
+        error.display_chain(),
+        "cell :3 references :3 (circular reference)");
+}
+
+/// Tests that resolving a self-referencing cell records the referrer.
+#[test]
+fn undefined_color_circular_referrer() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_reference(3, CellRef::Index(3)).unwrap();
+
+    let error = basic.color(&CellRef::Index(3)).unwrap_err();
+    assert_eq!(
+        error.display_chain(),
+        "cell :3 references :3 (circular reference)");
+}
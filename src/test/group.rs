@@ -0,0 +1,122 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Group assignment tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellRef;
+use crate::color::Color;
+use crate::color::ColorExt as _;
+use crate::color::HueCategory;
+use crate::color::Rgb;
+use crate::error::PaletteError;
+use crate::palette::BasicPalette;
+
+
+/// Returns the indices of the members of the given group, in group order.
+fn members(basic: &BasicPalette, group: &'static str) -> Vec<u32> {
+    (0..)
+        .map(|idx| basic.resolve_ref_to_index(&CellRef::Group {
+            group: group.into(),
+            idx,
+        }))
+        .take_while(Result::is_ok)
+        .map(Result::unwrap)
+        .collect()
+}
+
+
+/// Tests that assigning an absurd group index returns an error instead of
+/// panicking, and leaves the palette unchanged.
+#[test]
+fn assign_group_absurd_index() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0, 0, 0])))
+        .unwrap();
+
+    match basic.assign_group(CellRef::Index(0), "a", Some(u32::MAX)) {
+        Err(PaletteError::GroupIndexOutOfBounds { index, max, .. }) => {
+            assert_eq!(index, u32::MAX);
+            assert_eq!(max, 0);
+        },
+        res => panic!("expected GroupIndexOutOfBounds, got {:?}", res),
+    }
+
+    assert!(!basic.is_assigned_group("a", u32::MAX));
+    assert!(!basic.is_occupied_group("a", u32::MAX));
+    assert_eq!(basic.resolve_group_if_occupied("a", u32::MAX), None);
+    assert!(basic.groups_of(0).unwrap().is_empty());
+}
+
+/// Tests that renaming a group preserves its member order, and that the undo
+/// operation restores the original name with the same order.
+#[test]
+fn rename_group_preserves_member_order() {
+    let mut basic = BasicPalette::new();
+    for idx in 0..3 {
+        let _ = basic.insert_color(idx, Color::from(Rgb::from([0, 0, 0])))
+            .unwrap();
+        let _ = basic.assign_group(CellRef::Index(idx), "a", Some(0))
+            .unwrap();
+    }
+    let original = basic.clone();
+    assert_eq!(members(&basic, "a"), vec![2, 1, 0]);
+
+    let undo = basic.rename_group("a", "b").unwrap();
+    assert_eq!(members(&basic, "a"), Vec::<u32>::new());
+    assert_eq!(members(&basic, "b"), vec![2, 1, 0]);
+
+    basic.apply_operations(&undo, None).unwrap();
+    assert_eq!(members(&basic, "a"), vec![2, 1, 0]);
+    assert_eq!(basic, original);
+}
+
+/// Tests the hue category boundaries.
+#[test]
+fn hue_category_boundaries() {
+    let cases = [
+        (-10.0, HueCategory::Red),
+        (0.0, HueCategory::Red),
+        (14.9, HueCategory::Red),
+        (15.0, HueCategory::Orange),
+        (45.0, HueCategory::Yellow),
+        (75.0, HueCategory::Green),
+        (165.0, HueCategory::Cyan),
+        (195.0, HueCategory::Blue),
+        (255.0, HueCategory::Magenta),
+        (344.9, HueCategory::Magenta),
+        (345.0, HueCategory::Red),
+        (360.0, HueCategory::Red),
+    ];
+    for (hue, category) in cases.iter() {
+        assert_eq!(HueCategory::from_hue(*hue), *category, "hue {}", hue);
+    }
+}
+
+/// Tests that grouping by hue assigns chromatic cells to their category's
+/// group, skips achromatic cells, and is undone.
+#[test]
+fn group_by_hue_assigns_categories() {
+    let mut basic = BasicPalette::new();
+    let colors = [0xFF0000, 0x0000FF, 0x808080, 0xFF8000, 0xFF002A];
+    for (idx, color) in colors.iter().enumerate() {
+        let _ = basic.insert_color(idx as u32, Color::from_rgb_u32(*color))
+            .unwrap();
+    }
+    let original = basic.clone();
+
+    let undo = basic.group_by_hue().unwrap();
+    assert_eq!(members(&basic, "red"), vec![0, 4]);
+    assert_eq!(members(&basic, "blue"), vec![1]);
+    assert_eq!(members(&basic, "orange"), vec![3]);
+    assert!(basic.groups_of(2).unwrap().is_empty());
+    assert!(basic.group_by_hue().unwrap().is_empty());
+
+    basic.apply_operations(&undo, None).unwrap();
+    assert_eq!(basic, original);
+}
@@ -0,0 +1,134 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Undo history tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellRef;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::error::PaletteError;
+use crate::palette::BasicPalette;
+use crate::palette::History;
+use crate::palette::Operation;
+
+
+/// Returns a set of operations marked with the given index.
+fn marked_ops(idx: u32) -> Vec<Operation> {
+    vec![Operation::RemoveCell { cell_ref: CellRef::Index(idx) }]
+}
+
+/// Returns the index marking the given operations.
+fn mark(ops: &[Operation]) -> u32 {
+    match ops {
+        [Operation::RemoveCell { cell_ref: CellRef::Index(idx) }] => *idx,
+        _ => panic!("unexpected operations {:?}", ops),
+    }
+}
+
+/// Undoes the next history entry and returns its marking index.
+fn undo_mark(history: &mut History) -> Option<u32> {
+    let mut undone = None;
+    history.undo_with(|ops| {
+        undone = Some(mark(ops));
+        ops.to_vec()
+    });
+    undone
+}
+
+
+/// Tests that pushing past the maximum depth evicts the oldest entries.
+#[test]
+fn history_max_depth_evicts_oldest() {
+    let mut history = History::new();
+    history.set_max_depth(Some(3));
+
+    for idx in 0..5 {
+        history.push_undo_ops(marked_ops(idx));
+        assert!(history.undo_len() <= 3);
+    }
+    assert_eq!(history.undo_len(), 3);
+    assert_eq!(history.redo_len(), 0);
+
+    assert_eq!(undo_mark(&mut history), Some(4));
+    assert_eq!(undo_mark(&mut history), Some(3));
+    assert_eq!(undo_mark(&mut history), Some(2));
+    assert_eq!(undo_mark(&mut history), None);
+    assert_eq!(history.undo_len(), 0);
+    assert_eq!(history.redo_len(), 3);
+}
+
+/// Tests that lowering the maximum depth evicts the oldest entries without
+/// affecting the redo entries.
+#[test]
+fn history_set_max_depth_keeps_redo() {
+    let mut history = History::new();
+    for idx in 0..5 {
+        history.push_undo_ops(marked_ops(idx));
+    }
+    assert_eq!(undo_mark(&mut history), Some(4));
+
+    history.set_max_depth(Some(2));
+    assert_eq!(history.undo_len(), 2);
+    assert_eq!(history.redo_len(), 1);
+    assert_eq!(undo_mark(&mut history), Some(3));
+    assert_eq!(undo_mark(&mut history), Some(2));
+    assert_eq!(undo_mark(&mut history), None);
+}
+
+/// Tests that pushing new operations after an undo clears the redo entries.
+#[test]
+fn history_push_clears_redo() {
+    let mut history = History::new();
+    for idx in 0..3 {
+        history.push_undo_ops(marked_ops(idx));
+    }
+    assert_eq!(undo_mark(&mut history), Some(2));
+    assert_eq!(undo_mark(&mut history), Some(1));
+    assert!(history.can_redo());
+    assert_eq!(history.redo_len(), 2);
+
+    history.push_undo_ops(marked_ops(5));
+    assert!(!history.can_redo());
+    assert_eq!(history.redo_len(), 0);
+    assert_eq!(history.undo_len(), 2);
+
+    let mut redone = false;
+    history.redo_with(|ops| { redone = true; ops.to_vec() });
+    assert!(!redone);
+    assert_eq!(undo_mark(&mut history), Some(5));
+    assert_eq!(undo_mark(&mut history), Some(0));
+}
+
+/// Tests that undoing with a history which does not match the palette returns
+/// an error instead of panicking, and leaves the palette and history intact.
+#[test]
+fn try_undo_mismatched_history() {
+    let color = Color::from(Rgb::from([0x10, 0x20, 0x30]));
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, color.clone()).unwrap();
+    let original = basic.clone();
+
+    let mut history = History::new();
+    basic.apply_operations(
+            &[Operation::MoveCell { from: CellRef::Index(0), to: 5 }],
+            Some(&mut history))
+        .unwrap();
+
+    // The recorded undo moves cell 5 back to 0, but cell 5 does not exist.
+    let mut drifted = original.clone();
+    match drifted.try_undo(&mut history, 1) {
+        Err(PaletteError::UndefinedCellReference { .. }) => (),
+        res => panic!("expected UndefinedCellReference, got {:?}", res),
+    }
+    assert_eq!(drifted, original);
+    assert_eq!(history.undo_len(), 1);
+
+    assert_eq!(basic.try_undo(&mut history, 1).unwrap(), 1);
+    assert_eq!(basic, original);
+}
@@ -0,0 +1,140 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Interpolation tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::color::Color;
+use crate::color::ColorExt as _;
+use crate::color::Rgb;
+use crate::palette::ColorSpace;
+use crate::palette::Interpolate;
+use crate::palette::InterpolateFunction;
+
+
+/// Tests that amounts just outside the range are clamped, while amounts
+/// clearly outside the range are rejected.
+#[test]
+fn interpolate_validate_epsilon() {
+    let interpolate = Interpolate {
+        amount: 1.000_000_1,
+        .. Interpolate::default()
+    };
+    assert_eq!(interpolate.validate().unwrap().amount, 1.0);
+
+    let interpolate = Interpolate {
+        amount: -0.000_000_1,
+        .. Interpolate::default()
+    };
+    assert_eq!(interpolate.validate().unwrap().amount, 0.0);
+
+    let interpolate = Interpolate {
+        amount: 1.1,
+        .. Interpolate::default()
+    };
+    assert!(interpolate.validate().is_err());
+}
+
+
+/// Tests the eased amounts of the smoothstep and ease-in-out functions at
+/// their endpoints, quarter points, and midpoint.
+#[test]
+fn interpolate_easing_midpoints() {
+    let smoothstep = InterpolateFunction::Smoothstep;
+    assert_eq!(smoothstep.ease(0.0), 0.0);
+    assert_eq!(smoothstep.ease(0.25), 0.156_25);
+    assert_eq!(smoothstep.ease(0.5), 0.5);
+    assert_eq!(smoothstep.ease(0.75), 0.843_75);
+    assert_eq!(smoothstep.ease(1.0), 1.0);
+
+    let ease_in_out = InterpolateFunction::EaseInOut;
+    assert_eq!(ease_in_out.ease(0.0), 0.0);
+    assert_eq!(ease_in_out.ease(0.25), 0.125);
+    assert_eq!(ease_in_out.ease(0.5), 0.5);
+    assert_eq!(ease_in_out.ease(0.75), 0.875);
+    assert_eq!(ease_in_out.ease(1.0), 1.0);
+}
+
+
+/// Tests that eased interpolations map amounts of 0.0 and 1.0 exactly to the
+/// endpoint colors.
+#[test]
+fn interpolate_easing_endpoints() {
+    let a = Color::from(Rgb::from([0x12, 0x34, 0x56]));
+    let b = Color::from(Rgb::from([0xAB, 0xCD, 0xEF]));
+
+    for interpolate_fn in [
+        InterpolateFunction::Smoothstep,
+        InterpolateFunction::EaseInOut,
+    ].iter()
+    {
+        let start = interpolate_fn.apply(ColorSpace::Rgb, a, b, 0.0);
+        let end = interpolate_fn.apply(ColorSpace::Rgb, a, b, 1.0);
+        assert_eq!(start.to_rgb_u32(), a.to_rgb_u32());
+        assert_eq!(end.to_rgb_u32(), b.to_rgb_u32());
+    }
+}
+
+
+/// Tests that a 50% mix of black and white is mid-gray in gamma-encoded RGB,
+/// and is lighter when interpolating linear light values.
+#[test]
+fn interpolate_linear_light_black_white_mix() {
+    let black = Color::from(Rgb::from([0x00, 0x00, 0x00]));
+    let white = Color::from(Rgb::from([0xFF, 0xFF, 0xFF]));
+
+    let gamma = Interpolate {
+        amount: 0.5,
+        .. Interpolate::default()
+    };
+    for channel in gamma.apply(black, white).rgb_ratios().iter() {
+        assert!((channel - 0.5).abs() < 1e-4,
+            "expected 0.5, got {}", channel);
+    }
+
+    let linear = Interpolate {
+        amount: 0.5,
+        linear_light: true,
+        .. Interpolate::default()
+    };
+    for channel in linear.apply(black, white).rgb_ratios().iter() {
+        assert!((channel - 0.735_357).abs() < 1e-4,
+            "expected 0.735357, got {}", channel);
+    }
+}
+
+
+/// Tests that interpolation amounts may be written with an exponent and no
+/// decimal point.
+#[test]
+fn interpolate_parse_exponent_amount() {
+    let interpolate: Interpolate = "1e-1".parse().unwrap();
+    assert_eq!(interpolate.amount, 0.1);
+
+    let interpolate: Interpolate = "linear(1e-1)".parse().unwrap();
+    assert_eq!(interpolate.amount, 0.1);
+
+    let interpolate: Interpolate = "smoothstep(1E-1, rgb)".parse().unwrap();
+    assert_eq!(interpolate.interpolate_fn, InterpolateFunction::Smoothstep);
+    assert_eq!(interpolate.amount, 0.1);
+}
+
+
+/// Tests that NaN interpolation amounts are rejected.
+#[test]
+fn interpolate_parse_nan_amount() {
+    assert!("nan".parse::<Interpolate>().is_err());
+    assert!("NaN".parse::<Interpolate>().is_err());
+    assert!("linear(nan)".parse::<Interpolate>().is_err());
+
+    let interpolate = Interpolate {
+        amount: std::f32::NAN,
+        .. Interpolate::default()
+    };
+    assert!(interpolate.validate().is_err());
+}
@@ -0,0 +1,166 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Palette journal tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::Cell;
+use crate::cell::CellRef;
+use crate::cell::Position;
+use crate::cell::PositionSelector;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+use crate::palette::Expr;
+use crate::palette::History;
+use crate::palette::Operation;
+use crate::palette::PaletteJournal;
+
+// Standard library imports.
+use std::io::Cursor;
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+
+/// Returns a palette with two cells, the first of which is positioned.
+fn base_palette() -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0xFF, 0x00, 0x00])))
+        .unwrap();
+    let _ = basic.insert_reference(1, CellRef::Index(0)).unwrap();
+    let _ = basic.assign_position(Position::ZERO, CellRef::Index(0)).unwrap();
+    basic
+}
+
+/// Returns a sequence of operations to apply to the base palette.
+fn journal_operations() -> Vec<Operation> {
+    vec![
+        Operation::InsertCell {
+            idx: 2,
+            cell: Cell::new_with_expr(
+                Expr::Color(Color::from(Rgb::from([0x00, 0x00, 0xFF])))),
+        },
+        Operation::AssignPosition {
+            cell_ref: CellRef::Index(2),
+            position: Position { page: 0, line: 0, column: 1 },
+        },
+        Operation::AssignName {
+            selector: PositionSelector::new(0, 0, None),
+            name: "first".into(),
+        },
+        Operation::SetDescription {
+            cell_ref: CellRef::Index(2),
+            description: "blue".into(),
+        },
+        Operation::AddTag {
+            cell_ref: CellRef::Index(2),
+            tag: "cool".into(),
+        },
+        Operation::LockCell {
+            cell_ref: CellRef::Index(0),
+        },
+        Operation::SetPositionCursor {
+            position: Position { page: 0, line: 0, column: 2 },
+        },
+    ]
+}
+
+/// A journal writer whose output can be read while it is attached to a
+/// palette.
+#[derive(Debug, Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    /// Returns a copy of the written data.
+    fn data(&self) -> Vec<u8> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+
+/// Tests that replaying a journal onto a saved base palette produces the same
+/// palette as applying the journaled operations in memory.
+#[test]
+fn journal_replay_matches_in_memory() {
+    let mut basic = base_palette();
+    let mut base = Vec::new();
+    basic.write_to_writer(&mut base).expect("write base palette");
+
+    let mut journal = PaletteJournal::new(Vec::new());
+    for op in journal_operations() {
+        basic.apply_operations(&[op.clone()], None).unwrap();
+        journal.append(&op).expect("append journal entry");
+    }
+    assert_eq!(journal.len(), journal_operations().len());
+
+    let mut replayed = BasicPalette::read_from_reader(&mut Cursor::new(base))
+        .expect("read base palette");
+    let count = replayed
+        .replay_journal(Cursor::new(journal.into_inner()))
+        .expect("replay journal");
+    assert_eq!(count, journal_operations().len());
+    assert_eq!(replayed, basic);
+    assert!(replayed.cell(&CellRef::Index(0)).unwrap().is_locked());
+}
+
+/// Tests that an incomplete final journal entry is ignored during replay.
+#[test]
+fn journal_replay_ignores_incomplete_entry() {
+    let mut journal = PaletteJournal::new(Vec::new());
+    journal.append_all(&journal_operations()).expect("append journal");
+    let mut data = journal.into_inner();
+    data.extend_from_slice(b"SetPositionCursor(position:(0,");
+
+    let mut expected = base_palette();
+    expected.apply_operations(&journal_operations(), None).unwrap();
+
+    let mut replayed = base_palette();
+    let count = replayed.replay_journal(Cursor::new(data))
+        .expect("replay journal");
+    assert_eq!(count, journal_operations().len());
+    assert_eq!(replayed, expected);
+}
+
+/// Tests that operations applied through a palette with an attached journal,
+/// including undo and redo, are recorded and replayed.
+#[test]
+fn journal_attached_to_palette() {
+    let mut basic = base_palette();
+    let base = basic.clone();
+    let buffer = SharedBuffer::default();
+    let _ = basic.set_journal(Some(
+        PaletteJournal::new(buffer.clone()).into_shared()));
+
+    let mut history = History::new();
+    basic.apply_operations(&journal_operations(), Some(&mut history))
+        .unwrap();
+    assert_eq!(basic.undo(&mut history, 1), 1);
+    assert_eq!(basic.redo(&mut history, 1), 1);
+    let journaled = basic.journal().unwrap().lock().unwrap().len();
+    assert!(journaled > journal_operations().len());
+
+    // Operations applied by replaying are not journaled again.
+    let mut replayed = base.clone();
+    let _ = replayed.set_journal(basic.set_journal(None));
+    let count = replayed.replay_journal(Cursor::new(buffer.data()))
+        .expect("replay journal");
+    assert_eq!(count, journaled);
+    assert_eq!(replayed, basic);
+    assert_eq!(replayed.journal().unwrap().lock().unwrap().len(), journaled);
+}
@@ -0,0 +1,94 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Cell lock tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::Axis;
+use crate::cell::CellIndexSelection;
+use crate::cell::CellRef;
+use crate::cell::Position;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::error::PaletteError;
+use crate::palette::BasicPalette;
+use crate::palette::Expr;
+
+
+/// Returns a palette holding red, green, and blue cells, with the green cell
+/// locked, followed by black and white cells.
+fn palette_with_locked_cell() -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    for (idx, rgb) in [
+        [0xFF, 0x00, 0x00],
+        [0x00, 0xFF, 0x00],
+        [0x00, 0x00, 0xFF],
+        [0x00, 0x00, 0x00],
+        [0xFF, 0xFF, 0xFF],
+    ].iter().enumerate()
+    {
+        let _ = basic.insert_color(idx as u32, Color::from(Rgb::from(*rgb)))
+            .unwrap();
+    }
+    let _ = basic.lock_cell(CellRef::Index(1)).unwrap();
+    basic
+}
+
+/// Checks that the given result is a `CellLocked` error for index 1.
+fn check_locked<T>(res: Result<T, PaletteError>)
+    where T: std::fmt::Debug
+{
+    match res {
+        Err(PaletteError::CellLocked { idx: 1 }) => (),
+        res => panic!("expected CellLocked error, got {:?}", res),
+    }
+}
+
+/// Tests that a locked cell rejects expression changes, but still resolves
+/// its color.
+#[test]
+fn locked_cell_rejects_set_expr() {
+    let color = Color::from(Rgb::from([0x12, 0x34, 0x56]));
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, color.clone()).unwrap();
+    let _ = basic.lock_cell(CellRef::Index(0)).unwrap();
+
+    match basic.set_expr(CellRef::Index(0), Expr::Empty) {
+        Err(PaletteError::CellLocked { idx: 0 }) => (),
+        res => panic!("expected CellLocked error, got {:?}", res),
+    }
+
+    assert_eq!(basic.color(&CellRef::Index(0)).unwrap(), Some(color));
+}
+
+/// Tests that operations on a selection containing a locked cell fail without
+/// modifying any of the selected cells.
+#[test]
+fn locked_cell_in_selection_leaves_palette_unmodified() {
+    let mut basic = palette_with_locked_cell();
+    let original = basic.clone();
+    let selection: CellIndexSelection = [0, 1, 2].iter().copied().collect();
+
+    check_locked(basic.assign_positions_sequential(
+        &selection,
+        Position::ZERO,
+        Axis::Column));
+    assert_eq!(basic, original);
+
+    check_locked(basic.group_by_hue());
+    assert_eq!(basic, original);
+
+    check_locked(basic.colorize(
+        &CellRef::Index(3),
+        &CellRef::Index(4),
+        &selection));
+    assert_eq!(basic, original);
+
+    check_locked(basic.auto_assign_positions(4, 4));
+    assert_eq!(basic, original);
+}
@@ -0,0 +1,91 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Palette merge tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellRef;
+use crate::cell::Position;
+use crate::cell::PositionSelector;
+use crate::color::Color;
+use crate::color::ColorExt as _;
+use crate::error::PaletteError;
+use crate::palette::BasicPalette;
+use crate::palette::Expr;
+use crate::palette::History;
+
+
+/// Returns a palette with a single positioned, named, and grouped cell.
+fn base_palette(name: &'static str, color: u32) -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from_rgb_u32(color)).unwrap();
+    let _ = basic.assign_position(Position::ZERO, CellRef::Index(0)).unwrap();
+    let _ = basic.assign_name(name, PositionSelector::new(0, 0, 0), false)
+        .unwrap();
+    let _ = basic.assign_group(CellRef::Index(0), "g", None).unwrap();
+    basic
+}
+
+
+/// Tests that merging re-homes the merged cells and their references, names,
+/// positions, and groups, and that the merge is undone.
+#[test]
+fn merge_rehomes_cells() {
+    let mut basic = base_palette("base", 0xFF0000);
+    let original = basic.clone();
+    let mut other = base_palette("other", 0x0000FF);
+    let _ = other.insert_reference(1, CellRef::Index(0)).unwrap();
+    let _ = other.assign_group(CellRef::Index(1), "g", None).unwrap();
+
+    let mut history = History::new();
+    let ops = basic.merge(other, None).unwrap();
+    history.push_undo_ops(ops);
+
+    assert_eq!(
+        basic.cell(&CellRef::Index(2)).unwrap().expr(),
+        &Expr::Reference(CellRef::Index(1)));
+    assert_eq!(
+        basic.color(&CellRef::Index(2)).unwrap().map(|c| c.to_rgb_u32()),
+        Some(0x0000FF));
+    assert_eq!(
+        basic.assigned_position(&CellRef::Index(1)),
+        Some(&Position { page: 1, line: 0, column: 0 }));
+    assert_eq!(
+        basic.resolve_ref_to_index(&CellRef::Name("other".into())).unwrap(),
+        1);
+    assert_eq!(
+        basic.resolve_ref_to_index(&CellRef::Name("base".into())).unwrap(),
+        0);
+    for (group_idx, idx) in [0, 1, 2].iter().enumerate() {
+        assert_eq!(
+            basic.resolve_ref_to_index(&CellRef::Group {
+                group: "g".into(),
+                idx: group_idx as u32,
+            }).unwrap(),
+            *idx);
+    }
+
+    assert_eq!(basic.undo(&mut history, 1), 1);
+    assert_eq!(basic, original);
+}
+
+/// Tests that merging a palette with a colliding name reports the collision
+/// without modifying the palette.
+#[test]
+fn merge_name_collision() {
+    let mut basic = base_palette("base", 0xFF0000);
+    let original = basic.clone();
+
+    match basic.merge(base_palette("base", 0x0000FF), None) {
+        Err(PaletteError::InvalidInputValue { msg }) => {
+            assert!(msg.contains("base"), "unexpected message {:?}", msg);
+        },
+        res => panic!("expected InvalidInputValue, got {:?}", res),
+    }
+    assert_eq!(basic, original);
+}
@@ -0,0 +1,42 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Atma test suite.
+////////////////////////////////////////////////////////////////////////////////
+
+mod blend;
+mod clear;
+mod color;
+mod command;
+mod diff;
+mod distance;
+mod error;
+mod group;
+mod history;
+mod interpolate;
+mod journal;
+mod lock;
+mod merge;
+mod name;
+#[cfg(feature = "name-regex")]
+mod name_regex;
+#[cfg(feature = "named-colors")]
+mod named_color;
+mod occupied;
+mod operation;
+mod parse;
+mod position;
+mod ramp;
+mod reference;
+#[cfg(feature = "image")]
+mod render;
+mod reverse_index;
+mod revision;
+mod selection;
+mod serialize;
+mod sort;
+mod tag;
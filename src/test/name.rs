@@ -0,0 +1,135 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Name assignment, resolution, and search tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellRef;
+use crate::cell::Position;
+use crate::cell::PositionSelector;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::error::PaletteError;
+use crate::palette::BasicPalette;
+
+// Standard library imports.
+use std::io::Cursor;
+
+
+/// Constructs a palette with a cell at each column of the first line, named
+/// by the given names in order.
+fn palette_with_names(names: &[&'static str]) -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    for (idx, name) in names.iter().enumerate() {
+        let _ = basic
+            .insert_color(idx as u32, Color::from(Rgb::from([0, 0, 0])))
+            .unwrap();
+        let _ = basic.assign_position(
+                Position { page: 0, line: 0, column: idx as u16 },
+                CellRef::Index(idx as u32))
+            .unwrap();
+        let _ = basic.assign_name(
+                *name,
+                PositionSelector::new(0, 0, idx as u16),
+                false)
+            .unwrap();
+    }
+    basic
+}
+
+
+/// Tests that a query matches both exact and partial names, with the exact
+/// match ranked first.
+#[test]
+fn search_names_exact_ranked_first() {
+    let mut basic = palette_with_names(
+        &["dark red", "rusted", "blue", "reddish", "red"]);
+    // Names which do not resolve to an occupied cell are omitted.
+    let _ = basic
+        .assign_name("red ghost", PositionSelector::new(1, 0, 0), false)
+        .unwrap();
+
+    let matches = basic.search_names("RED");
+    let matches: Vec<(&str, u32)> = matches
+        .iter()
+        .map(|(name, idx)| (name.as_ref(), *idx))
+        .collect();
+    assert_eq!(matches, vec![
+        ("red", 4),
+        ("reddish", 3),
+        ("dark red", 0),
+        ("rusted", 1),
+    ]);
+}
+
+/// Tests that assigning an assigned name to a different selector is rejected
+/// unless forced.
+#[test]
+fn assign_name_rejects_overwrite() {
+    let mut basic = palette_with_names(&["warm", "cool"]);
+    let original = basic.clone();
+
+    match basic.assign_name("warm", PositionSelector::new(0, 0, 1), false) {
+        Err(PaletteError::NameAlreadyAssigned { name, existing_selector }) => {
+            assert_eq!(name, "warm");
+            assert_eq!(existing_selector, PositionSelector::new(0, 0, 0));
+        },
+        res => panic!("expected NameAlreadyAssigned, got {:?}", res),
+    }
+    assert_eq!(basic, original);
+
+    // Reassigning the same selector is not an overwrite.
+    let _ = basic.assign_name("warm", PositionSelector::new(0, 0, 0), false)
+        .unwrap();
+    assert_eq!(basic, original);
+
+    let _ = basic.assign_name("warm", PositionSelector::new(0, 0, 1), true)
+        .unwrap();
+    assert_eq!(
+        basic.resolve_ref_to_index(&CellRef::Name("warm".into())).unwrap(),
+        1);
+}
+
+/// Tests that names resolve case-insensitively when enabled, and that the
+/// setting is preserved when the palette is saved and reopened.
+#[test]
+fn case_insensitive_names_resolve() {
+    let mut basic = palette_with_names(&["Warm", "cool"]);
+    assert!(basic.resolve_ref_to_index(&CellRef::Name("warm".into())).is_err());
+
+    basic.set_case_insensitive_names(true).unwrap();
+    for name in ["warm", "Warm", "WARM"].iter() {
+        assert_eq!(
+            basic.resolve_ref_to_index(&CellRef::Name((*name).into())).unwrap(),
+            0);
+    }
+
+    let mut buf = Vec::new();
+    basic.write_to_writer(&mut buf).expect("write palette");
+    let read = BasicPalette::read_from_reader(&mut Cursor::new(buf))
+        .expect("read palette");
+    assert!(read.case_insensitive_names());
+    assert_eq!(
+        read.resolve_ref_to_index(&CellRef::Name("COOL".into())).unwrap(),
+        1);
+}
+
+/// Tests that enabling case-insensitivity fails without modifying the
+/// palette if two names differ only by case.
+#[test]
+fn case_insensitive_names_collision() {
+    let mut basic = palette_with_names(&["Warm", "warm"]);
+    let original = basic.clone();
+
+    match basic.set_case_insensitive_names(true) {
+        Err(PaletteError::NameAlreadyAssigned { .. }) => (),
+        res => panic!("expected NameAlreadyAssigned, got {:?}", res),
+    }
+    assert!(!basic.case_insensitive_names());
+    assert_eq!(basic, original);
+}
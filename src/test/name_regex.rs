@@ -0,0 +1,105 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Name regex selector tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellRef;
+use crate::cell::CellSelection;
+use crate::cell::CellSelector;
+use crate::cell::Position;
+use crate::cell::PositionSelector;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+
+// External library imports.
+use ron::de::from_str;
+
+
+/// Returns a palette with three positioned and named cells.
+fn named_palette() -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    for (idx, name) in ["dark red", "dark blue", "light blue"]
+        .iter()
+        .enumerate()
+    {
+        let idx = idx as u32;
+        let column = idx as u16;
+        let _ = basic.insert_color(idx, Color::from(Rgb::from([0, 0, 0])))
+            .unwrap();
+        let _ = basic.assign_position(
+                Position { page: 0, line: 0, column },
+                CellRef::Index(idx))
+            .unwrap();
+        let _ = basic.assign_name(
+                *name,
+                PositionSelector::new(0, 0, column),
+                false)
+            .unwrap();
+    }
+    basic
+}
+
+
+/// Tests parsing and resolving name regex selectors.
+#[test]
+fn name_regex_resolve() {
+    let basic = named_palette();
+
+    let selection: CellSelection<'static> = "/^dark/".parse().unwrap();
+    assert_eq!(selection.resolve(&basic).iter().collect::<Vec<_>>(),
+        vec![0, 1]);
+
+    let selection: CellSelection<'static> = "/blue$/".parse().unwrap();
+    assert_eq!(selection.resolve(&basic).iter().collect::<Vec<_>>(),
+        vec![1, 2]);
+
+    let selection: CellSelection<'static> = "/green/".parse().unwrap();
+    assert!(selection.resolve(&basic).iter().next().is_none());
+}
+
+/// Tests that an escaped delimiter in a pattern is parsed and displayed.
+#[test]
+fn name_regex_escaped_delimiter() {
+    let selection: CellSelection<'static> = r"/a\/b/".parse().unwrap();
+    let selector = selection.into_iter().next().unwrap();
+    assert_eq!(selector, CellSelector::NameRegex("a/b".into()));
+    assert_eq!(selector.to_string(), r"/a\/b/");
+}
+
+/// Tests that invalid patterns are rejected when parsed or deserialized.
+#[test]
+fn name_regex_invalid_pattern() {
+    assert!("/(/".parse::<CellSelection<'static>>().is_err());
+    assert!("/[a-/".parse::<CellSelection<'static>>().is_err());
+
+    assert!(from_str::<CellSelector<'static>>("NameRegex(\"(\")").is_err());
+    assert_eq!(
+        from_str::<CellSelector<'static>>("NameRegex(\"^dark\")").unwrap(),
+        CellSelector::NameRegex("^dark".into()));
+}
+
+/// Tests that constructed selectors with invalid patterns select nothing,
+/// while the other selectors in the selection still resolve.
+#[test]
+fn name_regex_invalid_pattern_resolve() {
+    let basic = named_palette();
+    let selection: CellSelection<'static> = vec![
+            CellSelector::NameRegex("(".into()),
+            CellSelector::NameRegex("light".into()),
+            CellSelector::Subtract(
+                Box::new(CellSelector::NameRegex("(".into()))),
+            CellSelector::Index(0),
+        ]
+        .into();
+
+    assert_eq!(selection.resolve(&basic).iter().collect::<Vec<_>>(),
+        vec![0, 2]);
+    assert_eq!(selection.resolve_ordered(&basic), vec![2, 0]);
+}
@@ -0,0 +1,47 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Named color tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellRef;
+use crate::color::Color;
+use crate::color::ColorExt as _;
+use crate::color::named_color;
+use crate::palette::BasicPalette;
+
+
+/// Tests looking up a few known color names, ignoring case.
+#[test]
+fn named_color_lookup() {
+    let cases = [
+        ("tomato", 0xFF6347),
+        ("RebeccaPurple", 0x663399),
+        ("CORNFLOWERBLUE", 0x6495ED),
+        ("white", 0xFFFFFF),
+    ];
+    for (name, rgb) in cases.iter() {
+        assert_eq!(
+            named_color(name).map(|color| color.to_rgb_u32()),
+            Some(*rgb),
+            "looking up {}", name);
+    }
+    assert_eq!(named_color("notacolor"), None);
+}
+
+/// Tests setting a cell's expression to a named color.
+#[test]
+fn named_color_set_expr() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from_rgb_u32(0x000000)).unwrap();
+
+    let _ = basic.set_expr_from_str(CellRef::Index(0), "tomato").unwrap();
+    assert_eq!(
+        basic.color(&CellRef::Index(0)).unwrap().map(|c| c.to_rgb_u32()),
+        Some(0xFF6347));
+}
@@ -0,0 +1,40 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Occupied cell query tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+
+
+/// Tests the occupied cell count and indices of an empty palette.
+#[test]
+fn occupied_cells_empty() {
+    let basic = BasicPalette::new();
+
+    assert_eq!(basic.occupied_cell_count(), 0);
+    assert_eq!(basic.occupied_indices().next(), None);
+}
+
+/// Tests the occupied cell count and indices of a palette with gaps in its
+/// indices.
+#[test]
+fn occupied_cells_with_gaps() {
+    let mut basic = BasicPalette::new();
+    for idx in [7, 0, 3, 100].iter() {
+        let _ = basic.insert_color(*idx, Color::from(Rgb::from([0, 0, 0])))
+            .unwrap();
+    }
+
+    assert_eq!(basic.occupied_cell_count(), 4);
+    assert_eq!(
+        basic.occupied_indices().collect::<Vec<_>>(),
+        vec![0, 3, 7, 100]);
+}
@@ -0,0 +1,143 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Palette operation tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::Cell;
+use crate::cell::CellRef;
+use crate::cell::Position;
+use crate::color::Color;
+use crate::color::ColorExt as _;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+use crate::palette::Expr;
+use crate::palette::History;
+use crate::palette::Operation;
+
+
+/// Tests that `move_cell` carries the cell's position and groups, and that
+/// its undo operation restores the original index.
+#[test]
+fn move_cell_undo() {
+    let position = Position { page: 0, line: 0, column: 0 };
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0x10, 0x20, 0x30])))
+        .unwrap();
+    let _ = basic.assign_position(position, CellRef::Index(0)).unwrap();
+    let _ = basic.assign_group(CellRef::Index(0), "group", None).unwrap();
+    let original = basic.clone();
+
+    let undo = basic.move_cell(CellRef::Index(0), 5).unwrap();
+    assert!(basic.cell(&CellRef::Index(0)).is_err());
+    assert!(basic.cell(&CellRef::Index(5)).is_ok());
+    assert_eq!(
+        basic.resolve_ref_to_index(&CellRef::Position(position)).unwrap(),
+        5);
+    assert_eq!(
+        basic.resolve_ref_to_index(&CellRef::Group {
+            group: "group".into(),
+            idx: 0,
+        }).unwrap(),
+        5);
+
+    basic.apply_operations(&undo, None).unwrap();
+    assert_eq!(basic, original);
+}
+
+/// Tests setting a cell's expression from a parsed blend expression, and that
+/// its undo operation restores the previous expression.
+#[test]
+fn set_expr_from_str_blend() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from_rgb_u32(0xFFFF00)).unwrap();
+    let _ = basic.insert_color(1, Color::from_rgb_u32(0x00FFFF)).unwrap();
+    let _ = basic.insert_color(2, Color::from_rgb_u32(0x000000)).unwrap();
+    let original = basic.clone();
+
+    let undo = basic
+        .set_expr_from_str(CellRef::Index(2), "multiply(:0, :1)")
+        .unwrap();
+    match basic.cell(&CellRef::Index(2)).unwrap().expr() {
+        Expr::Blend(_) => (),
+        expr => panic!("expected blend expression, got {:?}", expr),
+    }
+    assert_eq!(
+        basic.color(&CellRef::Index(2)).unwrap().unwrap().to_rgb_u32(),
+        0x00FF00);
+
+    basic.apply_operations(&undo, None).unwrap();
+    assert_eq!(basic, original);
+
+    assert!(basic.set_expr_from_str(CellRef::Index(2), "multiply(:0").is_err());
+    assert_eq!(basic, original);
+}
+
+/// Tests that swapping an occupied index with an unoccupied one moves the
+/// cell while positions stay with their indices, and that undoing the swap
+/// restores the gap.
+#[test]
+fn swap_cells_with_gap_undo() {
+    let position = Position { page: 0, line: 0, column: 0 };
+    let color = Color::from(Rgb::from([0x10, 0x20, 0x30]));
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, color.clone()).unwrap();
+    let _ = basic.assign_position(position, CellRef::Index(0)).unwrap();
+    let original = basic.clone();
+
+    let mut history = History::new();
+    basic.apply_operations(
+            &[Operation::SwapCells {
+                a: CellRef::Index(0),
+                b: CellRef::Index(2),
+            }],
+            Some(&mut history))
+        .unwrap();
+    assert!(basic.cell(&CellRef::Index(0)).is_err());
+    assert_eq!(basic.color(&CellRef::Index(2)).unwrap(), Some(color.clone()));
+    assert_eq!(
+        basic.resolve_ref_to_index(&CellRef::Position(position)).unwrap(),
+        0);
+
+    assert_eq!(basic.undo(&mut history, 1), 1);
+    assert_eq!(basic, original);
+    assert!(basic.cell(&CellRef::Index(2)).is_err());
+    assert_eq!(basic.color(&CellRef::Index(0)).unwrap(), Some(color));
+}
+
+/// Tests that `insert_color` and `insert_reference` produce the same palette
+/// as inserting the equivalent cells manually, and that their undo operations
+/// are equivalent.
+#[test]
+fn insert_helpers_match_manual_insert() {
+    let color = Color::from(Rgb::from([0x10, 0x20, 0x30]));
+
+    let mut helper = BasicPalette::new();
+    let mut helper_undo = helper.insert_color(0, color.clone()).unwrap();
+    helper_undo.extend(helper.insert_reference(1, CellRef::Index(0))
+        .unwrap());
+
+    let mut manual = BasicPalette::new();
+    let mut manual_undo = manual
+        .insert_cell(0, Cell::new_with_expr(Expr::Color(color)))
+        .unwrap();
+    manual_undo.extend(manual
+        .insert_cell(1, Cell::new_with_expr(
+            Expr::Reference(CellRef::Index(0))))
+        .unwrap());
+
+    assert_eq!(helper, manual);
+    assert_eq!(
+        helper.color(&CellRef::Index(1)).unwrap(),
+        manual.color(&CellRef::Index(1)).unwrap());
+
+    helper.apply_operations(&helper_undo, None).unwrap();
+    manual.apply_operations(&manual_undo, None).unwrap();
+    assert_eq!(helper, manual);
+    assert_eq!(helper, BasicPalette::new());
+}
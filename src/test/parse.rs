@@ -0,0 +1,98 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Parser tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::color::ColorExt as _;
+use crate::palette::BasicPalette;
+use crate::palette::ColorEncoding;
+use crate::palette::Expr;
+use crate::palette::InsertExpr;
+use crate::palette::TaggedColor;
+use crate::parse::AtmaScanner;
+use crate::parse::AtmaToken;
+use crate::parse::string;
+
+// External library imports.
+use tephra::lexer::Lexer;
+use tephra::position::Lf;
+use tephra::result::ParseResultExt as _;
+
+
+/// Parses the given text as a string, returning None if the parse fails.
+fn parse_string(text: &str) -> Option<String> {
+    let scanner = AtmaScanner::new();
+    let column_metrics = Lf::with_tab_width(4);
+    let mut lexer = Lexer::new(scanner, text, column_metrics);
+    lexer.set_filter_fn(|tok| !tok.is_whitespace_or_comment());
+
+    string(lexer)
+        .finish()
+        .ok()
+        .map(|s| s.into_owned())
+}
+
+/// Scans the given text, returning its non-whitespace tokens.
+fn scan_tokens(text: &str) -> Vec<AtmaToken> {
+    let scanner = AtmaScanner::new();
+    let column_metrics = Lf::with_tab_width(4);
+    let mut lexer = Lexer::new(scanner, text, column_metrics);
+    lexer.set_filter_fn(|tok| !tok.is_whitespace_or_comment());
+
+    lexer.collect()
+}
+
+/// Parses the given text as an inserted color, returning its tagged color.
+fn parse_tagged_color(text: &str) -> TaggedColor {
+    let insert_expr: InsertExpr = text.parse().unwrap();
+    match insert_expr.exprs(&BasicPalette::new()).unwrap().pop() {
+        Some(Expr::TaggedColor(tagged)) => tagged,
+        expr => panic!("expected tagged color, got {:?}", expr),
+    }
+}
+
+
+/// Tests parsing a string containing a valid unicode escape.
+#[test]
+fn string_unicode_escape() {
+    assert_eq!(parse_string(r"'\u{00e9}'"), Some("\u{00e9}".to_owned()));
+}
+
+/// Tests that a string containing an invalid unicode escape fails to parse.
+#[test]
+fn string_invalid_unicode_escape() {
+    assert_eq!(parse_string(r"'\u{zz}'"), None);
+}
+
+/// Tests that a color authored in HSV is displayed in HSV, and that the
+/// displayed color parses to the same color.
+#[test]
+fn tagged_color_hsv_round_trip() {
+    let tagged = parse_tagged_color("hsv(120.0, 1.0, 1.0)");
+    assert_eq!(tagged.encoding, ColorEncoding::Hsv);
+    assert_eq!(tagged.color.to_rgb_u32(), 0x00FF00);
+
+    let text = tagged.to_string();
+    assert!(text.starts_with("hsv("), "unexpected display {:?}", text);
+
+    let reparsed = parse_tagged_color(&text);
+    assert_eq!(reparsed.encoding, ColorEncoding::Hsv);
+    assert_eq!(reparsed.color.to_rgb_u32(), 0x00FF00);
+}
+
+/// Tests that an exponent-only float is scanned as a Float, but not when it
+/// follows a reference prefix.
+#[test]
+fn scan_exponent_only_float() {
+    use AtmaToken::*;
+    assert_eq!(scan_tokens("1e2"), vec![Float]);
+    assert_eq!(scan_tokens(":1e2"), vec![Colon, Uint, Ident]);
+    assert_eq!(scan_tokens(": 1e2"), vec![Colon, Uint, Ident]);
+    assert_eq!(scan_tokens(":1, 1e2"), vec![Colon, Uint, Comma, Float]);
+}
@@ -0,0 +1,169 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Position tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::Axis;
+use crate::cell::CellIndexSelection;
+use crate::cell::CellRef;
+use crate::cell::Position;
+use crate::cell::PositionSelector;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+
+
+/// Tests that `prev` wraps symmetrically with `wrapping_succ`.
+#[test]
+fn position_prev_wraps() {
+    assert_eq!(Position::ZERO.prev(), Position::MAX);
+    assert_eq!(Position::MAX.wrapping_succ(), Position::ZERO);
+    assert_eq!(Position::ZERO.checked_prev(), None);
+
+    let pos = Position { page: 1, line: 0, column: 0 };
+    assert_eq!(pos.prev(), Position {
+        page: 0,
+        line: u16::MAX,
+        column: u16::MAX,
+    });
+    assert_eq!(pos.prev().wrapping_succ(), pos);
+}
+
+/// Tests that offsetting past the last column rolls into the next line, and
+/// past the last line into the next page.
+#[test]
+fn position_offset_column_overflow() {
+    let pos = Position { page: 0, line: 3, column: u16::MAX };
+    assert_eq!(
+        pos.offset(0, 0, 1),
+        Some(Position { page: 0, line: 4, column: 0 }));
+    assert_eq!(pos.offset(0, 0, 1).and_then(|p| p.offset(0, 0, -1)),
+        Some(pos));
+
+    let pos = Position { page: 2, line: u16::MAX, column: u16::MAX };
+    assert_eq!(
+        pos.offset(0, 0, 2),
+        Some(Position { page: 3, line: 0, column: 1 }));
+    assert_eq!(
+        pos.offset(0, 1, 0),
+        Some(Position { page: 3, line: 0, column: u16::MAX }));
+
+    assert_eq!(Position::MAX.offset(0, 0, 1), None);
+    assert_eq!(Position::ZERO.offset(0, 0, -1), None);
+}
+
+/// Tests that `positions_within` yields the selected positions within the
+/// bounds in position order, starting partway through a line.
+#[test]
+fn position_selector_positions_within() {
+    let selector = PositionSelector::new(1, None, 2);
+    let pos = |line, column| Position { page: 1, line, column };
+
+    assert_eq!(
+        selector.positions_within((pos(0, 0), pos(2, 5))).collect::<Vec<_>>(),
+        vec![pos(0, 2), pos(1, 2), pos(2, 2)]);
+    assert_eq!(
+        selector.positions_within((pos(0, 3), pos(2, 1))).collect::<Vec<_>>(),
+        vec![pos(1, 2)]);
+    assert!(selector
+        .positions_within((pos(0, 0), pos(2, 5)))
+        .all(|p| selector.contains(&p)));
+    assert_eq!(
+        selector
+            .positions_within((
+                Position { page: 2, line: 0, column: 0 },
+                Position::MAX))
+            .next(),
+        None);
+}
+
+/// Tests enumerating the positions a `:1.*.*` selector covers within a small
+/// bounded range, and that a fully specified selector yields one position.
+#[test]
+fn position_selector_positions_bounded() {
+    let selector: PositionSelector = ":1.*.*".parse().unwrap();
+    let low = Position { page: 0, line: 5, column: 5 };
+    let high = Position { page: 1, line: 0, column: 3 };
+
+    assert_eq!(
+        selector.positions_within((low, high)).collect::<Vec<_>>(),
+        (0..=3)
+            .map(|column| Position { page: 1, line: 0, column })
+            .collect::<Vec<_>>());
+
+    let pos = Position { page: 2, line: 3, column: 4 };
+    assert_eq!(
+        PositionSelector::from(pos).positions().collect::<Vec<_>>(),
+        vec![pos]);
+}
+
+/// Tests automatically laying out five cells in a 2x2 grid across two pages,
+/// and that the returned operations undo the assignments.
+#[test]
+fn auto_assign_positions_two_pages() {
+    let mut basic = BasicPalette::new();
+    for idx in 0..5 {
+        let _ = basic.insert_color(idx, Color::from(Rgb::from([0, 0, 0])))
+            .unwrap();
+    }
+    let original = basic.clone();
+
+    let undo = basic.auto_assign_positions(2, 2).unwrap();
+    let expected = [
+        Position { page: 0, line: 0, column: 0 },
+        Position { page: 0, line: 0, column: 1 },
+        Position { page: 0, line: 1, column: 0 },
+        Position { page: 0, line: 1, column: 1 },
+        Position { page: 1, line: 0, column: 0 },
+    ];
+    for (idx, position) in expected.iter().enumerate() {
+        assert_eq!(
+            basic.assigned_position(&CellRef::Index(idx as u32)),
+            Some(position));
+    }
+
+    basic.apply_operations(&undo, None).unwrap();
+    assert_eq!(basic, original);
+}
+
+/// Tests assigning consecutive columns to four cells in index order, and that
+/// the assignment is undone.
+#[test]
+fn assign_positions_sequential_column() {
+    let mut basic = BasicPalette::new();
+    for idx in [7, 0, 5, 3].iter() {
+        let _ = basic.insert_color(*idx, Color::from(Rgb::from([0, 0, 0])))
+            .unwrap();
+    }
+    let original = basic.clone();
+    let selection: CellIndexSelection = [7, 0, 5, 3].iter().copied().collect();
+    let start = Position { page: 1, line: 2, column: 3 };
+
+    let undo = basic
+        .assign_positions_sequential(&selection, start, Axis::Column)
+        .unwrap();
+    for (column, idx) in [(3, 0), (4, 3), (5, 5), (6, 7)].iter() {
+        assert_eq!(
+            basic.assigned_position(&CellRef::Index(*idx)),
+            Some(&Position { page: 1, line: 2, column: *column }));
+    }
+
+    basic.apply_operations(&undo, None).unwrap();
+    assert_eq!(basic, original);
+
+    // No positions are assigned if any target position is occupied.
+    let _ = basic.assign_position(
+            Position { page: 1, line: 2, column: 5 },
+            CellRef::Index(5))
+        .unwrap();
+    let occupied = basic.clone();
+    assert!(basic.assign_positions_sequential(&selection, start, Axis::Column)
+        .is_err());
+    assert_eq!(basic, occupied);
+}
@@ -0,0 +1,120 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Ramp and gradient expression tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::Cell;
+use crate::cell::CellRef;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+use crate::palette::BinaryBlendFunction;
+use crate::palette::BinaryBlendMethod;
+use crate::palette::BlendFunction;
+use crate::palette::ColorSpace;
+use crate::palette::Expr;
+use crate::palette::InsertExpr;
+use crate::palette::InterpolateRange;
+use crate::palette::RampExpr;
+
+
+/// Returns a ramp of the given amounts between the given cells.
+fn ramp(arg_0: u32, arg_1: u32, amounts: Vec<f32>) -> RampExpr {
+    RampExpr {
+        count: amounts.len() as u8,
+        blend_fn: BlendFunction::Binary(BinaryBlendFunction {
+            color_space: ColorSpace::Rgb,
+            blend_method: BinaryBlendMethod::Blend,
+            arg_0: CellRef::Index(arg_0),
+            arg_1: CellRef::Index(arg_1),
+        }),
+        interpolate: InterpolateRange::default(),
+        amounts: Some(amounts),
+    }
+}
+
+
+/// Tests that exploding a three step ramp inserts concrete colors into the
+/// unoccupied indices and that the returned operations undo the insertions.
+#[test]
+fn explode_ramp_three_steps() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0x00, 0x00, 0x00])))
+        .unwrap();
+    let _ = basic.insert_color(1, Color::from(Rgb::from([0xFF, 0xFF, 0xFF])))
+        .unwrap();
+    let _ = basic.insert_color(3, Color::from(Rgb::from([0xFF, 0x00, 0x00])))
+        .unwrap();
+    let original = basic.clone();
+
+    let (indices, undo) = basic
+        .explode_ramp(&ramp(0, 1, vec![0.0, 0.5, 1.0]), 2)
+        .unwrap();
+    assert_eq!(indices, vec![2, 4, 5]);
+
+    let expected = [0.0, 0.5, 1.0];
+    for (idx, amount) in indices.iter().zip(expected.iter()) {
+        let color = match basic.cell(&CellRef::Index(*idx)).unwrap().expr() {
+            Expr::Color(color) => color.clone(),
+            expr => panic!("expected color expression, got {:?}", expr),
+        };
+        for channel in color.rgb_ratios().iter() {
+            assert!((channel - amount).abs() < 1e-2,
+                "cell {} channel {} != {}", idx, channel, amount);
+        }
+    }
+
+    basic.apply_operations(&undo, None).unwrap();
+    assert_eq!(basic, original);
+}
+
+/// Tests that a gradient is sampled into evenly spaced concrete colors which
+/// are unaffected by later changes to its endpoints, and that a gradient of
+/// fewer than two colors is rejected.
+#[test]
+fn gradient_bakes_colors() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0x00, 0x00, 0x00])))
+        .unwrap();
+    let _ = basic.insert_color(1, Color::from(Rgb::from([0xFF, 0xFF, 0xFF])))
+        .unwrap();
+
+    let insert_expr: InsertExpr = "gradient(:0, :1, 5)".parse().unwrap();
+    let exprs = insert_expr.exprs(&basic).unwrap();
+    assert_eq!(exprs.len(), 5);
+    for (i, expr) in exprs.iter().enumerate() {
+        let expected = i as f32 / 4.0;
+        let color = match expr {
+            Expr::Color(color) => color,
+            expr => panic!("expected color expression, got {:?}", expr),
+        };
+        for channel in color.rgb_ratios().iter() {
+            assert!((channel - expected).abs() < 1e-2,
+                "color {} channel {} != {}", i, channel, expected);
+        }
+    }
+
+    for (i, expr) in exprs.into_iter().enumerate() {
+        let _ = basic.insert_cell(2 + i as u32, Cell::new_with_expr(expr))
+            .unwrap();
+    }
+    let baked = basic.clone();
+    let _ = basic.set_expr(
+            CellRef::Index(1),
+            Expr::Color(Color::from(Rgb::from([0xFF, 0x00, 0x00]))))
+        .unwrap();
+    for idx in 2..7 {
+        let cell_ref = CellRef::Index(idx);
+        assert_eq!(
+            basic.cell(&cell_ref).unwrap().expr(),
+            baked.cell(&cell_ref).unwrap().expr());
+    }
+
+    assert!("gradient(:0, :1, 1)".parse::<InsertExpr>().is_err());
+}
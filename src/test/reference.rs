@@ -0,0 +1,168 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Reference resolution tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::Cell;
+use crate::cell::CellRef;
+use crate::cell::CellSelection;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::error::PaletteError;
+use crate::palette::BasicPalette;
+use crate::palette::ColorSpace;
+use crate::palette::Expr;
+use crate::palette::MixExpr;
+
+// Standard library imports.
+use std::collections::BTreeMap;
+
+
+/// Constructs a palette holding a color at index 0, followed by a chain of
+/// the given length in which each cell references the previous one.
+fn reference_chain(len: u32) -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0x10, 0x20, 0x30])))
+        .unwrap();
+    for idx in 1..=len {
+        let _ = basic.insert_reference(idx, CellRef::Index(idx - 1)).unwrap();
+    }
+    basic
+}
+
+
+/// Tests that a reference chain deeper than the limit returns an error.
+#[test]
+fn reference_chain_too_deep() {
+    let mut basic = reference_chain(20);
+    let _ = basic.set_max_reference_depth(8);
+
+    match basic.color(&CellRef::Index(20)) {
+        Err(PaletteError::ReferenceTooDeep { depth: 8 }) => (),
+        res => panic!("expected ReferenceTooDeep error, got {:?}", res),
+    }
+    // Chains within the limit still resolve.
+    assert!(basic.color(&CellRef::Index(7)).unwrap().is_some());
+}
+
+/// Tests that batch color resolution reports the same reference chains as too
+/// deep as resolving each color individually.
+#[test]
+fn reference_chain_too_deep_memoized() {
+    let selection: CellSelection<'static> = ":*".parse().unwrap();
+    let mut basic = reference_chain(20);
+    let _ = basic.set_max_reference_depth(8);
+
+    let colors = basic.colors_in_selection(&selection);
+    assert_eq!(colors.len(), 21);
+    for (idx, color) in colors {
+        // Use a fresh palette so that no colors are cached.
+        let mut fresh = reference_chain(20);
+        let _ = fresh.set_max_reference_depth(8);
+        match (color, fresh.color(&CellRef::Index(idx))) {
+            (Ok(a), Ok(b)) => assert_eq!(a, b),
+            (
+                Err(PaletteError::ReferenceTooDeep { depth: a }),
+                Err(PaletteError::ReferenceTooDeep { depth: b }),
+            ) => assert_eq!(a, b),
+            res => panic!("mismatched resolution of {}: {:?}", idx, res),
+        }
+        assert_eq!(idx < 8, fresh.color(&CellRef::Index(idx)).is_ok());
+    }
+
+    let mut fresh = reference_chain(20);
+    let _ = fresh.set_max_reference_depth(8);
+    let text_colors: Vec<_> = fresh.text_colors(&selection)
+        .into_iter()
+        .map(|(idx, _)| idx)
+        .collect();
+    assert_eq!(text_colors, (0..8).collect::<Vec<_>>());
+}
+
+/// Tests that touching every cell reports only the cell with an unresolvable
+/// reference.
+#[test]
+fn touch_all_reports_broken_reference() {
+    let mut basic = reference_chain(3);
+    let _ = basic.insert_reference(4, CellRef::Index(9)).unwrap();
+
+    let broken = basic.touch_all();
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].0, 4);
+    assert!(basic.touch(&CellRef::Index(3)).is_ok());
+    assert!(basic.touch(&CellRef::Index(4)).is_err());
+}
+
+/// Tests that overriding a base color changes a dependent cell's previewed
+/// color without modifying the palette, and that overrides participate in
+/// cycle detection.
+#[test]
+fn color_with_overrides_preview() {
+    let mut basic = reference_chain(2);
+    let original = basic.color(&CellRef::Index(2)).unwrap();
+    let revision = basic.revision();
+
+    let blue = Color::from(Rgb::from([0x00, 0x00, 0xFF]));
+    let mut overrides = BTreeMap::new();
+    let _ = overrides.insert(0, Expr::Color(blue.clone()));
+    assert_eq!(
+        basic.color_with_overrides(&CellRef::Index(2), &overrides).unwrap(),
+        Some(blue));
+    assert_eq!(basic.color(&CellRef::Index(2)).unwrap(), original);
+    assert_eq!(basic.revision(), revision);
+
+    let _ = overrides.insert(0, Expr::Reference(CellRef::Index(2)));
+    match basic.color_with_overrides(&CellRef::Index(2), &overrides) {
+        Err(PaletteError::UndefinedColor { circular: true, .. }) => (),
+        res => panic!("expected circular UndefinedColor, got {:?}", res),
+    }
+    assert_eq!(basic.color(&CellRef::Index(2)).unwrap(), original);
+}
+
+/// Tests resolving equally weighted and weighted mix expressions, an empty
+/// mix, and a mix referencing its own cell.
+#[test]
+fn mix_expr_resolution() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0x00, 0x00, 0x00])))
+        .unwrap();
+    let _ = basic.insert_color(1, Color::from(Rgb::from([0xFF, 0xFF, 0xFF])))
+        .unwrap();
+    let _ = basic.insert_color(2, Color::from(Rgb::from([0xFF, 0xFF, 0xFF])))
+        .unwrap();
+
+    let cases = [
+        (3, "mix(:0, :1, :2)", 2.0 / 3.0),
+        (4, "mix([:0, :1], [3.0, 1.0])", 0.25),
+    ];
+    for (idx, text, expected) in cases.iter() {
+        let _ = basic.insert_cell(*idx, Cell::new()).unwrap();
+        let _ = basic.set_expr_from_str(CellRef::Index(*idx), text).unwrap();
+        let color = basic.color(&CellRef::Index(*idx)).unwrap().unwrap();
+        for channel in color.rgb_ratios().iter() {
+            assert!((channel - expected).abs() < 1e-3,
+                "{}: channel {} != {}", text, channel, expected);
+        }
+    }
+
+    let _ = basic.insert_cell(5, Cell::new_with_expr(Expr::Mix(MixExpr {
+            refs: Vec::new(),
+            weights: None,
+            color_space: ColorSpace::Rgb,
+        })))
+        .unwrap();
+    assert_eq!(basic.color(&CellRef::Index(5)).unwrap(), None);
+
+    let _ = basic.insert_cell(6, Cell::new()).unwrap();
+    let _ = basic.set_expr_from_str(CellRef::Index(6), "mix(:0, :6)").unwrap();
+    match basic.color(&CellRef::Index(6)) {
+        Err(PaletteError::UndefinedColor { circular: true, .. }) => (),
+        res => panic!("expected circular UndefinedColor, got {:?}", res),
+    }
+}
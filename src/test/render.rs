@@ -0,0 +1,45 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Palette rendering tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellSelection;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+
+
+/// Tests that the first pixel of a rendered strip has the first cell's color.
+#[test]
+fn render_strip_first_pixel() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0xFF, 0x00, 0x00])))
+        .unwrap();
+    let _ = basic.insert_color(1, Color::from(Rgb::from([0x00, 0x00, 0xFF])))
+        .unwrap();
+    let selection: CellSelection<'static> = ":*".parse().unwrap();
+
+    let strip = basic.render_strip(&selection, 8, 4).unwrap();
+    assert_eq!(strip.dimensions(), (16, 4));
+    assert_eq!(*strip.get_pixel(0, 0), image::Rgb([0xFF, 0x00, 0x00]));
+    assert_eq!(*strip.get_pixel(8, 0), image::Rgb([0x00, 0x00, 0xFF]));
+}
+
+/// Tests that a strip too wide for an image is rejected.
+#[test]
+fn render_strip_width_overflow() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0xFF, 0x00, 0x00])))
+        .unwrap();
+    let _ = basic.insert_color(1, Color::from(Rgb::from([0x00, 0x00, 0xFF])))
+        .unwrap();
+    let selection: CellSelection<'static> = ":*".parse().unwrap();
+
+    assert!(basic.render_strip(&selection, u32::MAX, 1).is_err());
+}
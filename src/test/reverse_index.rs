@@ -0,0 +1,188 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Reverse name and group index tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellRef;
+use crate::cell::Position;
+use crate::cell::PositionSelector;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+
+// Standard library imports.
+use std::borrow::Cow;
+use std::io::Cursor;
+
+
+/// Returns the sorted names of the given index.
+fn names(basic: &BasicPalette, idx: u32) -> Vec<String> {
+    let mut names: Vec<_> = basic.names_of(idx)
+        .unwrap()
+        .into_iter()
+        .map(|name| name.into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Returns the sorted groups and group indices of the given index.
+fn groups(basic: &BasicPalette, idx: u32) -> Vec<(String, u32)> {
+    let mut groups: Vec<_> = basic.groups_of(idx)
+        .unwrap()
+        .into_iter()
+        .map(|(group, group_idx)| (group.into_owned(), group_idx))
+        .collect();
+    groups.sort();
+    groups
+}
+
+/// Returns a palette with three cells, two positioned and named, and two in
+/// a group.
+fn named_palette() -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    for idx in 0..3 {
+        let _ = basic.insert_color(idx, Color::from(Rgb::from([0, 0, 0])))
+            .unwrap();
+    }
+    let _ = basic.assign_position(
+            Position { page: 0, line: 0, column: 0 },
+            CellRef::Index(0))
+        .unwrap();
+    let _ = basic.assign_position(
+            Position { page: 0, line: 0, column: 1 },
+            CellRef::Index(1))
+        .unwrap();
+    let _ = basic.assign_name("row", PositionSelector::new(0, 0, None), false)
+        .unwrap();
+    let _ = basic.assign_name("one", PositionSelector::new(0, 0, 1), false)
+        .unwrap();
+    let _ = basic.assign_group(CellRef::Index(0), "g", None).unwrap();
+    let _ = basic.assign_group(CellRef::Index(2), "g", None).unwrap();
+    basic
+}
+
+/// Returns a copy of the given palette with freshly built reverse lookups.
+fn reloaded(basic: &BasicPalette) -> BasicPalette {
+    let mut buf = Vec::new();
+    basic.write_to_writer(&mut buf).expect("write palette");
+    BasicPalette::read_from_reader(&mut Cursor::new(buf))
+        .expect("read palette")
+}
+
+
+/// Tests the reverse lookups of a freshly constructed palette.
+#[test]
+fn reverse_index_lookup() {
+    let basic = named_palette();
+    assert_eq!(names(&basic, 0), vec!["row".to_owned()]);
+    assert_eq!(names(&basic, 1), vec!["one".to_owned(), "row".to_owned()]);
+    assert!(names(&basic, 2).is_empty());
+    assert_eq!(groups(&basic, 0), vec![("g".to_owned(), 0)]);
+    assert!(groups(&basic, 1).is_empty());
+    assert_eq!(groups(&basic, 2), vec![("g".to_owned(), 1)]);
+}
+
+/// Tests that the reverse lookups stay consistent through operations and
+/// their undo operations.
+#[test]
+fn reverse_index_consistent_through_undo() {
+    let mut basic = named_palette();
+    let original = basic.clone();
+    let mut undo = Vec::new();
+
+    undo.push(basic.move_cell(CellRef::Index(2), 5).unwrap());
+    assert!(groups(&basic, 2).is_empty());
+    assert_eq!(groups(&basic, 5), vec![("g".to_owned(), 1)]);
+
+    undo.push(basic.unassign_name(PositionSelector::new(0, 0, 1)).unwrap());
+    assert_eq!(names(&basic, 1), vec!["row".to_owned()]);
+
+    undo.push(basic.rename_group("g", "h").unwrap());
+    assert_eq!(groups(&basic, 0), vec![("h".to_owned(), 0)]);
+    assert_eq!(groups(&basic, 5), vec![("h".to_owned(), 1)]);
+
+    undo.push(basic.unassign_position(CellRef::Index(1)).unwrap());
+    assert!(names(&basic, 1).is_empty());
+
+    undo.push(basic.unassign_group(CellRef::Index(0), "h").unwrap());
+    assert!(groups(&basic, 0).is_empty());
+    assert_eq!(groups(&basic, 5), vec![("h".to_owned(), 0)]);
+
+    for ops in undo.iter().rev() {
+        basic.apply_operations(ops, None).unwrap();
+    }
+    assert_eq!(basic, original);
+    for idx in 0..6 {
+        assert_eq!(names(&basic, idx), names(&original, idx));
+        assert_eq!(groups(&basic, idx), groups(&original, idx));
+    }
+}
+
+/// Tests that the reverse lookups updated by name and group assignments match
+/// freshly built lookups.
+#[test]
+fn reverse_index_updated_in_place() {
+    let mut basic = named_palette();
+    // Build the reverse lookups before modifying the palette.
+    assert_eq!(names(&basic, 0), vec!["row".to_owned()]);
+
+    // Overwrite the name of an existing selector and the selector of an
+    // existing name.
+    let _ = basic.assign_name("first", PositionSelector::new(0, 0, 1), true)
+        .unwrap();
+    let _ = basic.assign_name("row", PositionSelector::new(0, 0, 0), true)
+        .unwrap();
+    // Insert into the middle of a group and add a second group.
+    let _ = basic.assign_group(CellRef::Index(1), "g", Some(1)).unwrap();
+    let _ = basic.assign_group(CellRef::Index(2), "h", None).unwrap();
+    let _ = basic.unassign_group(CellRef::Index(0), "g").unwrap();
+    let _ = basic.unassign_name(PositionSelector::new(0, 0, 0)).unwrap();
+    let _ = basic.assign_name("last", PositionSelector::new(0, 0, 1), false)
+        .unwrap();
+
+    let fresh = reloaded(&basic);
+    for idx in 0..3 {
+        assert_eq!(names(&basic, idx), names(&fresh, idx));
+        assert_eq!(groups(&basic, idx), groups(&fresh, idx));
+    }
+    assert!(names(&basic, 0).is_empty());
+    assert_eq!(names(&basic, 1), vec!["last".to_owned()]);
+    assert_eq!(groups(&basic, 1), vec![("g".to_owned(), 0)]);
+    assert_eq!(groups(&basic, 2), vec![
+        ("g".to_owned(), 1),
+        ("h".to_owned(), 0),
+    ]);
+}
+
+/// Tests that the aliases of a cell with two names, a position, and a group
+/// slot are all reported, whichever reference is used to find them.
+#[test]
+fn aliases_reported() {
+    let mut basic = named_palette();
+    let _ = basic.assign_group(CellRef::Index(1), "h", None).unwrap();
+
+    let aliases = basic.aliases(&CellRef::Name("one".into())).unwrap();
+    assert_eq!(aliases.index, 1);
+    let mut alias_names: Vec<_> = aliases.names
+        .iter()
+        .map(|name| name.as_ref())
+        .collect();
+    alias_names.sort();
+    assert_eq!(alias_names, vec!["one", "row"]);
+    assert_eq!(
+        aliases.position,
+        Some(Position { page: 0, line: 0, column: 1 }));
+    assert_eq!(aliases.groups, vec![(Cow::from("h"), 0)]);
+
+    let position = CellRef::Position(Position { page: 0, line: 0, column: 1 });
+    let group = CellRef::Group { group: "h".into(), idx: 0 };
+    assert_eq!(basic.aliases(&position).unwrap(), aliases);
+    assert_eq!(basic.aliases(&group).unwrap(), aliases);
+}
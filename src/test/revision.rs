@@ -0,0 +1,111 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Palette revision tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::Cell;
+use crate::cell::CellRef;
+use crate::cell::PositionSelector;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+use crate::palette::Expr;
+use crate::palette::History;
+use crate::palette::Operation;
+
+// Standard library imports.
+use std::io::Cursor;
+
+
+/// Returns an operation inserting a black cell at the given index.
+fn insert_black(idx: u32) -> Operation {
+    Operation::InsertCell {
+        idx,
+        cell: Cell::new_with_expr(
+            Expr::Color(Color::from(Rgb::from([0x00, 0x00, 0x00])))),
+    }
+}
+
+
+/// Tests that applying operations bumps the revision by the number of applied
+/// operations.
+#[test]
+fn revision_counts_applied_operations() {
+    let mut basic = BasicPalette::new();
+    assert_eq!(basic.revision(), 0);
+
+    basic.apply_operations(&[insert_black(0), insert_black(1)], None)
+        .unwrap();
+    assert_eq!(basic.revision(), 2);
+
+    // A failed operation does not bump the revision.
+    assert!(basic.apply_operation(&Operation::SetExpr {
+            cell_ref: CellRef::Index(5),
+            expr: Expr::Empty,
+        })
+        .is_err());
+    assert_eq!(basic.revision(), 2);
+}
+
+/// Tests that undo and redo bump the revision.
+#[test]
+fn revision_bumped_by_undo_redo() {
+    let mut basic = BasicPalette::new();
+    let mut history = History::new();
+    basic.apply_operations(
+            &[insert_black(0), insert_black(1)],
+            Some(&mut history))
+        .unwrap();
+    assert_eq!(basic.revision(), 2);
+
+    assert_eq!(basic.undo(&mut history, 1), 1);
+    assert_eq!(basic.revision(), 4);
+    assert_eq!(basic.redo(&mut history, 1), 1);
+    assert_eq!(basic.revision(), 6);
+}
+
+/// Tests that modifying the palette directly bumps the revision.
+#[test]
+fn revision_bumped_by_direct_modification() {
+    let mut basic = BasicPalette::new();
+    let mut revision = basic.revision();
+    let mut check_bumped = |basic: &BasicPalette| {
+        assert!(basic.revision() > revision);
+        revision = basic.revision();
+    };
+
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0x00, 0x00, 0x00])))
+        .unwrap();
+    check_bumped(&basic);
+    let _ = basic.set_expr(CellRef::Index(0), Expr::Empty).unwrap();
+    check_bumped(&basic);
+    let _ = basic.assign_name("zero", PositionSelector::new(0, 0, 0), false)
+        .unwrap();
+    check_bumped(&basic);
+    let _ = basic.add_tag(CellRef::Index(0), "tag").unwrap();
+    check_bumped(&basic);
+    let _ = basic.auto_assign_positions(4, 4).unwrap();
+    check_bumped(&basic);
+    let _ = basic.clear_all();
+    check_bumped(&basic);
+}
+
+/// Tests that the revision is preserved when the palette is written and read.
+#[test]
+fn revision_persists_across_save_load() {
+    let mut basic = BasicPalette::new();
+    basic.apply_operations(&[insert_black(0), insert_black(1)], None)
+        .unwrap();
+
+    let mut buf = Vec::new();
+    basic.write_to_writer(&mut buf).expect("write palette");
+    let read = BasicPalette::read_from_reader(&mut Cursor::new(buf))
+        .expect("read palette");
+    assert_eq!(read.revision(), basic.revision());
+}
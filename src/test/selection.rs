@@ -0,0 +1,161 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Cell selection tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellIndexSelection;
+use crate::cell::CellRef;
+use crate::cell::CellSelection;
+use crate::cell::Position;
+use crate::cell::PositionSelector;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+
+
+/// Constructs a palette with cells at the given indices.
+fn palette_with_cells(indices: &[u32]) -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    for idx in indices {
+        let _ = basic.insert_color(*idx, Color::from(Rgb::from([0, 0, 0])))
+            .unwrap();
+    }
+    basic
+}
+
+/// Returns a pair of overlapping index selections.
+fn overlapping_selections() -> (CellIndexSelection, CellIndexSelection) {
+    (
+        [0, 1, 2, 3, 10].iter().copied().collect(),
+        [2, 3, 4, 11].iter().copied().collect(),
+    )
+}
+
+
+/// Tests that `resolve_ordered` preserves the order of the selectors.
+#[test]
+fn resolve_ordered_preserves_selector_order() {
+    let basic = palette_with_cells(&[1, 3, 5]);
+    let selection: CellSelection<'static> = ":5, :1".parse().unwrap();
+
+    assert_eq!(selection.resolve_ordered(&basic), vec![5, 1]);
+    assert_eq!(selection.resolve(&basic).iter().collect::<Vec<_>>(),
+        vec![1, 5]);
+}
+
+
+/// Tests that a subtractive group selector removes the group's members from
+/// an all selector, regardless of selector order.
+#[test]
+fn resolve_subtract_group_from_all() {
+    let mut basic = palette_with_cells(&[0, 1, 2, 3, 4]);
+    let _ = basic.assign_group(CellRef::Index(1), "warm", None).unwrap();
+    let _ = basic.assign_group(CellRef::Index(3), "warm", None).unwrap();
+
+    for text in [":*, -'warm':*", "-'warm':*, :*"].iter() {
+        let selection: CellSelection<'static> = text.parse().unwrap();
+        assert_eq!(selection.resolve(&basic).iter().collect::<Vec<_>>(),
+            vec![0, 2, 4]);
+        assert_eq!(selection.resolve_ordered(&basic), vec![0, 2, 4]);
+    }
+}
+
+/// Tests that nested subtractive selectors are rejected.
+#[test]
+fn parse_nested_subtract_fails() {
+    assert!("--'warm':*".parse::<CellSelection<'static>>().is_err());
+}
+
+/// Tests that a `-` following an index is parsed as a range separator
+/// regardless of whitespace, and as a subtractive prefix only after a list
+/// separator.
+#[test]
+fn parse_range_and_subtract_tokens() {
+    let basic = palette_with_cells(&[0, 1, 2, 3, 4]);
+
+    for text in [":0-:3", ":0 -:3", ":0 - :3"].iter() {
+        let selection: CellSelection<'static> = text.parse().unwrap();
+        assert_eq!(selection.resolve(&basic).iter().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]);
+    }
+
+    let selection: CellSelection<'static> = ":0-:3, -:2".parse().unwrap();
+    assert_eq!(selection.resolve(&basic).iter().collect::<Vec<_>>(),
+        vec![0, 1, 3]);
+}
+
+/// Tests resolving the `*named` and `*grouped` selectors.
+#[test]
+fn resolve_named_and_grouped() {
+    let mut basic = palette_with_cells(&[0, 1, 2, 3, 4]);
+    let _ = basic.assign_position(
+            Position { page: 0, line: 0, column: 1 },
+            CellRef::Index(1))
+        .unwrap();
+    let _ = basic.assign_position(
+            Position { page: 0, line: 0, column: 2 },
+            CellRef::Index(2))
+        .unwrap();
+    let _ = basic.assign_name("one", PositionSelector::new(0, 0, 1), false)
+        .unwrap();
+    let _ = basic.assign_group(CellRef::Index(4), "warm", None).unwrap();
+    let _ = basic.assign_group(CellRef::Index(3), "warm", None).unwrap();
+    let _ = basic.assign_group(CellRef::Index(4), "cool", None).unwrap();
+
+    let cases = [
+        ("*named", vec![1]),
+        ("*grouped", vec![3, 4]),
+        ("*grouped, *named", vec![1, 3, 4]),
+        (":*, -*grouped", vec![0, 1, 2]),
+    ];
+    for (text, expected) in cases.iter() {
+        let selection: CellSelection<'static> = text.parse().unwrap();
+        assert_eq!(
+            selection.resolve(&basic).iter().collect::<Vec<_>>(),
+            *expected,
+            "resolving {}", text);
+    }
+}
+
+/// Tests the union of two overlapping index selections.
+#[test]
+fn index_selection_union() {
+    let (a, b) = overlapping_selections();
+    assert_eq!(a.union(&b).iter().collect::<Vec<_>>(),
+        vec![0, 1, 2, 3, 4, 10, 11]);
+    assert_eq!(b.union(&a).iter().collect::<Vec<_>>(),
+        a.union(&b).iter().collect::<Vec<_>>());
+}
+
+/// Tests the intersection of two overlapping index selections.
+#[test]
+fn index_selection_intersection() {
+    let (a, b) = overlapping_selections();
+    assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), vec![2, 3]);
+    assert_eq!(b.intersection(&a).iter().collect::<Vec<_>>(),
+        a.intersection(&b).iter().collect::<Vec<_>>());
+}
+
+/// Tests the difference of two overlapping index selections.
+#[test]
+fn index_selection_difference() {
+    let (a, b) = overlapping_selections();
+    assert_eq!(a.difference(&b).iter().collect::<Vec<_>>(), vec![0, 1, 10]);
+    assert_eq!(b.difference(&a).iter().collect::<Vec<_>>(), vec![4, 11]);
+}
+
+/// Tests the symmetric difference of two overlapping index selections.
+#[test]
+fn index_selection_symmetric_difference() {
+    let (a, b) = overlapping_selections();
+    assert_eq!(a.symmetric_difference(&b).iter().collect::<Vec<_>>(),
+        vec![0, 1, 4, 10, 11]);
+    assert_eq!(b.symmetric_difference(&a).iter().collect::<Vec<_>>(),
+        a.symmetric_difference(&b).iter().collect::<Vec<_>>());
+}
@@ -0,0 +1,409 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Palette serialization tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::Cell;
+use crate::cell::CellRef;
+use crate::cell::Position;
+use crate::cell::PositionSelector;
+use crate::color::Color;
+use crate::color::ColorExt as _;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+use crate::palette::ColorSpace;
+use crate::palette::Expr;
+use crate::palette::Interpolate;
+use crate::palette::InterpolateFunction;
+use crate::palette::PaletteWriteConfig;
+
+// External library imports.
+use ron::de::from_str;
+use ron::ser::to_string;
+
+// Standard library imports.
+use std::fs::OpenOptions;
+use std::io::Cursor;
+use std::io::Read as _;
+use std::io::Seek as _;
+use std::io::SeekFrom;
+
+
+/// A palette written in format version 0, with cells stored as bare `Expr`s.
+const V0_PALETTE: &str = "(
+    cells: {
+        0: Empty,
+        1: Reference(Index(0)),
+    },
+    names: {},
+    positions: {
+        (0, 0, 0): 0,
+    },
+    groups: {},
+    next_index: 2,
+    position_cursor: (0, 0, 1),
+)";
+
+
+/// Writes the given palette into a buffer and reads it back.
+fn round_trip(basic: &BasicPalette) -> BasicPalette {
+    let mut buf = Vec::new();
+    basic.write_to_writer(&mut buf).expect("write palette");
+    BasicPalette::read_from_reader(&mut Cursor::new(buf))
+        .expect("read palette")
+}
+
+/// Writes the given palette to a temporary file using the given config and
+/// returns the written bytes.
+fn write_with(basic: &BasicPalette, config: PaletteWriteConfig, name: &str)
+    -> Vec<u8>
+{
+    let path = std::env::temp_dir()
+        .join(format!("atma-test-{}-{}.atma", std::process::id(), name));
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .expect("open temporary file");
+    basic.write_to_file_with(&mut file, config).expect("write palette");
+
+    let mut buf = Vec::new();
+    let _ = file.seek(SeekFrom::Start(0)).expect("seek temporary file");
+    let _ = file.read_to_end(&mut buf).expect("read temporary file");
+    std::fs::remove_file(&path).expect("remove temporary file");
+    buf
+}
+
+
+/// Tests reading and rewriting a version 0 palette.
+#[test]
+fn basic_palette_v0_round_trip() {
+    let basic = BasicPalette::read_from_reader(
+            &mut Cursor::new(V0_PALETTE.as_bytes()))
+        .expect("read v0 palette");
+
+    assert_eq!(
+        basic.cell(&CellRef::Index(0)).unwrap().expr(),
+        &Expr::Empty);
+    assert_eq!(
+        basic.cell(&CellRef::Index(1)).unwrap().expr(),
+        &Expr::Reference(CellRef::Index(0)));
+    assert_eq!(
+        basic.assigned_position(&CellRef::Index(0)),
+        Some(&Position::ZERO));
+    assert_eq!(
+        basic.position_cursor(),
+        Position { page: 0, line: 0, column: 1 });
+
+    assert_eq!(round_trip(&basic), basic);
+}
+
+/// Tests writing a palette into a `Vec<u8>` cursor and reading it back from
+/// the same cursor.
+#[test]
+fn basic_palette_vec_cursor_round_trip() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0x10, 0x20, 0x30])))
+        .unwrap();
+    let _ = basic.insert_reference(1, CellRef::Index(0)).unwrap();
+    let _ = basic.assign_position(Position::ZERO, CellRef::Index(1)).unwrap();
+    let _ = basic.assign_name("ref", PositionSelector::new(0, 0, 0), false)
+        .unwrap();
+
+    let mut cursor: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+    basic.write_to_writer(&mut cursor).expect("write palette");
+    assert!(!cursor.get_ref().is_empty());
+
+    let _ = cursor.seek(SeekFrom::Start(0)).expect("seek cursor");
+    let read = BasicPalette::read_from_reader(&mut cursor)
+        .expect("read palette");
+    assert_eq!(read, basic);
+    assert_eq!(
+        read.resolve_ref_to_index(&CellRef::Name("ref".into())).unwrap(),
+        1);
+}
+
+/// Tests writing and reading a palette with annotated cells.
+#[test]
+fn basic_palette_annotated_round_trip() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_cell(0, Cell::new_with_expr(Expr::Empty)
+            .with_description("background")
+            .with_tags(vec!["dark", "base"])
+            .with_locked(true))
+        .unwrap();
+    let _ = basic.insert_cell(1, Cell::new_with_expr(
+            Expr::Reference(CellRef::Index(0))))
+        .unwrap();
+
+    let read = round_trip(&basic);
+    assert_eq!(read, basic);
+
+    let cell = read.cell(&CellRef::Index(0)).unwrap();
+    assert_eq!(&cell.description()[..], "background");
+    assert!(cell.has_tag("dark"));
+    assert!(cell.has_tag("base"));
+    assert!(cell.is_locked());
+}
+
+/// Tests that compact output is a single line which is shorter than the
+/// default pretty output, and that both read back identically.
+#[test]
+fn basic_palette_compact_shorter_than_pretty() {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_cell(0, Cell::new_with_expr(Expr::Empty)
+            .with_description("background"))
+        .unwrap();
+    let _ = basic.insert_cell(1, Cell::new_with_expr(
+            Expr::Reference(CellRef::Index(0))))
+        .unwrap();
+
+    let compact = write_with(&basic, PaletteWriteConfig::compact(), "compact");
+    let pretty = write_with(&basic, PaletteWriteConfig::default(), "pretty");
+    assert!(compact.len() < pretty.len(),
+        "compact output ({} bytes) is not shorter than pretty output ({} \
+        bytes)", compact.len(), pretty.len());
+    assert!(!compact.contains(&b'\n'));
+
+    let read_compact = BasicPalette::read_from_reader(&mut Cursor::new(compact))
+        .expect("read compact palette");
+    let read_pretty = BasicPalette::read_from_reader(&mut Cursor::new(pretty))
+        .expect("read pretty palette");
+    assert_eq!(read_compact, basic);
+    assert_eq!(read_pretty, basic);
+}
+
+
+/// Tests that a default `Interpolate` serializes without any fields and
+/// reloads identically.
+#[test]
+fn interpolate_default_round_trip() {
+    let interpolate = Interpolate::default();
+    let text = to_string(&interpolate).unwrap();
+    assert_eq!(text, "()");
+    assert_eq!(from_str::<Interpolate>(&text).unwrap(), interpolate);
+}
+
+/// Tests that a non-default `Interpolate` serializes only its non-default
+/// fields and reloads identically.
+#[test]
+fn interpolate_partial_round_trip() {
+    let interpolate = Interpolate {
+        color_space: ColorSpace::Hsl,
+        amount: 0.5,
+        .. Interpolate::default()
+    };
+    let text = to_string(&interpolate).unwrap();
+    assert!(!text.contains("interpolate_fn"));
+    assert!(!text.contains("linear_light"));
+    assert_eq!(from_str::<Interpolate>(&text).unwrap(), interpolate);
+
+    let interpolate = Interpolate {
+        interpolate_fn: InterpolateFunction::Cubic(0.25, 0.75),
+        linear_light: true,
+        .. Interpolate::default()
+    };
+    let text = to_string(&interpolate).unwrap();
+    assert_eq!(from_str::<Interpolate>(&text).unwrap(), interpolate);
+}
+
+/// Tests that reading a saved palette and saving it again over the same file
+/// produces byte-identical output.
+#[test]
+fn basic_palette_resave_byte_identical() {
+    let path = std::env::temp_dir()
+        .join(format!("atma-test-{}-resave.atma", std::process::id()));
+
+    let mut basic = BasicPalette::new();
+    for idx in (0..4).rev() {
+        let _ = basic.insert_color(idx, Color::from(Rgb::from([
+                0x40 * idx as u8,
+                0x10,
+                0xFF - 0x40 * idx as u8,
+            ])))
+            .unwrap();
+        let _ = basic.assign_position(
+                Position { page: 0, line: 0, column: idx as u16 },
+                CellRef::Index(idx))
+            .unwrap();
+        let _ = basic.assign_group(CellRef::Index(idx), "ramp", Some(0))
+            .unwrap();
+    }
+    let _ = basic.assign_name("b", PositionSelector::new(0, 0, 1), false)
+        .unwrap();
+    let _ = basic.assign_name("a", PositionSelector::new(0, 0, 0), false)
+        .unwrap();
+    basic.write_to_path(&path).expect("write palette");
+    let saved = std::fs::read(&path).expect("read saved palette");
+
+    let read = BasicPalette::read_from_path(&path).expect("read palette");
+    read.write_to_path(&path).expect("rewrite palette");
+    let resaved = std::fs::read(&path).expect("read resaved palette");
+    std::fs::remove_file(&path).expect("remove temporary file");
+
+    assert_eq!(resaved, saved);
+}
+
+/// Tests that a palette written as a GIMP palette reads back with the same
+/// colors and names, and with positions wrapped by the `Columns` value.
+#[test]
+fn basic_palette_gpl_round_trip() {
+    let path = std::env::temp_dir()
+        .join(format!("atma-test-{}-round-trip.gpl", std::process::id()));
+
+    let mut basic = BasicPalette::new();
+    for idx in 0..3 {
+        let _ = basic.insert_color(idx, Color::from(Rgb::from([
+                0x40 * idx as u8,
+                0x10,
+                0xFF - 0x40 * idx as u8,
+            ])))
+            .unwrap();
+        let _ = basic.assign_position(
+                Position { page: 0, line: 0, column: idx as u16 },
+                CellRef::Index(idx))
+            .unwrap();
+    }
+    let _ = basic.assign_name("first", PositionSelector::new(0, 0, 0), false)
+        .unwrap();
+    let _ = basic.assign_name("last", PositionSelector::new(0, 0, 2), false)
+        .unwrap();
+    basic.write_gpl_to_path(&path, Some("test"), Some(2))
+        .expect("write GIMP palette");
+    let saved = std::fs::read(&path).expect("read saved GIMP palette");
+
+    let read = BasicPalette::read_gpl_from_path(&path)
+        .expect("read GIMP palette");
+    read.write_gpl_to_path(&path, Some("test"), Some(2))
+        .expect("rewrite GIMP palette");
+    let resaved = std::fs::read(&path).expect("read resaved GIMP palette");
+    std::fs::remove_file(&path).expect("remove temporary file");
+
+    for idx in 0..3 {
+        let cell_ref = CellRef::Index(idx);
+        assert_eq!(
+            read.color(&cell_ref).unwrap(),
+            basic.color(&cell_ref).unwrap());
+    }
+    assert_eq!(
+        read.assigned_name(&CellRef::Index(0)).map(|n| n.as_ref()),
+        Some("first"));
+    assert_eq!(read.assigned_name(&CellRef::Index(1)), None);
+    assert_eq!(
+        read.assigned_name(&CellRef::Index(2)).map(|n| n.as_ref()),
+        Some("last"));
+    assert_eq!(
+        read.assigned_position(&CellRef::Index(2)),
+        Some(&Position { page: 0, line: 1, column: 0 }));
+    assert_eq!(resaved, saved);
+}
+
+/// Tests that a palette with names, positions, and groups is written as valid
+/// JSON, and reads back unchanged.
+#[cfg(feature = "json")]
+#[test]
+fn basic_palette_json_round_trip() {
+    let path = std::env::temp_dir()
+        .join(format!("atma-test-{}-round-trip.json", std::process::id()));
+
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0x10, 0x20, 0x30])))
+        .unwrap();
+    let _ = basic.insert_reference(1, CellRef::Index(0)).unwrap();
+    let _ = basic.assign_position(Position::ZERO, CellRef::Index(0)).unwrap();
+    let _ = basic.assign_position(
+            Position { page: 1, line: 2, column: 3 },
+            CellRef::Index(1))
+        .unwrap();
+    let _ = basic.assign_name("first", PositionSelector::new(0, 0, 0), false)
+        .unwrap();
+    let _ = basic
+        .assign_name("page", PositionSelector::new(1, None, None), false)
+        .unwrap();
+    let _ = basic.assign_group(CellRef::Index(1), "g", None).unwrap();
+    basic.write_json_to_path(&path).expect("write JSON palette");
+
+    let text = std::fs::read_to_string(&path).expect("read saved JSON");
+    let read = BasicPalette::read_json_from_path(&path)
+        .expect("read JSON palette");
+    std::fs::remove_file(&path).expect("remove temporary file");
+
+    let value: serde_json::Value = serde_json::from_str(&text)
+        .expect("parse JSON");
+    assert!(value.is_object());
+    assert_eq!(read, basic);
+}
+
+/// Tests that nested groups and non-ASCII names survive a round-trip through
+/// the ASE format.
+#[test]
+fn basic_palette_ase_round_trip() {
+    let path = std::env::temp_dir()
+        .join(format!("atma-test-{}-round-trip.ase", std::process::id()));
+
+    let mut basic = BasicPalette::new();
+    let cells = [
+        (0xFF0000, "Grün", None),
+        (0x00FF00, "赤", Some("warm")),
+        (0x0000FF, "", Some("warm/deep")),
+        (0x808080, "", Some("cool")),
+    ];
+    for (idx, (color, name, group)) in cells.iter().enumerate() {
+        let idx = idx as u32;
+        let position = Position { page: 0, line: 0, column: idx as u16 };
+        let _ = basic.insert_color(idx, Color::from_rgb_u32(*color)).unwrap();
+        let _ = basic.assign_position(position, CellRef::Index(idx)).unwrap();
+        if !name.is_empty() {
+            let _ = basic.assign_name(*name, position.into(), false).unwrap();
+        }
+        if let Some(group) = group {
+            let _ = basic.assign_group(CellRef::Index(idx), *group, None)
+                .unwrap();
+        }
+    }
+    basic.write_ase_to_path(&path).expect("write ASE palette");
+    let saved = std::fs::read(&path).expect("read saved ASE palette");
+
+    let read = BasicPalette::read_ase_from_path(&path)
+        .expect("read ASE palette");
+    read.write_ase_to_path(&path).expect("rewrite ASE palette");
+    let resaved = std::fs::read(&path).expect("read resaved ASE palette");
+    std::fs::remove_file(&path).expect("remove temporary file");
+
+    // Ungrouped cells are written first, followed by each group in order.
+    let expected = [
+        (0xFF0000, None),
+        (0x808080, Some("cool")),
+        (0x00FF00, Some("warm")),
+        (0x0000FF, Some("warm/deep")),
+    ];
+    for (idx, (color, group)) in expected.iter().enumerate() {
+        let cell_ref = CellRef::Index(idx as u32);
+        assert_eq!(
+            read.color(&cell_ref).unwrap().map(|c| c.to_rgb_u32()),
+            Some(*color));
+        if let Some(group) = group {
+            assert_eq!(
+                read.resolve_ref_to_index(&CellRef::Group {
+                    group: (*group).into(),
+                    idx: 0,
+                }).unwrap(),
+                idx as u32);
+        }
+    }
+    assert_eq!(
+        read.resolve_ref_to_index(&CellRef::Name("Grün".into())).unwrap(),
+        0);
+    assert_eq!(
+        read.resolve_ref_to_index(&CellRef::Name("赤".into())).unwrap(),
+        2);
+    assert_eq!(resaved, saved);
+}
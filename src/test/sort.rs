@@ -0,0 +1,85 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Palette sorting tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellRef;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+use crate::palette::Expr;
+use crate::palette::SortKey;
+
+
+/// Returns a palette containing colors with distinct hues and luminances, and
+/// reference cells, including a chain of references.
+fn palette_with_references() -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    let _ = basic.insert_color(0, Color::from(Rgb::from([0x00, 0xFF, 0x00])))
+        .unwrap();
+    let _ = basic.insert_reference(1, CellRef::Index(3)).unwrap();
+    let _ = basic.insert_color(2, Color::from(Rgb::from([0xFF, 0x00, 0x00])))
+        .unwrap();
+    let _ = basic.insert_color(3, Color::from(Rgb::from([0x00, 0x00, 0xFF])))
+        .unwrap();
+    let _ = basic.insert_reference(4, CellRef::Index(1)).unwrap();
+    basic
+}
+
+/// Reindexes the palette using the given key and checks that each cell's
+/// color has moved to its sorted index, and that no references were broken.
+fn check_reindex_sorted(basic: &mut BasicPalette, key: SortKey) {
+    let expected: Vec<_> = basic.sorted_indices(key)
+        .into_iter()
+        .map(|idx| basic.color(&CellRef::Index(idx)).unwrap())
+        .collect();
+    let _ = basic.reindex_sorted(key).unwrap();
+
+    let actual: Vec<_> = basic.occupied_indices()
+        .map(|idx| basic.color(&CellRef::Index(idx)).unwrap())
+        .collect();
+    assert_eq!(actual, expected);
+}
+
+
+/// Tests that sorting by luminance and then by hue moves each cell's color to
+/// its sorted index and updates index references to follow the moved cells.
+#[test]
+fn reindex_sorted_updates_references() {
+    let mut basic = palette_with_references();
+
+    check_reindex_sorted(&mut basic, SortKey::Luminance);
+    // The references to the blue cell are moved ahead of it, and are updated.
+    assert_eq!(
+        basic.cell(&CellRef::Index(0)).unwrap().expr(),
+        &Expr::Reference(CellRef::Index(1)));
+    assert_eq!(
+        basic.cell(&CellRef::Index(2)).unwrap().expr(),
+        &Expr::Reference(CellRef::Index(0)));
+
+    check_reindex_sorted(&mut basic, SortKey::Hue);
+    assert_eq!(
+        basic.cell(&CellRef::Index(2)).unwrap().expr(),
+        &Expr::Reference(CellRef::Index(3)));
+    assert_eq!(
+        basic.cell(&CellRef::Index(4)).unwrap().expr(),
+        &Expr::Reference(CellRef::Index(2)));
+}
+
+/// Tests that the operations returned by `reindex_sorted` restore the
+/// original palette.
+#[test]
+fn reindex_sorted_undo() {
+    let mut basic = palette_with_references();
+    let original = basic.clone();
+
+    let undo = basic.reindex_sorted(SortKey::Luminance).unwrap();
+    basic.apply_operations(&undo, None).unwrap();
+    assert_eq!(basic, original);
+}
@@ -0,0 +1,93 @@
+////////////////////////////////////////////////////////////////////////////////
+// Atma structured color palette
+////////////////////////////////////////////////////////////////////////////////
+// Copyright 2020 Skylor R. Schermer
+// This code is dual licenced using the MIT or Apache 2 license.
+// See licence-mit.md and licence-apache.md for details.
+////////////////////////////////////////////////////////////////////////////////
+//! Cell tag tests.
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use crate::cell::CellRef;
+use crate::cell::CellSelection;
+use crate::cell::CellSelector;
+use crate::color::Color;
+use crate::color::Rgb;
+use crate::palette::BasicPalette;
+
+// Standard library imports.
+use std::io::Cursor;
+
+
+/// Returns a palette with three cells, the first and last tagged "warm".
+fn tagged_palette() -> BasicPalette {
+    let mut basic = BasicPalette::new();
+    for idx in 0..3 {
+        let _ = basic.insert_color(idx, Color::from(Rgb::from([0, 0, 0])))
+            .unwrap();
+    }
+    let _ = basic.add_tag(CellRef::Index(0), "warm").unwrap();
+    let _ = basic.add_tag(CellRef::Index(2), "warm").unwrap();
+    basic
+}
+
+
+/// Tests that adding and removing tags can be undone.
+#[test]
+fn tag_add_remove_undo() {
+    let mut basic = tagged_palette();
+    let original = basic.clone();
+
+    let undo = basic.add_tag(CellRef::Index(1), "cool").unwrap();
+    assert!(basic.cell(&CellRef::Index(1)).unwrap().has_tag("cool"));
+    basic.apply_operations(&undo, None).unwrap();
+    assert_eq!(basic, original);
+
+    let undo = basic.remove_tag(CellRef::Index(0), "warm").unwrap();
+    assert!(!basic.cell(&CellRef::Index(0)).unwrap().has_tag("warm"));
+    basic.apply_operations(&undo, None).unwrap();
+    assert_eq!(basic, original);
+
+    // Adding an existing tag or removing a missing tag does nothing.
+    assert!(basic.add_tag(CellRef::Index(0), "warm").unwrap().is_empty());
+    assert!(basic.remove_tag(CellRef::Index(1), "warm").unwrap().is_empty());
+    assert_eq!(basic, original);
+}
+
+/// Tests parsing and resolving a tag selector.
+#[test]
+fn tag_selector_resolve() {
+    let mut basic = tagged_palette();
+    let _ = basic.add_tag(CellRef::Index(1), "cafe").unwrap();
+
+    let selection: CellSelection<'static> = "#warm".parse().unwrap();
+    assert_eq!(selection.resolve(&basic).iter().collect::<Vec<_>>(),
+        vec![0, 2]);
+
+    // Tags beginning with hex digits are scanned differently.
+    let selection: CellSelection<'static> = "#cafe".parse().unwrap();
+    assert_eq!(selection.resolve(&basic).iter().collect::<Vec<_>>(),
+        vec![1]);
+
+    let selection: CellSelection<'static> = "#missing".parse().unwrap();
+    assert!(selection.resolve(&basic).iter().next().is_none());
+
+    assert_eq!(CellSelector::Tag("warm".into()).to_string(), "#warm");
+}
+
+/// Tests that tags are written and read back, including on cells without a
+/// description.
+#[test]
+fn tag_serialize_round_trip() {
+    let basic = tagged_palette();
+    let mut buf = Vec::new();
+    basic.write_to_writer(&mut buf).expect("write palette");
+    let read = BasicPalette::read_from_reader(&mut Cursor::new(buf))
+        .expect("read palette");
+
+    assert_eq!(read, basic);
+    assert!(read.cell(&CellRef::Index(0)).unwrap().has_tag("warm"));
+    assert!(read.cell(&CellRef::Index(1)).unwrap().tags().is_empty());
+    assert!(read.cell(&CellRef::Index(2)).unwrap().has_tag("warm"));
+}